@@ -35,7 +35,9 @@ pub fn derive_new_ast(input: TokenStream) -> TokenStream {
 fn create_new_fn(item_struct: &syn::ItemStruct) -> TokenStream {
     let struct_name = &item_struct.ident.clone();
 
+    // the constructor takes one argument per field, however many fields there are
     let mut new_fn: syn::ItemFn = syn::parse_quote! {
+        #[allow(clippy::too_many_arguments)]
         pub fn new() -> Self {
 
         }
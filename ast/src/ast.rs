@@ -1,27 +1,483 @@
+use crate::builtin::Builtin;
 use crate::operator::Operator;
 use crate::symbol::*;
 use crate::token::*;
+use std::cell::RefCell;
 use std::fmt;
+use std::rc::Rc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 extern crate ast_proc_macros;
 use ast_proc_macros::*;
 
 /// The type of Pine construct.
-#[derive(Clone, Debug, PartialEq)] // TODO move to different module
+#[derive(Clone, Debug)] // TODO move to different module
 pub enum PineType {
     Integer,
+    /// An unsigned integer type.
+    UInteger,
+    /// A byte, which is an unsigned integer from 0 to 255.
+    Byte,
+    /// An ascii character, which is held in a byte.
+    Char,
     Float,
     Bool,
     String,
     List(Box<PineType>),
+    /// A map type, such as `Map<string, int>`, with its key and value types.
+    Map(Box<PineType>, Box<PineType>),
+    /// A set type, such as `Set<int>`.
+    Set(Box<PineType>),
+    /// An optional type, such as `int?`, whose values are either `none` or a value of the inner type.
+    Optional(Box<PineType>),
+    /// A tuple type, such as `(int, string)`.
+    Tuple(Vec<PineType>),
+    /// An object type, along with the type arguments of a generic object type.
+    Object(ObjRef, Vec<PineType>),
+    Interface(IfaceRef),
+    /// An enum type, along with the type arguments of a generic enum type.
+    Enum(EnumRef, Vec<PineType>),
+    /// A type referred to by name along with its type arguments,
+    /// which is resolved to an object type, enum type, interface or type parameter during scoping.
+    Named(Vec<String>, Vec<PineType>),
     Function {
         params: Vec<PineType>,
         ret: Box<PineType>,
     },
     Void,
+    /// A type that has not been inferred yet, which is bound to a type during type inference.
+    Var(TypeVarRef),
+    /// A type parameter of a generic type, along with its name.
+    /// It is replaced by a fresh type variable at every use of the generic type.
+    Generic(usize, String),
     Unknown,
 }
 
+impl fmt::Display for PineType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PineType::Integer => write!(f, "int"),
+            PineType::UInteger => write!(f, "uint"),
+            PineType::Byte => write!(f, "byte"),
+            PineType::Char => write!(f, "char"),
+            PineType::Float => write!(f, "float"),
+            PineType::Bool => write!(f, "bool"),
+            PineType::String => write!(f, "string"),
+            PineType::List(t) => write!(f, "[{}]", t),
+            PineType::Map(k, v) => write!(f, "Map<{}, {}>", k, v),
+            PineType::Set(t) => write!(f, "Set<{}>", t),
+            PineType::Optional(t) => write!(f, "{}?", t),
+            PineType::Tuple(ts) => {
+                let ts: Vec<String> = ts.iter().map(|t| t.to_string()).collect();
+                write!(f, "({})", ts.join(", "))
+            }
+            PineType::Object(o, args) if args.is_empty() => write!(f, "{}", o.borrow().name),
+            PineType::Object(o, args) => {
+                let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+                write!(f, "{}<{}>", o.borrow().name, args.join(", "))
+            }
+            PineType::Interface(i) => write!(f, "{}", i.borrow().name),
+            PineType::Enum(e, args) if args.is_empty() => write!(f, "{}", e.borrow().name),
+            PineType::Enum(e, args) => {
+                let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+                write!(f, "{}<{}>", e.borrow().name, args.join(", "))
+            }
+            PineType::Named(path, _) => write!(f, "{}", path.join("::")),
+            PineType::Function { params, ret } => {
+                let params: Vec<String> = params.iter().map(|p| p.to_string()).collect();
+                write!(f, "fun({}) -> {}", params.join(", "), ret)
+            }
+            PineType::Void => write!(f, "void"),
+            PineType::Var(v) => match &*v.borrow() {
+                TypeVar::Bound(t) => write!(f, "{}", t),
+                TypeVar::Unbound { .. } => write!(f, "_"),
+            },
+            PineType::Generic(_, name) => write!(f, "{}", name),
+            PineType::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Types are compared after following the type variables that have been bound.
+impl PartialEq for PineType {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.resolve(), other.resolve()) {
+            (PineType::Integer, PineType::Integer)
+            | (PineType::UInteger, PineType::UInteger)
+            | (PineType::Byte, PineType::Byte)
+            | (PineType::Char, PineType::Char)
+            | (PineType::Float, PineType::Float)
+            | (PineType::Bool, PineType::Bool)
+            | (PineType::String, PineType::String)
+            | (PineType::Void, PineType::Void)
+            | (PineType::Unknown, PineType::Unknown) => true,
+            (PineType::List(a), PineType::List(b)) => a == b,
+            (PineType::Map(ak, av), PineType::Map(bk, bv)) => ak == bk && av == bv,
+            (PineType::Set(a), PineType::Set(b)) => a == b,
+            (PineType::Optional(a), PineType::Optional(b)) => a == b,
+            (PineType::Tuple(a), PineType::Tuple(b)) => a == b,
+            (PineType::Object(a, aa), PineType::Object(b, ba)) => a == b && aa == ba,
+            (PineType::Interface(a), PineType::Interface(b)) => a == b,
+            (PineType::Enum(a, aa), PineType::Enum(b, ba)) => a == b && aa == ba,
+            (PineType::Named(a, aa), PineType::Named(b, ba)) => a == b && aa == ba,
+            (PineType::Function { params: ap, ret: ar }, PineType::Function { params: bp, ret: br }) => ap == bp && ar == br,
+            (PineType::Var(a), PineType::Var(b)) => Rc::ptr_eq(&a, &b),
+            (PineType::Generic(a, _), PineType::Generic(b, _)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl PineType {
+    /// Creates a fresh type variable at a let level.
+    pub fn var(level: usize) -> Self {
+        let id = next_type_id();
+        PineType::Var(Rc::new(RefCell::new(TypeVar::Unbound { id, level })))
+    }
+
+    /// Creates a fresh type parameter.
+    pub fn generic(name: String) -> Self {
+        PineType::Generic(next_type_id(), name)
+    }
+
+    /// Follows the bound type variables at the top of the type.
+    pub fn resolve(&self) -> PineType {
+        match self {
+            PineType::Var(v) => match &*v.borrow() {
+                TypeVar::Bound(t) => t.resolve(),
+                TypeVar::Unbound { .. } => self.clone(),
+            },
+            t => t.clone(),
+        }
+    }
+
+    /// Follows all bound type variables within the type.
+    pub fn resolve_all(&self) -> PineType {
+        self.map(&|t| match t {
+            PineType::Var(_) => Some(t.clone()),
+            _ => None,
+        })
+    }
+
+    /// Replaces the type parameters within the type with the types they are bound to.
+    pub fn substitute(&self, subst: &HashMap<usize, PineType>) -> PineType {
+        self.map(&|t| match t {
+            PineType::Generic(id, _) => subst.get(id).cloned(),
+            _ => None,
+        })
+    }
+
+    /// Rebuilds the type with the bound type variables followed,
+    /// replacing every part of the type for which the function returns a type.
+    fn map(&self, f: &dyn Fn(&PineType) -> Option<PineType>) -> PineType {
+        let t = self.resolve();
+        if let Some(t) = f(&t) {
+            return t;
+        }
+
+        match t {
+            PineType::List(t) => PineType::List(Box::new(t.map(f))),
+            PineType::Map(k, v) => PineType::Map(Box::new(k.map(f)), Box::new(v.map(f))),
+            PineType::Set(t) => PineType::Set(Box::new(t.map(f))),
+            PineType::Optional(t) => PineType::Optional(Box::new(t.map(f))),
+            PineType::Tuple(ts) => PineType::Tuple(ts.iter().map(|t| t.map(f)).collect()),
+            PineType::Object(o, args) => PineType::Object(o, args.iter().map(|a| a.map(f)).collect()),
+            PineType::Enum(e, args) => PineType::Enum(e, args.iter().map(|a| a.map(f)).collect()),
+            PineType::Function { params, ret } => PineType::Function {
+                params: params.iter().map(|p| p.map(f)).collect(),
+                ret: Box::new(ret.map(f)),
+            },
+            t => t,
+        }
+    }
+
+    /// Gets the ids of the type parameters within the type, in the order they first occur.
+    pub fn generics(&self) -> Vec<usize> {
+        let mut ids = vec![];
+        self.collect_generics(&mut ids);
+        ids
+    }
+
+    fn collect_generics(&self, ids: &mut Vec<usize>) {
+        match self.resolve() {
+            PineType::Generic(id, _) if !ids.contains(&id) => ids.push(id),
+            PineType::List(t) | PineType::Set(t) | PineType::Optional(t) => t.collect_generics(ids),
+            PineType::Map(k, v) => {
+                k.collect_generics(ids);
+                v.collect_generics(ids);
+            }
+            PineType::Tuple(args) | PineType::Object(_, args) | PineType::Enum(_, args) => {
+                for a in &args {
+                    a.collect_generics(ids);
+                }
+            }
+            PineType::Function { params, ret } => {
+                for p in &params {
+                    p.collect_generics(ids);
+                }
+
+                ret.collect_generics(ids);
+            }
+            _ => {}
+        }
+    }
+
+    /// Determines if the type is a type variable that has not been bound.
+    pub fn is_unknown(&self) -> bool {
+        matches!(self.resolve(), PineType::Var(_))
+    }
+
+    /// Determines if the type is a number, on which arithmetic is defined.
+    pub fn is_number(&self) -> bool {
+        matches!(self.resolve(), PineType::Integer | PineType::UInteger | PineType::Byte | PineType::Float)
+    }
+
+    /// Determines if the type is a type parameter.
+    pub fn is_generic(&self) -> bool {
+        matches!(self.resolve(), PineType::Generic(..))
+    }
+}
+
+/// Gets a fresh id for a type variable or type parameter.
+fn next_type_id() -> usize {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The type variable reference type.
+pub type TypeVarRef = Rc<RefCell<TypeVar>>;
+
+/// Represents a type variable.
+#[derive(Debug)]
+pub enum TypeVar {
+    /// A type variable that has not been bound, along with the let level it was created at.
+    Unbound { id: usize, level: usize },
+    Bound(PineType),
+}
+
+/// The object definition reference type.
+pub type ObjRef = Rc<RefCell<ObjDef>>;
+
+/// Represents the definition of an object type.
+/// Object types are compared by name, so that object types can refer to themselves.
+pub struct ObjDef {
+    pub name: String,
+    /// The qualified name of the module that defines the object.
+    pub module: String,
+    /// The type parameters of a generic object type.
+    pub params: Vec<PineType>,
+    /// The fields of the object, in the order they are laid out in memory.
+    pub fields: Vec<(String, PineType)>,
+    /// The methods declared for the object, which include the methods that overload operators.
+    pub methods: Vec<SymbolRef>,
+    /// The interfaces the object implements.
+    pub ifaces: Vec<IfaceRef>,
+}
+
+impl ObjDef {
+    pub fn new(name: String, module: String) -> ObjRef {
+        Rc::new(RefCell::new(Self {
+            name,
+            module,
+            params: vec![],
+            fields: vec![],
+            methods: vec![],
+            ifaces: vec![],
+        }))
+    }
+
+    /// Gets the symbol of a method by name.
+    pub fn method(&self, name: &str) -> Option<SymbolRef> {
+        self.methods.iter().find(|m| m.borrow().name == name).cloned()
+    }
+
+    /// Gets the index and type of a field by name, for an object with type arguments.
+    pub fn instance_field(&self, name: &str, args: &[PineType]) -> Option<(usize, PineType)> {
+        let subst: HashMap<usize, PineType> = self.params.iter()
+            .flat_map(|p| p.generics())
+            .zip(args.iter().cloned())
+            .collect();
+        self.field(name).map(|(i, t)| (i, t.substitute(&subst)))
+    }
+
+    /// Gets the index and type of a field by name.
+    pub fn field(&self, name: &str) -> Option<(usize, PineType)> {
+        self.fields
+            .iter()
+            .position(|(n, _)| n == name)
+            .map(|i| (i, self.fields[i].1.clone()))
+    }
+}
+
+impl PartialEq for ObjDef {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.module == other.module
+    }
+}
+
+impl fmt::Debug for ObjDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.module, self.name)
+    }
+}
+
+/// The enum definition reference type.
+pub type EnumRef = Rc<RefCell<EnumDef>>;
+
+/// Represents the definition of an enum type.
+/// Enum types are compared by name, like object types.
+pub struct EnumDef {
+    pub name: String,
+    /// The qualified name of the module that defines the enum.
+    pub module: String,
+    /// The type parameters of a generic enum type.
+    pub params: Vec<PineType>,
+    /// The variants of the enum and the types of their payloads, in the order of their tags.
+    pub variants: Vec<(String, Vec<PineType>)>,
+}
+
+impl EnumDef {
+    pub fn new(name: String, module: String) -> EnumRef {
+        Rc::new(RefCell::new(Self {
+            name,
+            module,
+            params: vec![],
+            variants: vec![],
+        }))
+    }
+
+    /// Creates the builtin `Result<T, E>` enum, whose `Ok` variant holds a value and whose `Err` variant holds an error.
+    /// Builtin enums do not belong to a module.
+    pub fn new_result() -> EnumRef {
+        let (value, error) = (PineType::generic("T".to_string()), PineType::generic("E".to_string()));
+        let result = EnumDef::new("Result".to_string(), String::new());
+        result.borrow_mut().variants = vec![("Ok".to_string(), vec![value.clone()]), ("Err".to_string(), vec![error.clone()])];
+        result.borrow_mut().params = vec![value, error];
+        result
+    }
+
+    /// Determines if the enum is built into the compiler.
+    pub fn is_builtin(&self) -> bool {
+        self.module.is_empty()
+    }
+
+    /// Gets the payload types of a variant by tag, for an enum with type arguments.
+    pub fn instance_variant(&self, tag: usize, args: &[PineType]) -> Vec<PineType> {
+        let subst: HashMap<usize, PineType> = self.params.iter()
+            .flat_map(|p| p.generics())
+            .zip(args.iter().cloned())
+            .collect();
+        self.variants[tag].1.iter().map(|t| t.substitute(&subst)).collect()
+    }
+}
+
+impl PartialEq for EnumDef {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.module == other.module
+    }
+}
+
+impl fmt::Debug for EnumDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.module, self.name)
+    }
+}
+
+/// The interface definition reference type.
+pub type IfaceRef = Rc<RefCell<IfaceDef>>;
+
+/// Represents the definition of an interface.
+/// Interfaces are compared by name, like object types.
+pub struct IfaceDef {
+    pub name: String,
+    /// The qualified name of the module that defines the interface.
+    pub module: String,
+    /// The symbols of the methods of the interface, in the order they are laid out in a vtable.
+    /// The first parameter of every method has the type of the interface.
+    pub methods: Vec<SymbolRef>,
+    /// The implementations of the interface.
+    pub impls: Vec<ImplDef>,
+}
+
+/// Represents the implementation of an interface for an object type.
+#[derive(Clone)]
+pub struct ImplDef {
+    pub obj: ObjRef,
+    /// The prefix of the labels of the implementing functions.
+    pub prefix: String,
+    /// The symbols of the implementing functions, in the order of the interface methods.
+    pub methods: Vec<SymbolRef>,
+}
+
+impl IfaceDef {
+    pub fn new(name: String, module: String) -> IfaceRef {
+        Rc::new(RefCell::new(Self {
+            name,
+            module,
+            methods: vec![],
+            impls: vec![],
+        }))
+    }
+
+    /// Gets the index and symbol of a method by name.
+    pub fn method(&self, name: &str) -> Option<(usize, SymbolRef)> {
+        self.methods
+            .iter()
+            .position(|m| m.borrow().name == name)
+            .map(|i| (i, self.methods[i].clone()))
+    }
+
+    /// Gets the implementation of the interface for an object type, if any.
+    pub fn impl_for(&self, obj: &ObjRef) -> Option<ImplDef> {
+        self.impls.iter().find(|d| d.obj == *obj).cloned()
+    }
+
+    /// Gets the name of the global variable that holds the vtable of an implementation.
+    pub fn vtable(impl_def: &ImplDef) -> String {
+        format!("{}::vtable", impl_def.prefix)
+    }
+}
+
+impl PartialEq for IfaceDef {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.module == other.module
+    }
+}
+
+impl fmt::Debug for IfaceDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}::{}", self.module, self.name)
+    }
+}
+
+/// Represents the value of a constant, which is evaluated at compile time.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstValue {
+    Integer(i64),
+    UInteger(u64),
+    Byte(u8),
+    Float(f64),
+    Bool(bool),
+    Char(char),
+    String(String),
+}
+
+impl ConstValue {
+    /// Gets the type of the value.
+    pub fn pine_type(&self) -> PineType {
+        match self {
+            ConstValue::Integer(_) => PineType::Integer,
+            ConstValue::UInteger(_) => PineType::UInteger,
+            ConstValue::Byte(_) => PineType::Byte,
+            ConstValue::Float(_) => PineType::Float,
+            ConstValue::Bool(_) => PineType::Bool,
+            ConstValue::Char(_) => PineType::Char,
+            ConstValue::String(_) => PineType::String,
+        }
+    }
+}
+
 impl Default for PineType {
     fn default() -> Self {
         PineType::Unknown
@@ -41,45 +497,143 @@ pub(crate) trait ScopedAst {
 
 /// Represents a Pine executable program.
 pub struct Program {
-    pub main_module: Box<Module>,
+    /// The modules of the program in dependency order. The main module is last.
+    pub modules: Vec<Module>,
     pub main_fun: SymbolRef
 }
 
 impl Program {
-    pub fn new(main_module: Box<Module>) -> Self {
+    pub fn new(modules: Vec<Module>) -> Self {
         Self {
-            main_module,
+            modules,
             main_fun: Symbol::default()
         }
     }
+
+    /// Gets the main module of the program.
+    pub fn main_module(&self) -> &Module {
+        self.modules.last().expect("program has no modules")
+    }
 }
 
 /// Represents a Pine module.
 #[ast]
 pub struct Module {
+    #[default(String::new)] pub name: String,
     pub imports: Vec<Import>,
+    pub exports: Vec<Export>,
+    pub objs: Vec<Obj>,
+    pub enums: Vec<Enum>,
+    pub interfaces: Vec<Interface>,
+    pub impls: Vec<Impl>,
+    pub consts: Vec<Const>,
+    pub globals: Vec<Global>,
+    pub funs: Vec<Fun>,
+}
+
+/// Represents a Pine object declaration, such as `obj Point` or the generic `obj Box<T>`.
+#[ast]
+pub struct Obj {
+    pub ident: Box<Ident>,
+    pub type_params: Vec<Ident>,
+    pub fields: Vec<Field>,
+}
+
+/// Represents a Pine enum declaration, such as `enum Shape` or the generic `enum Option<T>`.
+#[ast]
+pub struct Enum {
+    pub ident: Box<Ident>,
+    pub type_params: Vec<Ident>,
+    pub variants: Vec<Variant>,
+}
+
+/// Represents a variant of a Pine enum, such as `Rect(float, float)` or `Empty`.
+#[ast]
+pub struct Variant {
+    pub ident: Box<Ident>,
+    pub payload: Vec<Ty>,
+}
+
+/// Represents a field of a Pine object.
+#[ast]
+pub struct Field {
+    pub ident: Box<Ident>,
+    pub ty: Box<Ty>,
+}
+
+/// Represents a Pine interface declaration.
+#[ast]
+pub struct Interface {
+    pub ident: Box<Ident>,
+    pub methods: Vec<MethodSig>,
+}
+
+/// Represents the signature of an interface method.
+#[ast]
+pub struct MethodSig {
+    pub ident: Box<Ident>,
+    pub params: Vec<Param>,
+    pub return_ty: Option<Box<Ty>>,
+}
+
+/// Represents the implementation of an interface for an object, such as `impl Shape for Rect`,
+/// or the methods of an object, such as `impl Vec`.
+#[ast]
+pub struct Impl {
+    pub iface: Option<Box<Ty>>,
+    pub obj: Box<Ty>,
     pub funs: Vec<Fun>,
 }
 
-/// Represents a Pine function.
+/// Represents a Pine constant, such as `const MAX = 10`.
+/// The expression is evaluated at compile time, and can only refer to literals and other constants.
+#[ast]
+pub struct Const {
+    pub ident: Box<Ident>,
+    pub ty: Option<Box<Ty>>,
+    pub expr: Box<Expr>,
+}
+
+/// Represents a module-level let binding, such as `let count = 0`.
+/// Global variables are initialized before main is called, in the order the modules depend on each other.
+#[ast]
+pub struct Global {
+    pub ident: Box<Ident>,
+    pub ty: Option<Box<Ty>>,
+    pub expr: Box<Expr>,
+}
+
+/// Represents a Pine function, such as `fun f(x: int)` or the generic `fun id<T>(x: T)`.
 #[ast]
 pub struct Fun {
     pub ident: Box<Ident>,
+    pub type_params: Vec<Ident>,
     pub params: Vec<Param>,
     pub return_ty: Option<Box<Ty>>,
     pub block: Box<Block>,
+    /// The type parameters of the impl the function is a method of, such as `T` in `impl Box<T>`.
+    #[default(Vec::new)] pub impl_type_params: Vec<PineType>,
 }
 
-/// Represents a Pine parameter.
+/// Represents a Pine parameter. The type of a parameter without a type annotation is inferred.
 #[ast]
 pub struct Param {
     pub ident: Box<Ident>,
-    pub ty: Box<Ty>,
+    pub ty: Option<Box<Ty>>,
 }
 
+/// Represents a Pine import.
 #[ast]
 pub struct Import {
     pub ident: Box<Ident>,
+    /// The qualified name of the imported module, set during module resolution.
+    #[default(String::new)] pub module: String,
+}
+
+/// Represents a Pine export list.
+#[ast]
+pub struct Export {
+    pub idents: Vec<Ident>,
 }
 
 /// Represents a Pine let statement.
@@ -90,10 +644,22 @@ pub struct LetStmt {
     pub expr: Box<Expr>,
 }
 
+/// Represents a Pine let statement that destructures a tuple, such as `let (q, r) = divmod(7, 2)`.
+/// The pattern must match every value of the type of the expression.
+#[ast]
+pub struct LetTupleStmt {
+    pub pattern: Box<Pattern>,
+    pub ty: Option<Box<Ty>>,
+    pub expr: Box<Expr>,
+}
+
 /// Represents a Pine set statement.
 #[ast]
 pub struct SetStmt {
-    pub ident: Box<Ident>,
+    /// The place being assigned, either an identifier, an index expression, or a field expression.
+    pub target: Box<Expr>,
+    /// The operator of a compound assignment, such as `+=`, if any.
+    pub op: Option<Operator>,
     pub expr: Box<Expr>,
 }
 
@@ -112,12 +678,28 @@ pub struct WhileStmt {
     pub block: Box<Block>,
 }
 
+/// Represents a Pine for statement, which iterates over a range or a list.
+#[ast]
+pub struct ForStmt {
+    pub ident: Box<Ident>,
+    pub iter: Box<Expr>,
+    pub block: Box<Block>,
+}
+
 /// Represents a Pine return statement.
 #[ast]
 pub struct ReturnStmt {
     pub expr: Option<Box<Expr>>,
 }
 
+/// Represents a break statement, which exits the innermost enclosing loop.
+#[ast]
+pub struct BreakStmt {}
+
+/// Represents a continue statement, which skips to the next iteration of the innermost enclosing loop.
+#[ast]
+pub struct ContinueStmt {}
+
 /// Represents a Pine expression statement.
 #[ast]
 pub struct ExprStmt {
@@ -134,10 +716,14 @@ pub struct Block {
 #[derive(Debug)]
 pub enum Stmt {
     Let(LetStmt),
+    LetTuple(LetTupleStmt),
     Set(SetStmt),
     If(IfStmt),
     While(WhileStmt),
+    For(ForStmt),
     Return(ReturnStmt),
+    Break(BreakStmt),
+    Continue(ContinueStmt),
     Expr(ExprStmt),
     Block(Block),
 }
@@ -146,11 +732,15 @@ impl Ast for Stmt {
     fn span(&self) -> Span {
         match self {
             Stmt::Let(let_stmt) => let_stmt.span(),
+            Stmt::LetTuple(let_tuple_stmt) => let_tuple_stmt.span(),
             Stmt::Set(set_stmt) => set_stmt.span(),
             Stmt::If(if_stmt) => if_stmt.span(),
             Stmt::While(while_stmt) => while_stmt.span(),
+            Stmt::For(for_stmt) => for_stmt.span(),
             Stmt::Return(return_stmt) => return_stmt.span(),
             Stmt::Expr(expr_stmt) => expr_stmt.span(),
+            Stmt::Break(break_stmt) => break_stmt.span(),
+            Stmt::Continue(continue_stmt) => continue_stmt.span(),
             Stmt::Block(block) => block.span(),
         }
     }
@@ -160,11 +750,15 @@ impl ScopedAst for Stmt {
     fn scope(&self) -> ScopeRef {
         match self {
             Stmt::Let(let_stmt) => let_stmt.scope(),
+            Stmt::LetTuple(let_tuple_stmt) => let_tuple_stmt.scope(),
             Stmt::Set(set_stmt) => set_stmt.scope(),
             Stmt::If(if_stmt) => if_stmt.scope(),
             Stmt::While(while_stmt) => while_stmt.scope(),
+            Stmt::For(for_stmt) => for_stmt.scope(),
             Stmt::Return(return_stmt) => return_stmt.scope(),
             Stmt::Expr(expr_stmt) => expr_stmt.scope(),
+            Stmt::Break(break_stmt) => break_stmt.scope(),
+            Stmt::Continue(continue_stmt) => continue_stmt.scope(),
             Stmt::Block(block) => block.scope(),
         }
     }
@@ -172,20 +766,25 @@ impl ScopedAst for Stmt {
     fn set_scope(&mut self, scope: ScopeRef) {
         match self {
             Stmt::Let(let_stmt) => let_stmt.set_scope(scope),
+            Stmt::LetTuple(let_tuple_stmt) => let_tuple_stmt.set_scope(scope),
             Stmt::Set(set_stmt) => set_stmt.set_scope(scope),
             Stmt::If(if_stmt) => if_stmt.set_scope(scope),
             Stmt::While(while_stmt) => while_stmt.set_scope(scope),
+            Stmt::For(for_stmt) => for_stmt.set_scope(scope),
             Stmt::Return(return_stmt) => return_stmt.set_scope(scope),
             Stmt::Expr(expr_stmt) => expr_stmt.set_scope(scope),
+            Stmt::Break(break_stmt) => break_stmt.set_scope(scope),
+            Stmt::Continue(continue_stmt) => continue_stmt.set_scope(scope),
             Stmt::Block(block) => block.set_scope(scope),
         }
     }
 }
 
 /// Represents an integer literal.
+/// The value is wide enough for the values of both `int` and `uint`, and for negative literals in patterns.
 #[ast]
 pub struct IntLitExpr {
-    pub value: i64,
+    pub value: i128,
     #[default(PineType::default)] pub ty: PineType,
     #[default(pvm::Operand::default)] pub dest: pvm::Operand,
 }
@@ -198,6 +797,14 @@ pub struct FloatLitExpr {
     #[default(pvm::Operand::default)] pub dest: pvm::Operand,
 }
 
+/// Represents a character literal, such as `'a'`.
+#[ast]
+pub struct CharLitExpr {
+    pub value: char,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
 /// Represents a bool literal.
 #[ast]
 pub struct BoolLitExpr {
@@ -214,19 +821,39 @@ pub struct StringLitExpr {
     #[default(pvm::Operand::default)] pub dest: pvm::Operand,
 }
 
+/// Represents an interpolated string, such as `"x = {x}, y = {y:.2}"`.
+/// The parts are the string literals between the embedded expressions, and the calls of the builtin `to_string`
+/// that convert the embedded expressions, which are concatenated into a new string.
+#[ast]
+pub struct InterpStringExpr {
+    pub parts: Vec<Expr>,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
 /// Represents an identifier expression.
+/// The identifier may be qualified by a path of modules, as in `sub::util::func`.
 #[ast]
 pub struct IdentExpr {
+    pub path: Vec<Ident>,
     pub ident: Box<Ident>,
+    /// Whether the identifier refers to an optional variable that has been checked to hold a value,
+    /// in which case the expression is the value rather than the optional.
+    #[default(bool::default)] pub narrowed: bool,
     #[default(PineType::default)] pub ty: PineType,
     #[default(pvm::Operand::default)] pub dest: pvm::Operand,
 }
 
 /// Represents a call expression.
+/// A call in method syntax, as in `o.f(a)`, is resolved during typing against the type of the receiver,
+/// and is rewritten into a call with the receiver as its first argument, as in `f(o, a)`, unless `f` is a field.
 #[ast]
 pub struct CallExpr {
     pub fun: Box<Expr>,
     pub args: Vec<Expr>,
+    /// The function in scope that a call in method syntax names, which is called if the receiver's type
+    /// has neither a field nor a method of that name.
+    #[default(Option::default)] pub fallback: Option<SymbolRef>,
     #[default(PineType::default)] pub ty: PineType,
     #[default(pvm::Operand::default)] pub dest: pvm::Operand,
 }
@@ -250,30 +877,283 @@ pub struct BinaryExpr {
     #[default(pvm::Operand::default)] pub dest: pvm::Operand,
 }
 
+/// Represents a list literal expression, such as `[1, 2, 3]`.
+#[ast]
+pub struct ListLitExpr {
+    pub elems: Vec<Expr>,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents a map literal expression, such as `{"one": 1, "two": 2}`, or `{:}` for an empty map.
+#[ast]
+pub struct MapLitExpr {
+    pub entries: Vec<(Expr, Expr)>,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents a set literal expression, such as `{1, 2, 3}`, or `{}` for an empty set.
+#[ast]
+pub struct SetLitExpr {
+    pub elems: Vec<Expr>,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents a tuple literal expression, such as `(1, "one")`.
+#[ast]
+pub struct TupleLitExpr {
+    pub elems: Vec<Expr>,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents an index expression, such as `xs[i]`.
+#[ast]
+pub struct IndexExpr {
+    pub expr: Box<Expr>,
+    pub index: Box<Expr>,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents a field expression, such as `o.name`.
+#[ast]
+pub struct FieldExpr {
+    pub expr: Box<Expr>,
+    pub field: Box<Ident>,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents the conversion of an expression to another type.
+/// Objects are implicitly cast to the interfaces they implement, and numbers are explicitly cast
+/// with `as`, as in `x as float`, in which case the type that is written is the target.
+#[ast]
+pub struct CastExpr {
+    pub expr: Box<Expr>,
+    #[default(Option::default)] pub target: Option<Box<Ty>>,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents the `none` literal, which is the empty value of an optional type.
+#[ast]
+pub struct NoneLitExpr {
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents a check of whether an optional holds a value, as in `x is some` or `x is none`.
+#[ast]
+pub struct IsExpr {
+    pub expr: Box<Expr>,
+    pub some: bool,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents the propagation of the error of a result, as in `parse(s)?`.
+/// The expression is the value of an `Ok` result, and an `Err` result is returned from the enclosing function.
+#[ast]
+pub struct TryExpr {
+    pub expr: Box<Expr>,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents a range expression, such as `0..n` or `0..=n`.
+#[ast]
+pub struct RangeExpr {
+    pub start: Box<Expr>,
+    pub end: Box<Expr>,
+    pub inclusive: bool,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents a match expression, which evaluates the arm of the first pattern that matches the value.
+/// A match in statement position may have block arms, and its value is discarded.
+#[ast]
+pub struct MatchExpr {
+    pub expr: Box<Expr>,
+    pub arms: Vec<MatchArm>,
+    pub is_stmt: bool,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents an arm of a match, such as `case Circle(r) => r * r` or `case _ then ... `.
+#[ast]
+pub struct MatchArm {
+    pub pattern: Box<Pattern>,
+    pub body: ArmBody,
+}
+
+/// Represents the body of a match arm.
+#[derive(Debug)]
+pub enum ArmBody {
+    Expr(Box<Expr>),
+    Block(Box<Block>),
+}
+
+/// Represents a lambda expression, such as `fun(x) => x * 2` or `fun(x: int) -> int begin ... end`.
+/// The local variables the body refers to are captured by value when the lambda is created.
+#[ast]
+pub struct LambdaExpr {
+    pub params: Vec<Param>,
+    pub return_ty: Option<Box<Ty>>,
+    pub body: LambdaBody,
+    /// The symbol of the lambda, which owns the scope of its body and holds its captured variables.
+    #[default(Symbol::default)] pub symbol: SymbolRef,
+    #[default(PineType::default)] pub ty: PineType,
+    #[default(pvm::Operand::default)] pub dest: pvm::Operand,
+}
+
+/// Represents the body of a lambda.
+#[derive(Debug)]
+pub enum LambdaBody {
+    Expr(Box<Expr>),
+    Block(Box<Block>),
+}
+
+/// Represents the wildcard pattern `_`, which matches any value.
+#[ast]
+pub struct WildcardPattern {}
+
+/// Represents a literal pattern, such as `1`, `-2.5`, `true` or `"on"`.
+#[ast]
+pub struct LitPattern {
+    pub expr: Box<Expr>,
+}
+
+/// Represents a binding pattern, which matches any value and binds it to a variable.
+#[ast]
+pub struct BindingPattern {
+    pub ident: Box<Ident>,
+}
+
+/// Represents a variant pattern, such as `Rect(w, h)` or `shapes::Empty`.
+/// A bare identifier that names a unit variant is a variant pattern rather than a binding pattern.
+#[ast]
+pub struct VariantPattern {
+    pub variant: Box<IdentExpr>,
+    pub args: Vec<Pattern>,
+}
+
+/// Represents a tuple pattern, such as `(x, _)`.
+#[ast]
+pub struct TuplePattern {
+    pub elems: Vec<Pattern>,
+}
+
+/// Represents a Pine pattern.
+#[derive(Debug)]
+pub enum Pattern {
+    Wildcard(WildcardPattern),
+    Lit(LitPattern),
+    Binding(BindingPattern),
+    Variant(VariantPattern),
+    Tuple(TuplePattern),
+}
+
+impl Ast for Pattern {
+    fn span(&self) -> Span {
+        match self {
+            Pattern::Wildcard(p) => p.span(),
+            Pattern::Lit(p) => p.span(),
+            Pattern::Binding(p) => p.span(),
+            Pattern::Variant(p) => p.span(),
+            Pattern::Tuple(p) => p.span(),
+        }
+    }
+}
+
+impl ScopedAst for Pattern {
+    fn scope(&self) -> ScopeRef {
+        match self {
+            Pattern::Wildcard(p) => p.scope(),
+            Pattern::Lit(p) => p.scope(),
+            Pattern::Binding(p) => p.scope(),
+            Pattern::Variant(p) => p.scope(),
+            Pattern::Tuple(p) => p.scope(),
+        }
+    }
+
+    fn set_scope(&mut self, scope: ScopeRef) {
+        match self {
+            Pattern::Wildcard(p) => p.set_scope(scope),
+            Pattern::Lit(p) => p.set_scope(scope),
+            Pattern::Binding(p) => p.set_scope(scope),
+            Pattern::Variant(p) => p.set_scope(scope),
+            Pattern::Tuple(p) => p.set_scope(scope),
+        }
+    }
+}
+
+impl VariantPattern {
+    /// Gets the enum and tag of the variant the pattern matches.
+    pub fn variant(&self) -> (EnumRef, usize) {
+        self.variant.ident.symbol.borrow().variant.clone().expect("pattern is not a variant")
+    }
+}
+
 /// Represents a Pine expression.
 #[derive(Debug)]
 pub enum Expr {
     IntLit(IntLitExpr),
     FloatLit(FloatLitExpr),
+    CharLit(CharLitExpr),
     BoolLit(BoolLitExpr),
     StringLit(StringLitExpr),
+    InterpString(InterpStringExpr),
     Ident(IdentExpr),
     Call(CallExpr),
     Unary(UnaryExpr),
     Binary(BinaryExpr),
+    Range(RangeExpr),
+    ListLit(ListLitExpr),
+    MapLit(MapLitExpr),
+    SetLit(SetLitExpr),
+    TupleLit(TupleLitExpr),
+    Index(IndexExpr),
+    Field(FieldExpr),
+    Cast(CastExpr),
+    NoneLit(NoneLitExpr),
+    Is(IsExpr),
+    Try(TryExpr),
+    Match(MatchExpr),
+    Lambda(LambdaExpr),
 }
 
 impl Expr {
+    /// Gets the type of the expression, with the type variables it was inferred to resolved.
     pub fn ty(&self) -> PineType {
         match self {
-            Expr::IntLit(int_lit) => int_lit.ty.clone(),
-            Expr::FloatLit(float_lit) => float_lit.ty.clone(),
-            Expr::BoolLit(bool_lit) => bool_lit.ty.clone(),
-            Expr::StringLit(string_lit) => string_lit.ty.clone(),
-            Expr::Ident(ident) => ident.ty.clone(),
-            Expr::Call(call) => call.ty.clone(),
-            Expr::Unary(unary) => unary.ty.clone(),
-            Expr::Binary(binary) => binary.ty.clone(),
+            Expr::IntLit(int_lit) => int_lit.ty.resolve_all(),
+            Expr::FloatLit(float_lit) => float_lit.ty.resolve_all(),
+            Expr::CharLit(char_lit) => char_lit.ty.resolve_all(),
+            Expr::BoolLit(bool_lit) => bool_lit.ty.resolve_all(),
+            Expr::StringLit(string_lit) => string_lit.ty.resolve_all(),
+            Expr::InterpString(interp_string) => interp_string.ty.resolve_all(),
+            Expr::Ident(ident) => ident.ty.resolve_all(),
+            Expr::Call(call) => call.ty.resolve_all(),
+            Expr::Unary(unary) => unary.ty.resolve_all(),
+            Expr::Binary(binary) => binary.ty.resolve_all(),
+            Expr::Range(range) => range.ty.resolve_all(),
+            Expr::ListLit(list_lit) => list_lit.ty.resolve_all(),
+            Expr::MapLit(map_lit) => map_lit.ty.resolve_all(),
+            Expr::SetLit(set_lit) => set_lit.ty.resolve_all(),
+            Expr::TupleLit(tuple_lit) => tuple_lit.ty.resolve_all(),
+            Expr::Index(index) => index.ty.resolve_all(),
+            Expr::Field(field) => field.ty.resolve_all(),
+            Expr::Cast(cast) => cast.ty.resolve_all(),
+            Expr::NoneLit(none_lit) => none_lit.ty.resolve_all(),
+            Expr::Is(is) => is.ty.resolve_all(),
+            Expr::Try(t) => t.ty.resolve_all(),
+            Expr::Match(m) => m.ty.resolve_all(),
+            Expr::Lambda(l) => l.ty.resolve_all(),
         }
     }
 
@@ -281,12 +1161,27 @@ impl Expr {
         match self {
             Expr::IntLit(int_lit) => int_lit.ty = ty,
             Expr::FloatLit(float_lit) => float_lit.ty = ty,
+            Expr::CharLit(char_lit) => char_lit.ty = ty,
             Expr::BoolLit(bool_lit) => bool_lit.ty = ty,
             Expr::StringLit(string_lit) => string_lit.ty = ty,
+            Expr::InterpString(interp_string) => interp_string.ty = ty,
             Expr::Ident(ident) => ident.ty = ty,
             Expr::Call(call) => call.ty = ty,
             Expr::Unary(unary) => unary.ty = ty,
             Expr::Binary(binary) => binary.ty = ty,
+            Expr::Range(range) => range.ty = ty,
+            Expr::ListLit(list_lit) => list_lit.ty = ty,
+            Expr::MapLit(map_lit) => map_lit.ty = ty,
+            Expr::SetLit(set_lit) => set_lit.ty = ty,
+            Expr::TupleLit(tuple_lit) => tuple_lit.ty = ty,
+            Expr::Index(index) => index.ty = ty,
+            Expr::Field(field) => field.ty = ty,
+            Expr::Cast(cast) => cast.ty = ty,
+            Expr::NoneLit(none_lit) => none_lit.ty = ty,
+            Expr::Is(is) => is.ty = ty,
+            Expr::Try(t) => t.ty = ty,
+            Expr::Match(m) => m.ty = ty,
+            Expr::Lambda(l) => l.ty = ty,
         }
     }
 
@@ -294,12 +1189,27 @@ impl Expr {
         match self {
             Expr::IntLit(int_lit) => int_lit.dest.clone(),
             Expr::FloatLit(float_lit) => float_lit.dest.clone(),
+            Expr::CharLit(char_lit) => char_lit.dest.clone(),
             Expr::BoolLit(bool_lit) => bool_lit.dest.clone(),
             Expr::StringLit(string_lit) => string_lit.dest.clone(),
+            Expr::InterpString(interp_string) => interp_string.dest.clone(),
             Expr::Ident(ident) => ident.dest.clone(),
             Expr::Call(call) => call.dest.clone(),
             Expr::Unary(unary) => unary.dest.clone(),
             Expr::Binary(binary) => binary.dest.clone(),
+            Expr::Range(range) => range.dest.clone(),
+            Expr::ListLit(list_lit) => list_lit.dest.clone(),
+            Expr::MapLit(map_lit) => map_lit.dest.clone(),
+            Expr::SetLit(set_lit) => set_lit.dest.clone(),
+            Expr::TupleLit(tuple_lit) => tuple_lit.dest.clone(),
+            Expr::Index(index) => index.dest.clone(),
+            Expr::Field(field) => field.dest.clone(),
+            Expr::Cast(cast) => cast.dest.clone(),
+            Expr::NoneLit(none_lit) => none_lit.dest.clone(),
+            Expr::Is(is) => is.dest.clone(),
+            Expr::Try(t) => t.dest.clone(),
+            Expr::Match(m) => m.dest.clone(),
+            Expr::Lambda(l) => l.dest.clone(),
         }
     }
 }
@@ -308,12 +1218,27 @@ impl Ast for Expr {
         match self {
             Expr::IntLit(int_lit_expr) => int_lit_expr.span(),
             Expr::FloatLit(float_lit_expr) => float_lit_expr.span(),
+            Expr::CharLit(char_lit_expr) => char_lit_expr.span(),
             Expr::BoolLit(bool_lit_expr) => bool_lit_expr.span(),
             Expr::StringLit(string_lit_expr) => string_lit_expr.span(),
+            Expr::InterpString(interp_string_expr) => interp_string_expr.span(),
             Expr::Ident(ident_expr) => ident_expr.span(),
             Expr::Call(call_expr) => call_expr.span(),
             Expr::Unary(unary_expr) => unary_expr.span(),
             Expr::Binary(binary_expr) => binary_expr.span(),
+            Expr::Range(range_expr) => range_expr.span(),
+            Expr::ListLit(list_lit_expr) => list_lit_expr.span(),
+            Expr::MapLit(map_lit_expr) => map_lit_expr.span(),
+            Expr::SetLit(set_lit_expr) => set_lit_expr.span(),
+            Expr::TupleLit(tuple_lit_expr) => tuple_lit_expr.span(),
+            Expr::Index(index_expr) => index_expr.span(),
+            Expr::Field(field_expr) => field_expr.span(),
+            Expr::Cast(cast_expr) => cast_expr.span(),
+            Expr::NoneLit(none_lit_expr) => none_lit_expr.span(),
+            Expr::Is(is_expr) => is_expr.span(),
+            Expr::Try(try_expr) => try_expr.span(),
+            Expr::Match(match_expr) => match_expr.span(),
+            Expr::Lambda(lambda_expr) => lambda_expr.span(),
         }
     }
 }
@@ -323,12 +1248,27 @@ impl ScopedAst for Expr {
         match self {
             Expr::IntLit(int_lit_expr) => int_lit_expr.scope(),
             Expr::FloatLit(float_lit_expr) => float_lit_expr.scope(),
+            Expr::CharLit(char_lit_expr) => char_lit_expr.scope(),
             Expr::BoolLit(bool_lit_expr) => bool_lit_expr.scope(),
             Expr::StringLit(string_lit_expr) => string_lit_expr.scope(),
+            Expr::InterpString(interp_string_expr) => interp_string_expr.scope(),
             Expr::Ident(ident_expr) => ident_expr.scope(),
             Expr::Call(call_expr) => call_expr.scope(),
             Expr::Unary(unary_expr) => unary_expr.scope(),
             Expr::Binary(binary_expr) => binary_expr.scope(),
+            Expr::Range(range_expr) => range_expr.scope(),
+            Expr::ListLit(list_lit_expr) => list_lit_expr.scope(),
+            Expr::MapLit(map_lit_expr) => map_lit_expr.scope(),
+            Expr::SetLit(set_lit_expr) => set_lit_expr.scope(),
+            Expr::TupleLit(tuple_lit_expr) => tuple_lit_expr.scope(),
+            Expr::Index(index_expr) => index_expr.scope(),
+            Expr::Field(field_expr) => field_expr.scope(),
+            Expr::Cast(cast_expr) => cast_expr.scope(),
+            Expr::NoneLit(none_lit_expr) => none_lit_expr.scope(),
+            Expr::Is(is_expr) => is_expr.scope(),
+            Expr::Try(try_expr) => try_expr.scope(),
+            Expr::Match(match_expr) => match_expr.scope(),
+            Expr::Lambda(lambda_expr) => lambda_expr.scope(),
         }
     }
 
@@ -336,12 +1276,27 @@ impl ScopedAst for Expr {
         match self {
             Expr::IntLit(int_lit_expr) => int_lit_expr.set_scope(scope),
             Expr::FloatLit(float_lit_expr) => float_lit_expr.set_scope(scope),
+            Expr::CharLit(char_lit_expr) => char_lit_expr.set_scope(scope),
             Expr::BoolLit(bool_lit_expr) => bool_lit_expr.set_scope(scope),
             Expr::StringLit(string_lit_expr) => string_lit_expr.set_scope(scope),
+            Expr::InterpString(interp_string_expr) => interp_string_expr.set_scope(scope),
             Expr::Ident(ident_expr) => ident_expr.set_scope(scope),
             Expr::Call(call_expr) => call_expr.set_scope(scope),
             Expr::Unary(unary_expr) => unary_expr.set_scope(scope),
             Expr::Binary(binary_expr) => binary_expr.set_scope(scope),
+            Expr::Range(range_expr) => range_expr.set_scope(scope),
+            Expr::ListLit(list_lit_expr) => list_lit_expr.set_scope(scope),
+            Expr::MapLit(map_lit_expr) => map_lit_expr.set_scope(scope),
+            Expr::SetLit(set_lit_expr) => set_lit_expr.set_scope(scope),
+            Expr::TupleLit(tuple_lit_expr) => tuple_lit_expr.set_scope(scope),
+            Expr::Index(index_expr) => index_expr.set_scope(scope),
+            Expr::Field(field_expr) => field_expr.set_scope(scope),
+            Expr::Cast(cast_expr) => cast_expr.set_scope(scope),
+            Expr::NoneLit(none_lit_expr) => none_lit_expr.set_scope(scope),
+            Expr::Is(is_expr) => is_expr.set_scope(scope),
+            Expr::Try(try_expr) => try_expr.set_scope(scope),
+            Expr::Match(match_expr) => match_expr.set_scope(scope),
+            Expr::Lambda(lambda_expr) => lambda_expr.set_scope(scope),
         }
     }
 }
@@ -360,6 +1315,16 @@ impl Ident {
     }
 }
 
+impl CallExpr {
+    /// Gets the builtin being called, if any.
+    pub fn builtin(&self) -> Option<Builtin> {
+        match self.fun.as_ref() {
+            Expr::Ident(e) => e.ident.symbol.borrow().builtin,
+            _ => None,
+        }
+    }
+}
+
 /// Represents a Pine type.
 #[ast]
 pub struct Ty {
@@ -0,0 +1,169 @@
+use crate::ast::PineType;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Represents a function that is built into the compiler.
+/// Builtins are type checked and lowered by the compiler instead of being defined in Pine.
+#[derive(Debug, PartialEq, Copy, Clone, EnumIter)]
+pub enum Builtin {
+    Print,
+    Println,
+    Len,
+    Push,
+    Get,
+    Insert,
+    Remove,
+    Contains,
+    /// Converts a value to a string.
+    ToString,
+    LoadChar,
+    Halt,
+}
+
+impl Builtin {
+    /// Gets all builtins.
+    pub fn all() -> Vec<Self> {
+        Self::iter().collect()
+    }
+
+    /// Gets the name of the builtin.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Builtin::Print => "print",
+            Builtin::Println => "println",
+            Builtin::Len => "len",
+            Builtin::Push => "push",
+            Builtin::Get => "get",
+            Builtin::Insert => "insert",
+            Builtin::Remove => "remove",
+            Builtin::Contains => "contains",
+            Builtin::ToString => "to_string",
+            Builtin::LoadChar => "load_char",
+            Builtin::Halt => "halt",
+        }
+    }
+
+    /// Whether the builtin is an intrinsic, which is only visible in the core module.
+    /// Intrinsics are the operations the core module cannot write in Pine.
+    pub fn is_intrinsic(&self) -> bool {
+        matches!(self, Builtin::LoadChar | Builtin::Halt)
+    }
+
+    /// Gets the type returned by the builtin.
+    pub fn return_pine_type(&self) -> PineType {
+        match self {
+            Builtin::Len => PineType::Integer,
+            Builtin::Contains => PineType::Bool,
+            Builtin::ToString => PineType::String,
+            Builtin::LoadChar => PineType::Char,
+            _ => PineType::Void,
+        }
+    }
+
+    /// Gets the type resulting from calling the builtin with the argument types.
+    pub fn call_pine_type(&self, args: &[PineType]) -> Result<PineType, String> {
+        match self {
+            Builtin::Print | Builtin::Println => {
+                let arity_ok = match self {
+                    Builtin::Print => args.len() == 1,
+                    _ => args.len() <= 1,
+                };
+                if !arity_ok {
+                    return Err(format!("`{}` expects a single argument", self.name()));
+                }
+
+                for a in args {
+                    if !a.is_number() && !matches!(a, PineType::Char | PineType::Bool | PineType::String) {
+                        return Err(format!("`{}` is not defined for type {}", self.name(), a));
+                    }
+                }
+
+                Ok(PineType::Void)
+            }
+            Builtin::Len => {
+                if args.len() != 1 {
+                    return Err(format!("`{}` expects a single argument", self.name()));
+                }
+
+                match &args[0] {
+                    PineType::List(_) | PineType::String | PineType::Map(..) | PineType::Set(_) => Ok(PineType::Integer),
+                    t => Err(format!("`{}` is not defined for type {}", self.name(), t)),
+                }
+            }
+            Builtin::Push => {
+                if args.len() != 2 {
+                    return Err(format!("`{}` expects a list and a value", self.name()));
+                }
+
+                match &args[0] {
+                    PineType::List(t) if **t == args[1] => Ok(PineType::Void),
+                    PineType::List(t) => Err(format!("cannot push a value of type {} to a list of type [{}]", args[1], t)),
+                    t => Err(format!("`{}` is not defined for type {}", self.name(), t)),
+                }
+            }
+            Builtin::Get => match args {
+                [PineType::Map(k, v), key] if **k == *key => Ok(*v.clone()),
+                [PineType::Map(k, _), key] => Err(format!("cannot get a key of type {} from a map with keys of type {}", key, k)),
+                [t, _] => Err(format!("`{}` is not defined for type {}", self.name(), t)),
+                _ => Err(format!("`{}` expects a map and a key", self.name())),
+            },
+            Builtin::Insert => match args {
+                [PineType::Map(k, v), key, value] if **k == *key && **v == *value => Ok(PineType::Void),
+                [PineType::Map(k, v), key, value] => {
+                    Err(format!("cannot insert a key of type {} and a value of type {} into a map of type {}", key, value, PineType::Map(k.clone(), v.clone())))
+                }
+                [PineType::Set(t), elem] if **t == *elem => Ok(PineType::Void),
+                [PineType::Set(t), elem] => Err(format!("cannot insert a value of type {} into a set of type Set<{}>", elem, t)),
+                [PineType::Map(..), ..] => Err(format!("`{}` expects a map, a key and a value", self.name())),
+                [PineType::Set(_), ..] => Err(format!("`{}` expects a set and a value", self.name())),
+                [t, ..] => Err(format!("`{}` is not defined for type {}", self.name(), t)),
+                [] => Err(format!("`{}` expects a map or set", self.name())),
+            },
+            Builtin::Remove | Builtin::Contains => {
+                let ret = self.return_pine_type();
+                match args {
+                    [PineType::Map(k, _) | PineType::Set(k), key] if **k == *key => Ok(ret),
+                    [PineType::Map(k, _) | PineType::Set(k), key] => Err(format!("`{}` expects a key of type {} but was given {}", self.name(), k, key)),
+                    [t, _] => Err(format!("`{}` is not defined for type {}", self.name(), t)),
+                    _ => Err(format!("`{}` expects a map or set and a key", self.name())),
+                }
+            }
+            // objects are converted by their `to_string` method, collections by converting their elements,
+            // and floats can be given a number of decimal places
+            Builtin::ToString => match args {
+                [t] if converted_types(t).iter().all(is_convertible) => Ok(PineType::String),
+                [PineType::Float, PineType::Integer] => Ok(PineType::String),
+                [t, PineType::Integer] if *t != PineType::Float => Err(format!("only a float can be converted to a string with a precision, but found {}", t)),
+                [t] => Err(format!("`{}` is not defined for type {}", self.name(), t)),
+                _ => Err(format!("`{}` expects a value and an optional precision", self.name())),
+            },
+            Builtin::LoadChar => match args {
+                [PineType::String, PineType::Integer] => Ok(PineType::Char),
+                _ => Err(format!("`{}` expects a string and an index", self.name())),
+            },
+            Builtin::Halt => match args {
+                [PineType::Integer] => Ok(PineType::Void),
+                _ => Err(format!("`{}` expects an exit code", self.name())),
+            },
+        }
+    }
+}
+
+/// Gets the types of the values that are converted when a value of a type is converted to a string.
+/// A list, set, map, tuple or optional is converted by converting its elements.
+pub fn converted_types(ty: &PineType) -> Vec<PineType> {
+    match ty.resolve() {
+        PineType::List(t) | PineType::Set(t) | PineType::Optional(t) => converted_types(&t),
+        PineType::Map(k, v) => [converted_types(&k), converted_types(&v)].concat(),
+        PineType::Tuple(ts) => ts.iter().flat_map(converted_types).collect(),
+        t => vec![t],
+    }
+}
+
+/// Determines if a value of a type that is not a collection can be converted to a string.
+fn is_convertible(ty: &PineType) -> bool {
+    match ty {
+        PineType::Object(o, _) => o.borrow().method(Builtin::ToString.name()).is_some(),
+        t => t.is_number() || matches!(t, PineType::Char | PineType::Bool | PineType::String),
+    }
+}
@@ -0,0 +1,399 @@
+# The core module, which is implicitly imported into every program.
+# Its exports can be used in every module, or named as `core::name` where a module shadows them.
+# Only the functions that a program uses are compiled into it.
+
+export exit, panic, assert
+export abs, min, max, clamp, sqrt, gcd
+export char_at, chars, substring, starts_with, ends_with, find, repeat
+export is_digit, is_space, to_upper, to_lower, trim, split, join, parse_int
+export reverse, index_of, sum, keys, values, elements
+export Stack, stack, Queue, queue
+
+# process
+
+fun exit(code: int) begin
+    halt(code)
+end
+
+fun panic(message: string) begin
+    println("panic: " + message)
+    halt(1)
+end
+
+fun assert(condition: bool, message: string) begin
+    if not condition then
+        panic("assertion failed: " + message)
+    end
+end
+
+# math
+
+fun abs(x: int) -> int begin
+    if x < 0 then
+        return -x
+    end
+    return x
+end
+
+fun min<T>(a: T, b: T) -> T begin
+    if b < a then
+        return b
+    end
+    return a
+end
+
+fun max<T>(a: T, b: T) -> T begin
+    if b > a then
+        return b
+    end
+    return a
+end
+
+fun clamp<T>(x: T, low: T, high: T) -> T begin
+    if x < low then
+        return low
+    elif x > high then
+        return high
+    end
+    return x
+end
+
+fun sqrt(x: float) -> float begin
+    return x ** 0.5
+end
+
+fun gcd(a: int, b: int) -> int begin
+    let (x, y) = (abs(a), abs(b))
+    while y != 0 do
+        let r = x % y
+        set x = y
+        set y = r
+    end
+    return x
+end
+
+# strings
+
+fun char_at(s: string, i: int) -> char begin
+    return load_char(s, i)
+end
+
+fun chars(s: string) -> [char] begin
+    let cs: [char] = []
+    for i in 0..len(s) do
+        push(cs, load_char(s, i))
+    end
+    return cs
+end
+
+fun substring(s: string, start: int, end_: int) -> string begin
+    let result = ""
+    for i in max(start, 0)..min(end_, len(s)) do
+        set result = result + to_string(load_char(s, i))
+    end
+    return result
+end
+
+fun starts_with(s: string, prefix: string) -> bool begin
+    return matches_at(s, prefix, 0)
+end
+
+fun ends_with(s: string, suffix: string) -> bool begin
+    return matches_at(s, suffix, len(s) - len(suffix))
+end
+
+fun find(s: string, sub: string) -> int? begin
+    for i in 0..len(s) - len(sub) + 1 do
+        if matches_at(s, sub, i) then
+            return i
+        end
+    end
+    return none
+end
+
+fun repeat(s: string, n: int) -> string begin
+    let result = ""
+    for i in 0..n do
+        set result = result + s
+    end
+    return result
+end
+
+fun is_digit(c: char) -> bool begin
+    return c >= '0' and c <= '9'
+end
+
+fun is_space(c: char) -> bool begin
+    return c == ' ' or c == '\t' or c == '\n' or c == '\r'
+end
+
+fun to_upper(s: string) -> string begin
+    let result = ""
+    for c in chars(s) do
+        if c >= 'a' and c <= 'z' then
+            set result = result + to_string((c as int - 32) as char)
+        else
+            set result = result + to_string(c)
+        end
+    end
+    return result
+end
+
+fun to_lower(s: string) -> string begin
+    let result = ""
+    for c in chars(s) do
+        if c >= 'A' and c <= 'Z' then
+            set result = result + to_string((c as int + 32) as char)
+        else
+            set result = result + to_string(c)
+        end
+    end
+    return result
+end
+
+fun trim(s: string) -> string begin
+    let (start, end_) = (0, len(s))
+    while start < end_ and is_space(load_char(s, start)) do
+        set start = start + 1
+    end
+    while end_ > start and is_space(load_char(s, end_ - 1)) do
+        set end_ = end_ - 1
+    end
+    return substring(s, start, end_)
+end
+
+fun split(s: string, sep: string) -> [string] begin
+    let parts: [string] = []
+    let (start, i) = (0, 0)
+    while i <= len(s) - len(sep) do
+        if len(sep) > 0 and matches_at(s, sep, i) then
+            push(parts, substring(s, start, i))
+            set i = i + len(sep)
+            set start = i
+        else
+            set i = i + 1
+        end
+    end
+    push(parts, substring(s, start, len(s)))
+    return parts
+end
+
+fun join(parts: [string], sep: string) -> string begin
+    let result = ""
+    for i in 0..len(parts) do
+        if i > 0 then
+            set result = result + sep
+        end
+        set result = result + parts[i]
+    end
+    return result
+end
+
+fun parse_int(s: string) -> int? begin
+    let (value, start, sign) = (0, 0, 1)
+    if len(s) > 0 and load_char(s, 0) == '-' then
+        set start = 1
+        set sign = -1
+    end
+    if start == len(s) then
+        return none
+    end
+
+    for i in start..len(s) do
+        let c = load_char(s, i)
+        if not is_digit(c) then
+            return none
+        end
+        set value = value * 10 + (c as int - '0' as int)
+    end
+    return sign * value
+end
+
+# whether a string holds another string at an index
+fun matches_at(s: string, sub: string, at: int) -> bool begin
+    if at < 0 or at + len(sub) > len(s) then
+        return false
+    end
+
+    for i in 0..len(sub) do
+        if load_char(s, at + i) != load_char(sub, i) then
+            return false
+        end
+    end
+    return true
+end
+
+# lists
+
+fun reverse<T>(xs: [T]) -> [T] begin
+    let result: [T] = []
+    let i = len(xs)
+    while i > 0 do
+        set i = i - 1
+        push(result, xs[i])
+    end
+    return result
+end
+
+fun index_of<T>(xs: [T], x: T) -> int? begin
+    for i in 0..len(xs) do
+        if xs[i] == x then
+            return i
+        end
+    end
+    return none
+end
+
+fun sum(xs: [int]) -> int begin
+    let total = 0
+    for x in xs do
+        set total = total + x
+    end
+    return total
+end
+
+# maps and sets
+
+fun keys<K, V>(m: Map<K, V>) -> [K] begin
+    let result: [K] = []
+    for k in m do
+        push(result, k)
+    end
+    return result
+end
+
+fun values<K, V>(m: Map<K, V>) -> [V] begin
+    let result: [V] = []
+    for k in m do
+        push(result, get(m, k))
+    end
+    return result
+end
+
+fun elements<T>(s: Set<T>) -> [T] begin
+    let result: [T] = []
+    for x in s do
+        push(result, x)
+    end
+    return result
+end
+
+# conversions, which `to_string` calls to convert the builtin collections and optionals
+
+fun list_to_string<T>(xs: [T]) -> string begin
+    let parts: [string] = []
+    for x in xs do
+        push(parts, to_string(x))
+    end
+    return "[" + join(parts, ", ") + "]"
+end
+
+fun set_to_string<T>(s: Set<T>) -> string begin
+    let parts: [string] = []
+    for x in s do
+        push(parts, to_string(x))
+    end
+    return "{{" + join(parts, ", ") + "}}"
+end
+
+fun map_to_string<K, V>(m: Map<K, V>) -> string begin
+    let parts: [string] = []
+    for k in m do
+        push(parts, to_string(k) + ": " + to_string(get(m, k)))
+    end
+    return "{{" + join(parts, ", ") + "}}"
+end
+
+fun optional_to_string<T>(x: T?) -> string begin
+    if x is none then
+        return "none"
+    end
+    return to_string(x)
+end
+
+# collections
+
+# a last-in, first-out stack, whose items past its size are kept to be reused
+obj Stack<T> begin
+    items: [T]
+    size: int
+end
+
+fun stack<T>() -> Stack<T> begin
+    let items: [T] = []
+    return Stack(items, 0)
+end
+
+impl Stack<T> begin
+    fun push(self: Stack<T>, x: T) begin
+        if self.size < len(self.items) then
+            set self.items[self.size] = x
+        else
+            push(self.items, x)
+        end
+        set self.size = self.size + 1
+    end
+
+    fun pop(self: Stack<T>) -> T? begin
+        if self.size == 0 then
+            return none
+        end
+        set self.size = self.size - 1
+        return self.items[self.size]
+    end
+
+    fun peek(self: Stack<T>) -> T? begin
+        if self.size == 0 then
+            return none
+        end
+        return self.items[self.size - 1]
+    end
+
+    fun is_empty(self: Stack<T>) -> bool begin
+        return self.size == 0
+    end
+end
+
+# a first-in, first-out queue, whose items before its head have been dequeued
+obj Queue<T> begin
+    items: [T]
+    head: int
+end
+
+fun queue<T>() -> Queue<T> begin
+    let items: [T] = []
+    return Queue(items, 0)
+end
+
+impl Queue<T> begin
+    fun push(self: Queue<T>, x: T) begin
+        push(self.items, x)
+    end
+
+    fun pop(self: Queue<T>) -> T? begin
+        if self.head == len(self.items) then
+            return none
+        end
+        let x = self.items[self.head]
+        set self.head = self.head + 1
+
+        # the items are dropped once they have all been dequeued
+        if self.head == len(self.items) then
+            let items: [T] = []
+            set self.items = items
+            set self.head = 0
+        end
+        return x
+    end
+
+    fun peek(self: Queue<T>) -> T? begin
+        if self.head == len(self.items) then
+            return none
+        end
+        return self.items[self.head]
+    end
+
+    fun is_empty(self: Queue<T>) -> bool begin
+        return self.head == len(self.items)
+    end
+end
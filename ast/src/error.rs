@@ -26,6 +26,22 @@ impl Debug for Error {
     }
 }
 
+impl Error {
+    /// Adds the name of the module the error occurred in to the error message.
+    pub(crate) fn in_module(self, module: &str) -> Error {
+        match self {
+            Error::Parse(mut e) => {
+                e.msg = format!("{} in module {}", e.msg, module);
+                Error::Parse(e)
+            }
+            Error::Sem(mut e) => {
+                e.msg = format!("{} in module {}", e.msg, module);
+                Error::Sem(e)
+            }
+        }
+    }
+}
+
 /// A parse result.
 pub(crate) type ParseResult<T> = Result<T, Error>;
 
@@ -23,6 +23,8 @@ struct Scanner {
     line: usize,
     /// The current column into the input
     col: usize,
+    /// Whether the input is an expression embedded in a string, whose string literals are not interpolated
+    in_interpolation: bool,
 }
 
 impl Scanner {
@@ -36,6 +38,7 @@ impl Scanner {
             index: 0,
             line: 1,
             col: 1,
+            in_interpolation: false,
         }
     }
 
@@ -55,6 +58,10 @@ impl Scanner {
                     self.scan_identifier_or_keyword_or_operator()
                 } else if self.is_digit() {
                     self.scan_numeral()
+                } else if self.is_quote() {
+                    self.scan_string()
+                } else if self.is_single_quote() {
+                    self.scan_char()
                 } else if self.is_punctuation() || self.is_operator() {
                     self.scan_punctuation_or_operator()
                 } else {
@@ -105,10 +112,11 @@ impl Scanner {
             self.advance();
         }
 
-        if self.char() == '.' {
+        // a dot that is not followed by a digit starts a range, e.g. `0..n`
+        if !self.eof() && self.char() == '.' && !self.is_range_dots() {
             value.push(self.char());
             self.advance();
-            if !self.is_digit() {
+            if self.eof() || !self.is_digit() {
                 return Err(ParseError::error("invalid numeral", Span::new(start, start)));
             }
 
@@ -122,8 +130,227 @@ impl Scanner {
             Ok(Token::new(TokenType::Float(float), Span::new(start, end)))
         } else {
             let end = self.point();
-            let integer: i64 = value.parse().unwrap();
-            Ok(Token::new(TokenType::Integer(integer), Span::new(start, end)))
+            match value.parse::<u64>() {
+                Ok(integer) => Ok(Token::new(TokenType::Integer(integer), Span::new(start, end))),
+                Err(_) => Err(ParseError::error(format!("integer literal {} is out of range", value), Span::new(start, start))),
+            }
+        }
+    }
+
+    /// Scans a string literal and returns the token.
+    ///
+    /// # Arguments
+    /// * `self` - A mutable reference to the scanner.
+    fn scan_string(&mut self) -> ParseResult<Token> {
+        assert!(self.is_quote());
+        let start = self.point();
+        self.advance();
+
+        let mut value = String::new();
+        let mut segments = vec![];
+        while !self.eof() && !self.is_quote() && !self.is_newline() {
+            if self.char() == '\\' {
+                let escape = self.point();
+                self.advance();
+                if self.eof() {
+                    break;
+                }
+
+                value.push(self.scan_escape(escape)?);
+            } else if self.in_interpolation {
+                // the braces of a string in an embedded expression are characters
+                value.push(self.char());
+            } else if self.is_double_brace() {
+                // `{{` and `}}` stand for a brace
+                value.push(self.char());
+                self.advance();
+            } else if self.char() == '{' {
+                segments.push(StrSegment::Text(std::mem::take(&mut value)));
+                segments.push(self.scan_interpolation()?);
+                continue;
+            } else if self.char() == '}' {
+                return Err(ParseError::error("unmatched `}` in string", Span::new(self.point(), self.point())));
+            } else {
+                value.push(self.char());
+            }
+
+            self.advance();
+        }
+
+        if self.eof() || !self.is_quote() {
+            return Err(ParseError::error("unterminated string", Span::new(start, start)));
+        }
+
+        self.advance();
+        let end = self.point();
+        if segments.is_empty() {
+            return Ok(Token::new(TokenType::String(value), Span::new(start, end)));
+        }
+
+        segments.push(StrSegment::Text(value));
+        segments.retain(|s| *s != StrSegment::Text(String::new()));
+        Ok(Token::new(TokenType::InterpString(segments), Span::new(start, end)))
+    }
+
+    /// Scans an expression embedded in a string, as in `{x}` or `{y:.2}`, and returns the segment.
+    /// The expression is lexed where it appears, so that its tokens have their place in the input.
+    /// String and character literals in the expression are skipped over, so that their quotes and braces
+    /// do not end the expression. The strings are not interpolated themselves.
+    ///
+    /// # Arguments
+    /// * `self` - A mutable reference to the scanner.
+    fn scan_interpolation(&mut self) -> ParseResult<StrSegment> {
+        assert!(self.char() == '{');
+        let open = self.point();
+        self.advance();
+
+        let expr_start = (self.index, self.line, self.col);
+        let mut depth = 0;
+        let mut colon = None;
+        while !self.eof() && !self.is_newline() {
+            match self.char() {
+                // a string that is not terminated within the line leaves the expression unterminated
+                '"' => {
+                    if !self.skip_string() {
+                        break;
+                    }
+                    continue;
+                }
+                '\'' => {
+                    self.scan_char()?;
+                    continue;
+                }
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' => depth -= 1,
+                '}' if depth == 0 => break,
+                '}' => depth -= 1,
+                // a single colon at the top level starts the format specifier, while `::` separates a path
+                ':' if depth == 0 && colon.is_none() && self.char_at(self.index + 1) != Some(':') && self.char_at(self.index - 1) != Some(':') => {
+                    colon = Some(self.index)
+                }
+                _ => {}
+            }
+
+            self.advance();
+        }
+
+        if self.eof() || self.char() != '}' {
+            return Err(ParseError::error("unterminated expression in string", Span::new(open, open)));
+        }
+
+        let (start, line, col) = expr_start;
+        let expr_end = colon.unwrap_or(self.index);
+        let precision = match colon {
+            Some(c) => {
+                let spec: String = self.input[c + 1..self.index].iter().collect();
+                match spec.strip_prefix('.').map(|p| p.parse::<usize>()) {
+                    Some(Ok(precision)) => Some(precision),
+                    _ => return Err(ParseError::error("invalid format specifier, expected `.` and a number of decimal places", Span::new(open, open))),
+                }
+            }
+            None => None,
+        };
+
+        let mut scanner = Scanner {
+            input: self.input[start..expr_end].to_vec(),
+            index: 0,
+            line,
+            col,
+            in_interpolation: true,
+        };
+        let mut tokens = scanner.scan()?;
+        if tokens.is_empty() {
+            return Err(ParseError::error("expected an expression in braces", Span::new(open, open)));
+        }
+
+        // the closing brace ends the tokens of the expression, so that the parser does not run past them
+        let close = self.point();
+        self.advance();
+        tokens.push(Token::new(TokenType::Punctuation(Punctuation::CloseBrace), Span::new(close, self.point())));
+        Ok(StrSegment::Expr(tokens, precision))
+    }
+
+    /// Skips over a string literal in an expression embedded in a string, up to and including its closing quote.
+    /// Returns whether the string is terminated within the line.
+    ///
+    /// # Arguments
+    /// * `self` - A mutable reference to the scanner.
+    fn skip_string(&mut self) -> bool {
+        assert!(self.is_quote());
+        self.advance();
+        while !self.eof() && !self.is_quote() && !self.is_newline() {
+            // an escaped quote does not end the string
+            if self.char() == '\\' {
+                self.advance();
+                if self.eof() || self.is_newline() {
+                    break;
+                }
+            }
+
+            self.advance();
+        }
+
+        if self.eof() || !self.is_quote() {
+            return false;
+        }
+
+        self.advance();
+        true
+    }
+
+    /// Scans a character literal, such as `'a'` or `'\n'`, and returns the token.
+    /// Characters are single bytes, so only ascii characters can be written.
+    ///
+    /// # Arguments
+    /// * `self` - A mutable reference to the scanner.
+    fn scan_char(&mut self) -> ParseResult<Token> {
+        assert!(self.is_single_quote());
+        let start = self.point();
+        self.advance();
+        if self.eof() || self.is_single_quote() || self.is_newline() {
+            return Err(ParseError::error("invalid character literal", Span::new(start, start)));
+        }
+
+        let value = if self.char() == '\\' {
+            let escape = self.point();
+            self.advance();
+            if self.eof() {
+                return Err(ParseError::error("unterminated character literal", Span::new(start, start)));
+            }
+
+            self.scan_escape(escape)?
+        } else {
+            self.char()
+        };
+        if !value.is_ascii() {
+            return Err(ParseError::error("character literals must be ascii", Span::new(start, start)));
+        }
+
+        self.advance();
+        if self.eof() || !self.is_single_quote() {
+            return Err(ParseError::error("unterminated character literal", Span::new(start, start)));
+        }
+
+        self.advance();
+        let end = self.point();
+        Ok(Token::new(TokenType::Char(value), Span::new(start, end)))
+    }
+
+    /// Gets the character that the escape sequence at the current char stands for.
+    ///
+    /// # Arguments
+    /// * `self` - A reference to the scanner.
+    /// * `escape` - The point of the backslash that starts the escape sequence.
+    fn scan_escape(&self, escape: Point) -> ParseResult<char> {
+        match self.char() {
+            'n' => Ok('\n'),
+            't' => Ok('\t'),
+            'r' => Ok('\r'),
+            '0' => Ok('\0'),
+            '\\' => Ok('\\'),
+            '"' => Ok('"'),
+            '\'' => Ok('\''),
+            _ => Err(ParseError::error("invalid escape sequence", Span::new(escape, escape))),
         }
     }
 
@@ -241,6 +468,47 @@ impl Scanner {
         self.char().is_whitespace()
     }
 
+    /// Returns a value indicating whether the current char is a double quote.
+    ///
+    /// # Arguments
+    /// * `self` - A reference to the scanner.
+    fn is_quote(&self) -> bool {
+        self.char() == '"'
+    }
+
+    /// Returns a value indicating whether the current chars are a doubled brace, as in `{{`.
+    ///
+    /// # Arguments
+    /// * `self` - A reference to the scanner.
+    fn is_double_brace(&self) -> bool {
+        matches!(self.char(), '{' | '}') && self.char_at(self.index + 1) == Some(self.char())
+    }
+
+    /// Returns the char at an index into the input, if there is one.
+    ///
+    /// # Arguments
+    /// * `self` - A reference to the scanner.
+    /// * `index` - The index of the char.
+    fn char_at(&self, index: usize) -> Option<char> {
+        self.input.get(index).copied()
+    }
+
+    /// Returns a value indicating whether the current char is a single quote.
+    ///
+    /// # Arguments
+    /// * `self` - A reference to the scanner.
+    fn is_single_quote(&self) -> bool {
+        self.char() == '\''
+    }
+
+    /// Returns a value indicating whether the current chars are the dots of a range.
+    ///
+    /// # Arguments
+    /// * `self` - A reference to the scanner.
+    fn is_range_dots(&self) -> bool {
+        self.index + 1 < self.input.len() && self.slice(2) == ".."
+    }
+
     /// Returns a value indicating whether the current char is a comment.
     ///
     /// # Arguments
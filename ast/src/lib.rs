@@ -2,37 +2,45 @@ pub mod lex;
 pub mod parse;
 mod ast;
 mod operator;
+mod builtin;
 mod token;
 mod error;
 mod symbol;
 mod sem;
 
+use std::path::Path;
+
 pub use ast::*;
 pub use operator::*;
+pub use builtin::*;
 pub use symbol::*;
 pub use token::*;
 pub use error::*;
 
-/// Parses a Pine input program into an AST. Returns the annotated AST.
+/// The name of the core module, which is written in Pine and implicitly imported into every program.
+pub const CORE_MODULE: &str = "core";
+
+/// Parses a Pine program into an AST, starting at the root module. Returns the annotated AST.
+///
+/// Imported modules are located relative to the module that imports them,
+/// either as `foo.p` or as the directory module `foo/mod.p`.
+/// The core module is part of every program, and its exports can be used in every module.
 /// 
 /// # Arguments
-/// - `input` - the Pine input.
+/// - `path` - the path of the root module.
 /// 
 /// # Returns
 /// The parse result containing the program AST if successful.
 /// 
 /// # Examples
+/// ```no_run
+/// let program = ast::parse("main.p").unwrap();
 /// ```
-/// let input = "fun main() begin end";
-/// let program = ast::parse(input).unwrap();
-/// ```
-pub fn parse<T>(input: T) -> Result<Program, Error>
-where T: Into<String> {
-    let main_module = parse_module(input)?;
-    
-    let mut program = Program::new(Box::new(main_module));
+pub fn parse<P>(path: P) -> Result<Program, Error>
+where P: AsRef<Path> {
+    let modules = sem::modresv::resolve_modules(path.as_ref())?;
     
-    // let modules = sem::modresv::resolve_modules(&main_module);
+    let mut program = Program::new(modules);
 
     // check function returns
     sem::ret::check(&mut program)?;
@@ -44,6 +52,9 @@ where T: Into<String> {
     // annotate the AST with types
     sem::typing::global(&mut program)?;
     sem::typing::local(&mut program)?;
+
+    // check that matches are exhaustive
+    sem::matching::check(&program)?;
     
     Ok(program)
 }
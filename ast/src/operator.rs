@@ -150,11 +150,21 @@ impl Operator {
             | Operator::LessThan
             | Operator::GreaterThanOrEqual
             | Operator::LessThanOrEqual => {
-                // TODO also need to ensure operator is defined for type
                 if lhs != rhs {
                     return Err(format!("Operands of `{}` must have the same type", self));
                 }
 
+                let defined = match self {
+                    Operator::Equals | Operator::NotEquals => lhs.is_number() || matches!(
+                        lhs,
+                        PineType::Char | PineType::Bool | PineType::String
+                    ),
+                    _ => lhs.is_number() || matches!(lhs, PineType::Char | PineType::String),
+                };
+                if !defined {
+                    return Err(format!("Operator `{}` is not defined for type {}", self, lhs));
+                }
+
                 Ok(PineType::Bool)
             }
             Operator::And | Operator::Or => {
@@ -174,6 +184,14 @@ impl Operator {
                     return Err(format!("Operands of `{}` must have the same type", self));
                 }
 
+                let defined = match self {
+                    Operator::Add => lhs.is_number() || lhs == PineType::String,
+                    _ => lhs.is_number(),
+                };
+                if !defined {
+                    return Err(format!("Operator `{}` is not defined for type {}", self, lhs));
+                }
+
                 Ok(lhs)
             }
             _ => unimplemented!(),
@@ -194,14 +212,54 @@ impl Operator {
 
                 Ok(PineType::Bool)
             }
-            Operator::Subtract => {
-                // TODO ensure operator is defined for type
-                Ok(t)
-            }
+            Operator::Subtract => match t {
+                PineType::Integer | PineType::Float => Ok(t),
+                t => Err(format!("Operator `{}` is not defined for type {}", self, t)),
+            },
             _ => unimplemented!(),
         }
     }
 
+    /// Gets the name of the method that overloads the binary operator for an object type.
+    /// `!=` is the negation of `eq`, and the ordering operators are derived from `lt`.
+    pub fn binary_method(&self) -> Option<&'static str> {
+        match self {
+            Operator::Equals | Operator::NotEquals => Some("eq"),
+            Operator::GreaterThan
+            | Operator::LessThan
+            | Operator::GreaterThanOrEqual
+            | Operator::LessThanOrEqual => Some("lt"),
+            Operator::Add => Some("add"),
+            Operator::Subtract => Some("sub"),
+            Operator::Multiply => Some("mul"),
+            Operator::Divide => Some("div"),
+            Operator::Power => Some("pow"),
+            Operator::Modulo => Some("mod"),
+            Operator::And | Operator::Or | Operator::Not => None,
+        }
+    }
+
+    /// Gets the name of the method that overloads the unary operator for an object type.
+    pub fn unary_method(&self) -> Option<&'static str> {
+        match self {
+            Operator::Subtract => Some("neg"),
+            _ => None,
+        }
+    }
+
+    /// Determines if the operator compares its operands, resulting in a bool.
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Operator::Equals
+                | Operator::NotEquals
+                | Operator::GreaterThan
+                | Operator::LessThan
+                | Operator::GreaterThanOrEqual
+                | Operator::LessThanOrEqual
+        )
+    }
+
     /// Determines if the operator is unary.
     pub fn is_unary(&self) -> bool {
         self.get_bool("IsUnary").unwrap()
@@ -1,4 +1,5 @@
 use crate::ast::*;
+use crate::builtin::Builtin;
 use crate::operator::Operator;
 use crate::token::*;
 use std::fmt::Debug;
@@ -9,6 +10,9 @@ pub fn parse(tokens: Vec<Token>) -> ParseResult<Module> {
     parser.parse()
 }
 
+/// The identifier, optional type annotation and expression of a module-level binding.
+type Binding = (Box<Ident>, Option<Box<Ty>>, Box<Expr>);
+
 /// Represents the Pine parser
 struct Parser {
     /// The vector of tokens representing the Pine program
@@ -25,14 +29,42 @@ impl Parser {
     /// Parses the input.
     pub fn parse(&mut self) -> ParseResult<Module> {
         let mut functions = vec![];
+        let mut objs = vec![];
+        let mut enums = vec![];
+        let mut interfaces = vec![];
+        let mut impls = vec![];
         let mut imports = vec![];
+        let mut exports = vec![];
+        let mut consts = vec![];
+        let mut globals = vec![];
         while !self.eof() {
             if self.matches(Keyword::Fun) {
                 let function = self.parse_function()?;
                 functions.push(function);
+            } else if self.matches(Keyword::Obj) {
+                let obj = self.parse_obj()?;
+                objs.push(obj);
+            } else if self.matches(Keyword::Enum) {
+                let enum_decl = self.parse_enum()?;
+                enums.push(enum_decl);
+            } else if self.matches(Keyword::Interface) {
+                let interface = self.parse_interface()?;
+                interfaces.push(interface);
+            } else if self.matches(Keyword::Impl) {
+                let impl_block = self.parse_impl()?;
+                impls.push(impl_block);
             } else if self.matches(Keyword::Import) {
                 let import = self.parse_import()?;
                 imports.push(import);
+            } else if self.matches(Keyword::Export) {
+                let export = self.parse_export()?;
+                exports.push(export);
+            } else if self.matches(Keyword::Const) {
+                let const_decl = self.parse_const()?;
+                consts.push(const_decl);
+            } else if self.matches(Keyword::Let) {
+                let global = self.parse_global()?;
+                globals.push(global);
             } else {
                 Err(ParseError::error("expected function", self.span()))?
             }
@@ -43,13 +75,136 @@ impl Parser {
         } else {
             Span::default()
         };
-        Ok(Module::new(imports, functions, span))
+        Ok(Module::new(imports, exports, objs, enums, interfaces, impls, consts, globals, functions, span))
+    }
+
+    /// Parses an object declaration.
+    fn parse_obj(&mut self) -> ParseResult<Obj> {
+        let obj = self.match_token(Keyword::Obj)?;
+        let identifier = self.parse_identifier()?;
+        let type_params = self.parse_type_params()?;
+        self.match_token(Keyword::Begin)?;
+        let mut fields = vec![];
+        while self.matches(TokenTypeMatch::Identifier) {
+            let field = self.parse_field()?;
+            fields.push(field);
+        }
+
+        let end = self.match_token(Keyword::End)?;
+        let span = obj.span + end.span;
+        Ok(Obj::new(Box::new(identifier), type_params, fields, span))
+    }
+
+    /// Parses an object field.
+    fn parse_field(&mut self) -> ParseResult<Field> {
+        let identifier = self.parse_identifier()?;
+        self.match_token(Punctuation::Colon)?;
+        let type_node = self.parse_type()?;
+        let span = identifier.span() + type_node.span();
+        Ok(Field::new(Box::new(identifier), Box::new(type_node), span))
+    }
+
+    /// Parses an enum declaration.
+    fn parse_enum(&mut self) -> ParseResult<Enum> {
+        let enum_token = self.match_token(Keyword::Enum)?;
+        let identifier = self.parse_identifier()?;
+        let type_params = self.parse_type_params()?;
+        self.match_token(Keyword::Begin)?;
+        let mut variants = vec![];
+        while self.matches(TokenTypeMatch::Identifier) {
+            let variant = self.parse_variant()?;
+            variants.push(variant);
+        }
+
+        let end = self.match_token(Keyword::End)?;
+        let span = enum_token.span + end.span;
+        Ok(Enum::new(Box::new(identifier), type_params, variants, span))
+    }
+
+    /// Parses an enum variant and the types of its payload.
+    fn parse_variant(&mut self) -> ParseResult<Variant> {
+        let identifier = self.parse_identifier()?;
+        let mut payload = vec![];
+        let mut span = identifier.span();
+        if self.matches(Punctuation::OpenParen) {
+            self.match_token(Punctuation::OpenParen)?;
+            while !self.matches(Punctuation::CloseParen) {
+                payload.push(self.parse_type()?);
+                if !self.matches(Punctuation::Comma) {
+                    break;
+                }
+
+                self.match_token(Punctuation::Comma)?;
+            }
+
+            let close = self.match_token(Punctuation::CloseParen)?;
+            span = span + close.span;
+        }
+
+        Ok(Variant::new(Box::new(identifier), payload, span))
+    }
+
+    /// Parses an interface declaration.
+    fn parse_interface(&mut self) -> ParseResult<Interface> {
+        let interface = self.match_token(Keyword::Interface)?;
+        let identifier = self.parse_identifier()?;
+        self.match_token(Keyword::Begin)?;
+        let mut methods = vec![];
+        while self.matches(Keyword::Fun) {
+            let method = self.parse_method_sig()?;
+            methods.push(method);
+        }
+
+        let end = self.match_token(Keyword::End)?;
+        let span = interface.span + end.span;
+        Ok(Interface::new(Box::new(identifier), methods, span))
+    }
+
+    /// Parses the signature of an interface method.
+    fn parse_method_sig(&mut self) -> ParseResult<MethodSig> {
+        let fun = self.match_token(Keyword::Fun)?;
+        let identifier = self.parse_identifier()?;
+        let params = self.parse_params()?;
+        let (return_type, span) = if self.matches(Punctuation::Arrow) {
+            self.match_token(Punctuation::Arrow)?;
+            let return_type = self.parse_type()?;
+            let span = fun.span + return_type.span();
+            (Some(Box::new(return_type)), span)
+        } else {
+            (None, fun.span + identifier.span())
+        };
+
+        Ok(MethodSig::new(Box::new(identifier), params, return_type, span))
+    }
+
+    /// Parses the implementation of an interface, or the methods of an object.
+    fn parse_impl(&mut self) -> ParseResult<Impl> {
+        let impl_token = self.match_token(Keyword::Impl)?;
+        let ty = self.parse_type()?;
+        let (iface, obj) = if self.matches(Keyword::For) {
+            self.match_token(Keyword::For)?;
+            (Some(Box::new(ty)), self.parse_type()?)
+        } else {
+            (None, ty)
+        };
+
+        self.match_token(Keyword::Begin)?;
+        let mut funs = vec![];
+        while self.matches(Keyword::Fun) {
+            let fun = self.parse_function()?;
+            funs.push(fun);
+        }
+
+        let end = self.match_token(Keyword::End)?;
+        let span = impl_token.span + end.span;
+        Ok(Impl::new(iface, Box::new(obj), funs, span))
     }
 
     /// Parses a function.
     fn parse_function(&mut self) -> ParseResult<Fun> {
         let fun = self.match_token(Keyword::Fun)?;
         let identifier = self.parse_identifier()?;
+        let type_params = self.parse_type_params()?;
         let params = self.parse_params()?;
 
         // compute the type of the function
@@ -67,6 +222,7 @@ impl Parser {
         let span = fun.span + body.span();
         Ok(Fun::new(
             Box::new(identifier),
+            type_params,
             params,
             return_type,
             Box::new(body),
@@ -74,6 +230,27 @@ impl Parser {
         ))
     }
 
+    /// Parses the type parameters of a generic declaration, if there are any.
+    fn parse_type_params(&mut self) -> ParseResult<Vec<Ident>> {
+        let mut type_params = vec![];
+        if !self.matches(Operator::LessThan) {
+            return Ok(type_params);
+        }
+
+        self.match_token(Operator::LessThan)?;
+        loop {
+            type_params.push(self.parse_identifier()?);
+            if !self.matches(Punctuation::Comma) {
+                break;
+            }
+
+            self.match_token(Punctuation::Comma)?;
+        }
+
+        self.match_token(Operator::GreaterThan)?;
+        Ok(type_params)
+    }
+
     /// Parses the function params.
     fn parse_params(&mut self) -> ParseResult<Vec<Param>> {
         self.match_token(Punctuation::OpenParen)?;
@@ -95,10 +272,15 @@ impl Parser {
     /// Parses a function param.
     fn parse_param(&mut self) -> ParseResult<Param> {
         let identifier = self.parse_identifier()?;
+        if !self.matches(Punctuation::Colon) {
+            let span = identifier.span();
+            return Ok(Param::new(Box::new(identifier), None, span));
+        }
+
         self.match_token(Punctuation::Colon)?;
         let type_node = self.parse_type()?;
         let span = identifier.span() + type_node.span();
-        Ok(Param::new(Box::new(identifier), Box::new(type_node), span))
+        Ok(Param::new(Box::new(identifier), Some(Box::new(type_node)), span))
     }
     
     fn parse_import(&mut self) -> ParseResult<Import> {
@@ -107,6 +289,19 @@ impl Parser {
         let span = import.span + ident.span();
         Ok(Import::new(Box::new(ident), span))
     }
+
+    /// Parses an export list.
+    fn parse_export(&mut self) -> ParseResult<Export> {
+        let export = self.match_token(Keyword::Export)?;
+        let mut idents = vec![self.parse_identifier()?];
+        while self.matches(Punctuation::Comma) {
+            self.match_token(Punctuation::Comma)?;
+            idents.push(self.parse_identifier()?);
+        }
+
+        let span = export.span + idents.last().unwrap().span();
+        Ok(Export::new(idents, span))
+    }
     
     /// Parses an identifier.
     fn parse_identifier(&mut self) -> ParseResult<Ident> {
@@ -135,7 +330,9 @@ impl Parser {
 
     /// Parses a statement.
     fn parse_statement(&mut self) -> ParseResult<Stmt> {
-        if self.matches(Keyword::Let) {
+        if self.matches(Keyword::Let) && self.matches_next(Punctuation::OpenParen) {
+            Ok(Stmt::LetTuple(self.parse_let_tuple()?))
+        } else if self.matches(Keyword::Let) {
             Ok(Stmt::Let(self.parse_let()?))
         } else if self.matches(Keyword::Set) {
             Ok(Stmt::Set(self.parse_set()?))
@@ -143,15 +340,35 @@ impl Parser {
             Ok(Stmt::If(self.parse_if()?))
         } else if self.matches(Keyword::While) {
             Ok(Stmt::While(self.parse_while()?))
+        } else if self.matches(Keyword::For) {
+            Ok(Stmt::For(self.parse_for()?))
         } else if self.matches(Keyword::Return) {
             Ok(Stmt::Return(self.parse_return()?))
+        } else if self.matches(Keyword::Break) {
+            let token = self.match_token(Keyword::Break)?;
+            Ok(Stmt::Break(BreakStmt::new(token.span)))
+        } else if self.matches(Keyword::Continue) {
+            let token = self.match_token(Keyword::Continue)?;
+            Ok(Stmt::Continue(ContinueStmt::new(token.span)))
         } else if self.matches(Keyword::Begin) {
             self.match_token(Keyword::Begin)?;
             let block = self.parse_block()?;
             self.match_token(Keyword::End)?;
             Ok(Stmt::Block(block))
+        } else if self.matches(Keyword::Match) {
+            let expr = Expr::Match(self.parse_match(true)?);
+            let span = expr.span();
+            Ok(Stmt::Expr(ExprStmt::new(Box::new(expr), span)))
         } else if self.matches_expression() {
             let expr = self.parse_expression()?;
+            if self.matches_compound_assign() {
+                // a compound assignment does not need to be introduced by `set`
+                let op = Some(self.parse_compound_assign()?);
+                let rhs = self.parse_expression()?;
+                let span = expr.span() + rhs.span();
+                return Ok(Stmt::Set(SetStmt::new(Box::new(expr), op, Box::new(rhs), span)));
+            }
+
             let span = expr.span();
             Ok(Stmt::Expr(ExprStmt::new(Box::new(expr), span)))
         } else {
@@ -180,19 +397,88 @@ impl Parser {
         ))
     }
 
+    /// Parses a constant declaration.
+    fn parse_const(&mut self) -> ParseResult<Const> {
+        let const_token = self.match_token(Keyword::Const)?;
+        let (identifier, type_node, expression) = self.parse_binding()?;
+        let span = const_token.span + expression.span();
+        Ok(Const::new(identifier, type_node, expression, span))
+    }
+
+    /// Parses a module-level let binding.
+    fn parse_global(&mut self) -> ParseResult<Global> {
+        let let_token = self.match_token(Keyword::Let)?;
+        let (identifier, type_node, expression) = self.parse_binding()?;
+        let span = let_token.span + expression.span();
+        Ok(Global::new(identifier, type_node, expression, span))
+    }
+
+    /// Parses the identifier, optional type annotation and expression of a module-level binding.
+    fn parse_binding(&mut self) -> ParseResult<Binding> {
+        let identifier = self.parse_identifier()?;
+        let type_node = if self.matches(Punctuation::Colon) {
+            self.match_token(Punctuation::Colon)?;
+            Some(Box::new(self.parse_type()?))
+        } else {
+            None
+        };
+
+        self.match_token(Punctuation::EqualSign)?;
+        let expression = self.parse_expression()?;
+        Ok((Box::new(identifier), type_node, Box::new(expression)))
+    }
+
+    /// Parses a let statement that destructures a tuple.
+    fn parse_let_tuple(&mut self) -> ParseResult<LetTupleStmt> {
+        let let_token = self.match_token(Keyword::Let)?;
+        let pattern = self.parse_pattern()?;
+        let type_node = if self.matches(Punctuation::Colon) {
+            self.match_token(Punctuation::Colon)?;
+            let type_node = self.parse_type()?;
+            Some(Box::new(type_node))
+        } else {
+            None
+        };
+
+        self.match_token(Punctuation::EqualSign)?;
+        let expression = self.parse_expression()?;
+        let span = let_token.span + expression.span();
+        Ok(LetTupleStmt::new(
+            Box::new(pattern), type_node, Box::new(expression),
+            span,
+        ))
+    }
+
     /// Parses a set statement.
     fn parse_set(&mut self) -> ParseResult<SetStmt> {
         let let_token = self.match_token(Keyword::Set)?;
-        let identifier = self.parse_identifier()?;
-        self.match_token(Punctuation::EqualSign)?;
+        let target = self.parse_expression_term()?;
+        let op = if self.matches_compound_assign() {
+            Some(self.parse_compound_assign()?)
+        } else {
+            self.match_token(Punctuation::EqualSign)?;
+            None
+        };
+
         let expression = self.parse_expression()?;
         let span = let_token.span + expression.span();
         Ok(SetStmt::new(
-            Box::new(identifier), Box::new(expression),
+            Box::new(target), op, Box::new(expression),
             span,
         ))
     }
 
+    /// Parses the operator of a compound assignment, such as `+=`.
+    fn parse_compound_assign(&mut self) -> ParseResult<Operator> {
+        match self.token_type() {
+            TokenType::Punctuation(p) if p.compound_op().is_some() => {
+                self.match_token(p)?;
+                Ok(p.compound_op().unwrap())
+            }
+            _ => Err(ParseError::error("expected compound assignment", self.span())),
+        }
+    }
+
     /// Parses an if statement.
     fn parse_if(&mut self) -> ParseResult<IfStmt> {
         let if_token = self.match_token(Keyword::If)?;
@@ -240,6 +526,22 @@ impl Parser {
         ))
     }
 
+    /// Parses a for statement.
+    fn parse_for(&mut self) -> ParseResult<ForStmt> {
+        let for_token = self.match_token(Keyword::For)?;
+        let identifier = self.parse_identifier()?;
+        self.match_token(Keyword::In)?;
+        let iter = self.parse_expression()?;
+        self.match_token(Keyword::Do)?;
+        let body = self.parse_block()?;
+        let end = self.match_token(Keyword::End)?;
+        let span = for_token.span + end.span;
+        Ok(ForStmt::new(
+            Box::new(identifier), Box::new(iter), Box::new(body),
+            span,
+        ))
+    }
+
     /// Parses a return statement.
     fn parse_return(&mut self) -> ParseResult<ReturnStmt> {
         let ret = self.match_token(Keyword::Return)?;
@@ -254,15 +556,140 @@ impl Parser {
         Ok(ReturnStmt::new(expression, span))
     }
 
+    /// Parses a match. The arms of a match statement may be blocks, as in `case P then ...`,
+    /// while the arms of a match expression are expressions, as in `case P => e`.
+    fn parse_match(&mut self, is_stmt: bool) -> ParseResult<MatchExpr> {
+        let match_token = self.match_token(Keyword::Match)?;
+        let expr = self.parse_expression()?;
+        self.match_token(Keyword::Begin)?;
+        let mut arms = vec![];
+        while self.matches(Keyword::Case) {
+            let case = self.match_token(Keyword::Case)?;
+            let pattern = self.parse_pattern()?;
+            let (body, span) = if is_stmt && self.matches(Keyword::Then) {
+                self.match_token(Keyword::Then)?;
+                let block = self.parse_block()?;
+                let span = case.span + block.span();
+                (ArmBody::Block(Box::new(block)), span)
+            } else {
+                self.match_token(Punctuation::FatArrow)?;
+                let expr = self.parse_expression()?;
+                let span = case.span + expr.span();
+                (ArmBody::Expr(Box::new(expr)), span)
+            };
+
+            arms.push(MatchArm::new(Box::new(pattern), body, span));
+        }
+
+        let end = self.match_token(Keyword::End)?;
+        let span = match_token.span + end.span;
+        Ok(MatchExpr::new(Box::new(expr), arms, is_stmt, span))
+    }
+
+    /// Parses a pattern.
+    fn parse_pattern(&mut self) -> ParseResult<Pattern> {
+        if self.matches(Punctuation::Underscore) {
+            let token = self.match_token(Punctuation::Underscore)?;
+            Ok(Pattern::Wildcard(WildcardPattern::new(token.span)))
+        } else if self.matches(TokenTypeMatch::Identifier) {
+            let variant = self.parse_identifier_expression()?;
+            if variant.path.is_empty() && !self.matches(Punctuation::OpenParen) {
+                // a bare identifier is a binding, unless scoping finds that it names a variant
+                let span = variant.span();
+                return Ok(Pattern::Binding(BindingPattern::new(variant.ident, span)));
+            }
+
+            let mut args = vec![];
+            let mut span = variant.span();
+            if self.matches(Punctuation::OpenParen) {
+                self.match_token(Punctuation::OpenParen)?;
+                while !self.matches(Punctuation::CloseParen) {
+                    args.push(self.parse_pattern()?);
+                    if !self.matches(Punctuation::Comma) {
+                        break;
+                    }
+
+                    self.match_token(Punctuation::Comma)?;
+                }
+
+                let close = self.match_token(Punctuation::CloseParen)?;
+                span = span + close.span;
+            }
+
+            Ok(Pattern::Variant(VariantPattern::new(Box::new(variant), args, span)))
+        } else if self.matches(Punctuation::OpenParen) {
+            // a single parenthesized pattern is not a tuple pattern
+            let open = self.match_token(Punctuation::OpenParen)?;
+            let mut elems = vec![self.parse_pattern()?];
+            if !self.matches(Punctuation::Comma) {
+                self.match_token(Punctuation::CloseParen)?;
+                return Ok(elems.remove(0));
+            }
+
+            while self.matches(Punctuation::Comma) {
+                self.match_token(Punctuation::Comma)?;
+                elems.push(self.parse_pattern()?);
+            }
+
+            let close = self.match_token(Punctuation::CloseParen)?;
+            Ok(Pattern::Tuple(TuplePattern::new(elems, open.span + close.span)))
+        } else if self.matches(Operator::Subtract) {
+            // negative literals are folded into the literal
+            let minus = self.match_token(Operator::Subtract)?;
+            let expr = match self.parse_literal_pattern()? {
+                Expr::IntLit(e) => Expr::IntLit(IntLitExpr::new(-e.value, minus.span + e.span())),
+                Expr::FloatLit(e) => Expr::FloatLit(FloatLitExpr::new(-e.value, minus.span + e.span())),
+                _ => return Err(ParseError::error("invalid pattern", minus.span)),
+            };
+            let span = expr.span();
+            Ok(Pattern::Lit(LitPattern::new(Box::new(expr), span)))
+        } else {
+            let expr = self.parse_literal_pattern()?;
+            let span = expr.span();
+            Ok(Pattern::Lit(LitPattern::new(Box::new(expr), span)))
+        }
+    }
+
+    /// Parses the literal of a literal pattern.
+    fn parse_literal_pattern(&mut self) -> ParseResult<Expr> {
+        if self.matches(TokenTypeMatch::Integer) {
+            Ok(Expr::IntLit(self.parse_integer()?))
+        } else if self.matches(TokenTypeMatch::Float) {
+            Ok(Expr::FloatLit(self.parse_float()?))
+        } else if self.matches(TokenTypeMatch::Char) {
+            Ok(Expr::CharLit(self.parse_char()?))
+        } else if self.matches_any(vec![Keyword::True, Keyword::False]) {
+            Ok(Expr::BoolLit(self.parse_bool()?))
+        } else if self.matches(TokenTypeMatch::String) {
+            Ok(Expr::StringLit(self.parse_string()?))
+        } else {
+            Err(ParseError::error("invalid pattern", self.span()))
+        }
+    }
+
     /// Parses an expression.
     fn parse_expression(&mut self) -> ParseResult<Expr> {
-        self.parse_expression_by_precedence(Operator::max_precedence())
+        let expr = self.parse_expression_by_precedence(Operator::max_precedence())?;
+        if self.matches_any(vec![Punctuation::DotDot, Punctuation::DotDotEqual]) {
+            return Ok(Expr::Range(self.parse_range(expr)?));
+        }
+
+        Ok(expr)
+    }
+
+    /// Parses a range expression, given the start of the range.
+    fn parse_range(&mut self, start: Expr) -> ParseResult<RangeExpr> {
+        let inclusive = self.matches(Punctuation::DotDotEqual);
+        self.match_any(vec![Punctuation::DotDot, Punctuation::DotDotEqual])?;
+        let end = self.parse_expression_by_precedence(Operator::max_precedence())?;
+        let span = start.span() + end.span();
+        Ok(RangeExpr::new(Box::new(start), Box::new(end), inclusive, span))
     }
 
     /// Parses an expression by precedence.
     fn parse_expression_by_precedence(&mut self, precedence: i32) -> ParseResult<Expr> {
         if precedence < Operator::min_precedence() {
-            self.parse_expression_term()
+            self.parse_cast_expression()
         } else {
             let mut expr = self.parse_expression_by_precedence(precedence - 1)?;
             while self.matches_any(Operator::binary_ops_by_precedence(precedence)) {
@@ -283,6 +710,22 @@ impl Parser {
         }
     }
 
+    /// Parses an expression term that may be cast, as in `x as float`.
+    /// A cast binds more loosely than a unary operator, so `-1 as uint` casts `-1`.
+    fn parse_cast_expression(&mut self) -> ParseResult<Expr> {
+        let mut expr = self.parse_expression_term()?;
+        while self.matches(Keyword::As) {
+            self.match_token(Keyword::As)?;
+            let target = self.parse_type()?;
+            let span = expr.span() + target.span();
+            let mut cast = CastExpr::new(Box::new(expr), span);
+            cast.target = Some(Box::new(target));
+            expr = Expr::Cast(cast);
+        }
+
+        Ok(expr)
+    }
+
     /// Parses an expression term.
     fn parse_expression_term(&mut self) -> ParseResult<Expr> {
         let mut expr = if self.matches(TokenTypeMatch::Identifier) {
@@ -291,27 +734,95 @@ impl Parser {
             Ok(Expr::IntLit(self.parse_integer()?))
         } else if self.matches(TokenTypeMatch::Float) {
             Ok(Expr::FloatLit(self.parse_float()?))
+        } else if self.matches(TokenTypeMatch::Char) {
+            Ok(Expr::CharLit(self.parse_char()?))
         } else if self.matches_any(vec![Keyword::True, Keyword::False]) {
             Ok(Expr::BoolLit(self.parse_bool()?))
         } else if self.matches(TokenTypeMatch::String) {
             Ok(Expr::StringLit(self.parse_string()?))
+        } else if self.matches(TokenTypeMatch::InterpString) {
+            Ok(Expr::InterpString(self.parse_interp_string()?))
+        } else if self.matches(Keyword::None) {
+            let token = self.match_token(Keyword::None)?;
+            Ok(Expr::NoneLit(NoneLitExpr::new(token.span)))
         } else if self.matches_any(Operator::all_unary_ops()) {
             Ok(Expr::Unary(self.parse_unary_expression()?))
         } else if self.matches(Punctuation::OpenParen) {
             self.parse_parenthesized_expression()
+        } else if self.matches(Punctuation::OpenBracket) {
+            Ok(Expr::ListLit(self.parse_list_literal()?))
+        } else if self.matches(Punctuation::OpenBrace) {
+            self.parse_map_or_set_literal()
+        } else if self.matches(Keyword::Match) {
+            Ok(Expr::Match(self.parse_match(false)?))
+        } else if self.matches(Keyword::Fun) {
+            Ok(Expr::Lambda(self.parse_lambda()?))
         } else {
             Err(ParseError::error("invalid expression", self.span()))
         }?;
 
-        // check for function call or indexing expression
-        if self.matches(Punctuation::OpenParen) {
-            let (args, span) = self.parse_function_call_args()?;
-            expr = Expr::Call(CallExpr::new(Box::new(expr), args, span));
-        } // TODO index typed_ast
+        // check for function call, indexing, field or error propagation expressions
+        while self.matches_any(vec![Punctuation::OpenParen, Punctuation::OpenBracket, Punctuation::Dot, Punctuation::QuestionMark]) {
+            if self.matches(Punctuation::QuestionMark) {
+                let question = self.match_token(Punctuation::QuestionMark)?;
+                let span = expr.span() + question.span;
+                expr = Expr::Try(TryExpr::new(Box::new(expr), span));
+            } else if self.matches(Punctuation::Dot) {
+                self.match_token(Punctuation::Dot)?;
+                let field = self.parse_identifier()?;
+                let span = expr.span() + field.span();
+                expr = Expr::Field(FieldExpr::new(Box::new(expr), Box::new(field), span));
+            } else if self.matches(Punctuation::OpenParen) {
+                let (args, span) = self.parse_function_call_args()?;
+                let span = expr.span() + span;
+                expr = Expr::Call(CallExpr::new(Box::new(expr), args, span));
+            } else {
+                self.match_token(Punctuation::OpenBracket)?;
+                let index = self.parse_expression()?;
+                let close = self.match_token(Punctuation::CloseBracket)?;
+                let span = expr.span() + close.span;
+                expr = Expr::Index(IndexExpr::new(Box::new(expr), Box::new(index), span));
+            }
+        }
+
+        // check for a check of an optional, as in `x is some`
+        if self.matches(Keyword::Is) {
+            self.match_token(Keyword::Is)?;
+            let some = self.matches(Keyword::Some);
+            let token = self.match_any(vec![Keyword::Some, Keyword::None])?;
+            let span = expr.span() + token.span;
+            expr = Expr::Is(IsExpr::new(Box::new(expr), some, span));
+        }
 
         Ok(expr)
     }
 
+    /// Parses a lambda. The body of a lambda is either an expression, as in `fun(x) => x * 2`,
+    /// or a block, as in `fun(x) begin ... end`.
+    fn parse_lambda(&mut self) -> ParseResult<LambdaExpr> {
+        let fun = self.match_token(Keyword::Fun)?;
+        let params = self.parse_params()?;
+        let return_type = if self.matches(Punctuation::Arrow) {
+            self.match_token(Punctuation::Arrow)?;
+            Some(Box::new(self.parse_type()?))
+        } else {
+            None
+        };
+
+        if self.matches(Punctuation::FatArrow) {
+            self.match_token(Punctuation::FatArrow)?;
+            let expr = self.parse_expression()?;
+            let span = fun.span + expr.span();
+            return Ok(LambdaExpr::new(params, return_type, LambdaBody::Expr(Box::new(expr)), span));
+        }
+
+        self.match_token(Keyword::Begin)?;
+        let body = self.parse_block()?;
+        let end = self.match_token(Keyword::End)?;
+        let span = fun.span + end.span;
+        Ok(LambdaExpr::new(params, return_type, LambdaBody::Block(Box::new(body)), span))
+    }
+
     /// Parses the function call args.
     fn parse_function_call_args(&mut self) -> ParseResult<(Vec<Expr>, Span)> {
         let mut args: Vec<Expr> = vec![];
@@ -331,6 +842,70 @@ impl Parser {
         Ok((args, span))
     }
 
+    /// Parses a list literal.
+    fn parse_list_literal(&mut self) -> ParseResult<ListLitExpr> {
+        let mut elems: Vec<Expr> = vec![];
+        let open = self.match_token(Punctuation::OpenBracket)?;
+        while !self.matches(Punctuation::CloseBracket) {
+            let elem = self.parse_expression()?;
+            elems.push(elem);
+            if !self.matches(Punctuation::Comma) {
+                break;
+            }
+
+            self.match_token(Punctuation::Comma)?;
+        }
+
+        let close = self.match_token(Punctuation::CloseBracket)?;
+        let span = open.span + close.span;
+        Ok(ListLitExpr::new(elems, span))
+    }
+
+    /// Parses a map literal, whose entries are pairs separated by colons, or a set literal.
+    /// An empty map is written `{:}`, since `{}` is an empty set.
+    fn parse_map_or_set_literal(&mut self) -> ParseResult<Expr> {
+        let open = self.match_token(Punctuation::OpenBrace)?;
+        if self.matches(Punctuation::Colon) {
+            self.match_token(Punctuation::Colon)?;
+            let close = self.match_token(Punctuation::CloseBrace)?;
+            return Ok(Expr::MapLit(MapLitExpr::new(vec![], open.span + close.span)));
+        }
+
+        if self.matches(Punctuation::CloseBrace) {
+            let close = self.match_token(Punctuation::CloseBrace)?;
+            return Ok(Expr::SetLit(SetLitExpr::new(vec![], open.span + close.span)));
+        }
+
+        let first = self.parse_expression()?;
+        if self.matches(Punctuation::Colon) {
+            let mut entries = vec![];
+            let mut key = first;
+            loop {
+                self.match_token(Punctuation::Colon)?;
+                let value = self.parse_expression()?;
+                entries.push((key, value));
+                if !self.matches(Punctuation::Comma) {
+                    break;
+                }
+
+                self.match_token(Punctuation::Comma)?;
+                key = self.parse_expression()?;
+            }
+
+            let close = self.match_token(Punctuation::CloseBrace)?;
+            return Ok(Expr::MapLit(MapLitExpr::new(entries, open.span + close.span)));
+        }
+
+        let mut elems = vec![first];
+        while self.matches(Punctuation::Comma) {
+            self.match_token(Punctuation::Comma)?;
+            elems.push(self.parse_expression()?);
+        }
+
+        let close = self.match_token(Punctuation::CloseBrace)?;
+        Ok(Expr::SetLit(SetLitExpr::new(elems, open.span + close.span)))
+    }
+
     /// Parses a unary expression.
     fn parse_unary_expression(&mut self) -> ParseResult<UnaryExpr> {
         let op_token = self.match_any(Operator::all_unary_ops())?;
@@ -343,19 +918,38 @@ impl Parser {
         Ok(UnaryExpr::new(op, Box::new(expr), span))
     }
 
-    /// Parses a parenthesized expression.
+    /// Parses a parenthesized expression, or a tuple literal if the parentheses hold several expressions.
     fn parse_parenthesized_expression(&mut self) -> ParseResult<Expr> {
-        self.match_token(Punctuation::OpenParen)?;
-        let expr = self.parse_expression();
-        self.match_token(Punctuation::CloseParen)?;
-        expr
+        let open = self.match_token(Punctuation::OpenParen)?;
+        let expr = self.parse_expression()?;
+        if !self.matches(Punctuation::Comma) {
+            self.match_token(Punctuation::CloseParen)?;
+            return Ok(expr);
+        }
+
+        let mut elems = vec![expr];
+        while self.matches(Punctuation::Comma) {
+            self.match_token(Punctuation::Comma)?;
+            elems.push(self.parse_expression()?);
+        }
+
+        let close = self.match_token(Punctuation::CloseParen)?;
+        Ok(Expr::TupleLit(TupleLitExpr::new(elems, open.span + close.span)))
     }
 
-    /// Parses an identifier expression.
+    /// Parses an identifier expression, which may be qualified by a module path.
     fn parse_identifier_expression(&mut self) -> ParseResult<IdentExpr> {
-        let identifier = self.parse_identifier()?;
-        let span = identifier.span();
-        Ok(IdentExpr::new(Box::new(identifier), span))
+        let mut path = vec![];
+        let mut identifier = self.parse_identifier()?;
+        let start = identifier.span();
+        while self.matches(Punctuation::DoubleColon) {
+            self.match_token(Punctuation::DoubleColon)?;
+            path.push(identifier);
+            identifier = self.parse_identifier()?;
+        }
+
+        let span = start + identifier.span();
+        Ok(IdentExpr::new(path, Box::new(identifier), span))
     }
 
     /// Parses an integer.
@@ -363,7 +957,7 @@ impl Parser {
         let token = self.match_token(TokenTypeMatch::Integer)?;
         match token.token_type {
             TokenType::Integer(value) => {
-                Ok(IntLitExpr::new(value, token.span.clone()))
+                Ok(IntLitExpr::new(i128::from(value), token.span.clone()))
             }
             _ => panic!("parser bug"),
         }
@@ -380,6 +974,17 @@ impl Parser {
         }
     }
 
+    /// Parses a character.
+    fn parse_char(&mut self) -> ParseResult<CharLitExpr> {
+        let token = self.match_token(TokenTypeMatch::Char)?;
+        match token.token_type {
+            TokenType::Char(value) => {
+                Ok(CharLitExpr::new(value, token.span))
+            }
+            _ => panic!("parser bug"),
+        }
+    }
+
     /// Parses a bool.
     fn parse_bool(&mut self) -> ParseResult<BoolLitExpr> {
         let token = self.match_any(vec![Keyword::True, Keyword::False])?;
@@ -405,6 +1010,39 @@ impl Parser {
         }
     }
 
+    /// Parses an interpolated string, whose embedded expressions are each converted by a call of `to_string`.
+    fn parse_interp_string(&mut self) -> ParseResult<InterpStringExpr> {
+        let token = self.match_token(TokenTypeMatch::InterpString)?;
+        let segments = match token.token_type {
+            TokenType::InterpString(segments) => segments,
+            _ => panic!("parser bug"),
+        };
+
+        let mut parts = vec![];
+        for segment in segments {
+            let part = match segment {
+                StrSegment::Text(value) => Expr::StringLit(StringLitExpr::new(value, token.span)),
+                StrSegment::Expr(tokens, precision) => {
+                    let mut parser = Parser::new(tokens);
+                    let expr = parser.parse_expression()?;
+                    parser.match_token(Punctuation::CloseBrace)?;
+
+                    let span = expr.span();
+                    let fun = Ident::new(Builtin::ToString.name().to_string(), span);
+                    let mut args = vec![expr];
+                    if let Some(p) = precision {
+                        args.push(Expr::IntLit(IntLitExpr::new(p as i128, span)));
+                    }
+
+                    Expr::Call(CallExpr::new(Box::new(Expr::Ident(IdentExpr::new(vec![], Box::new(fun), span))), args, span))
+                }
+            };
+            parts.push(part);
+        }
+
+        Ok(InterpStringExpr::new(parts, token.span))
+    }
+
     /// Parses a type.
     fn parse_type(&mut self) -> ParseResult<Ty> {
         let span = self.span();
@@ -412,14 +1050,34 @@ impl Parser {
         Ok(Ty::new(pine_type, span))
     }
 
-    /// Matches a type.
+    /// Matches a type, which is optional when followed by `?`.
     fn match_type(&mut self) -> ParseResult<PineType> {
+        let mut pine_type = self.match_base_type()?;
+        while self.matches(Punctuation::QuestionMark) {
+            self.match_token(Punctuation::QuestionMark)?;
+            pine_type = PineType::Optional(Box::new(pine_type));
+        }
+
+        Ok(pine_type)
+    }
+
+    /// Matches a type that is not optional.
+    fn match_base_type(&mut self) -> ParseResult<PineType> {
         if self.matches(Keyword::Void) {
             self.match_token(Keyword::Void)?;
             Ok(PineType::Void)
         } else if self.matches(Keyword::Int) {
             self.match_token(Keyword::Int)?;
             Ok(PineType::Integer)
+        } else if self.matches(Keyword::Uint) {
+            self.match_token(Keyword::Uint)?;
+            Ok(PineType::UInteger)
+        } else if self.matches(Keyword::Byte) {
+            self.match_token(Keyword::Byte)?;
+            Ok(PineType::Byte)
+        } else if self.matches(Keyword::Char) {
+            self.match_token(Keyword::Char)?;
+            Ok(PineType::Char)
         } else if self.matches(Keyword::Float) {
             self.match_token(Keyword::Float)?;
             Ok(PineType::Float)
@@ -432,10 +1090,71 @@ impl Parser {
         } else if self.matches(Punctuation::OpenBracket) {
             self.match_token(Punctuation::OpenBracket)?;
             let elem_type = self.match_type()?;
-            self.match_token(Punctuation::OpenBracket)?;
+            self.match_token(Punctuation::CloseBracket)?;
             Ok(PineType::List(Box::new(elem_type)))
+        } else if self.matches(Punctuation::OpenParen) {
+            // a single parenthesized type is not a tuple type
+            self.match_token(Punctuation::OpenParen)?;
+            let mut elem_types = vec![self.match_type()?];
+            while self.matches(Punctuation::Comma) {
+                self.match_token(Punctuation::Comma)?;
+                elem_types.push(self.match_type()?);
+            }
+
+            self.match_token(Punctuation::CloseParen)?;
+            if elem_types.len() == 1 {
+                Ok(elem_types.remove(0))
+            } else {
+                Ok(PineType::Tuple(elem_types))
+            }
+        } else if self.matches(TokenTypeMatch::Identifier) {
+            let mut path = vec![self.parse_identifier()?.name];
+            while self.matches(Punctuation::DoubleColon) {
+                self.match_token(Punctuation::DoubleColon)?;
+                path.push(self.parse_identifier()?.name);
+            }
+
+            // the type arguments of a generic type
+            let mut args = vec![];
+            if self.matches(Operator::LessThan) {
+                self.match_token(Operator::LessThan)?;
+                loop {
+                    args.push(self.match_type()?);
+                    if !self.matches(Punctuation::Comma) {
+                        break;
+                    }
+
+                    self.match_token(Punctuation::Comma)?;
+                }
+
+                self.match_token(Operator::GreaterThan)?;
+            }
+
+            Ok(PineType::Named(path, args))
+        } else if self.matches(Keyword::Fun) {
+            // a function type without a return type returns void
+            self.match_token(Keyword::Fun)?;
+            self.match_token(Punctuation::OpenParen)?;
+            let mut params = vec![];
+            while !self.matches(Punctuation::CloseParen) {
+                params.push(self.match_type()?);
+                if !self.matches(Punctuation::Comma) {
+                    break;
+                }
+
+                self.match_token(Punctuation::Comma)?;
+            }
+
+            self.match_token(Punctuation::CloseParen)?;
+            let ret = if self.matches(Punctuation::Arrow) {
+                self.match_token(Punctuation::Arrow)?;
+                self.match_type()?
+            } else {
+                PineType::Void
+            };
+
+            Ok(PineType::Function { params, ret: Box::new(ret) })
         } else {
-            // TODO parse function and user defined types
             Err(ParseError::error("invalid type", self.span()))
         }
     }
@@ -476,6 +1195,14 @@ impl Parser {
         token_type.matches(&self.token_type())
     }
 
+    /// Determines if the token after the current token is matched.
+    fn matches_next<T>(&self, token_type: T) -> bool
+    where
+        T: TokenMatch + Copy + Debug,
+    {
+        self.index + 1 < self.tokens.len() && token_type.matches(&self.tokens[self.index + 1].token_type)
+    }
+
     /// Determines if any token is matches from a set of token types.
     fn matches_any<T>(&self, token_types: Vec<T>) -> bool
     where
@@ -484,6 +1211,11 @@ impl Parser {
         token_types.into_iter().any(|t| self.matches(t))
     }
     
+    /// Determines if the operator of a compound assignment is matched.
+    fn matches_compound_assign(&self) -> bool {
+        matches!(self.token_type(), TokenType::Punctuation(p) if p.compound_op().is_some())
+    }
+
     /// Determines if a statement is matched.
     fn matches_statement(&self) -> bool {
         if self.matches_any(vec![
@@ -494,6 +1226,9 @@ impl Parser {
             Keyword::For,
             Keyword::While,
             Keyword::Return,
+            Keyword::Break,
+            Keyword::Continue,
+            Keyword::Match,
         ]) {
             true
         } else if self.matches_expression() {
@@ -509,14 +1244,16 @@ impl Parser {
             TokenTypeMatch::Identifier,
             TokenTypeMatch::Integer,
             TokenTypeMatch::Float,
+            TokenTypeMatch::Char,
             TokenTypeMatch::String,
+            TokenTypeMatch::InterpString,
         ]) {
             true
-        } else if self.matches_any(vec![Keyword::True, Keyword::False]) {
+        } else if self.matches_any(vec![Keyword::True, Keyword::False, Keyword::None, Keyword::Match, Keyword::Fun]) {
             true
         } else if self.matches_any(Operator::all_unary_ops().into_iter().map(|o| o).collect()) {
             true
-        } else if self.matches_any(vec![Punctuation::OpenParen, Punctuation::OpenBracket]) {
+        } else if self.matches_any(vec![Punctuation::OpenParen, Punctuation::OpenBracket, Punctuation::OpenBrace]) {
             true
         } else {
             false
@@ -0,0 +1,134 @@
+use crate::ast::*;
+use crate::operator::Operator;
+use crate::sem::{SemError, SemResult};
+use crate::symbol::SymbolRef;
+use crate::token::Span;
+use std::rc::Rc;
+
+/// Evaluates the expression of a constant at compile time.
+/// The expression can only refer to literals and the constants that have been evaluated, and not to the
+/// constants that are declared later. The operators behave as they do at runtime, so integer arithmetic wraps around.
+pub(crate) fn eval(expr: &Expr, later: &[SymbolRef]) -> SemResult<ConstValue> {
+    match expr {
+        Expr::IntLit(e) => int_lit_value(e, &PineType::Unknown),
+        Expr::FloatLit(e) => Ok(ConstValue::Float(e.value)),
+        Expr::BoolLit(e) => Ok(ConstValue::Bool(e.value)),
+        Expr::CharLit(e) => Ok(ConstValue::Char(e.value)),
+        Expr::StringLit(e) => Ok(ConstValue::String(e.value.clone())),
+        Expr::Ident(e) if later.iter().any(|s| Rc::ptr_eq(s, &e.ident.symbol)) => {
+            Err(SemError::error(format!("constant {} is used before it is declared", e.ident.name), e.span()))
+        }
+        Expr::Ident(e) => match &e.ident.symbol.borrow().constant {
+            Some(value) => Ok(value.clone()),
+            None => Err(SemError::error(format!("{} is not a constant", e.ident.name), e.span())),
+        },
+        Expr::Unary(e) => {
+            let value = eval(&e.expr, later)?;
+            if let Err(msg) = e.op.unary_pine_type(value.pine_type()) {
+                return Err(SemError::error(msg, e.span()));
+            }
+
+            Ok(match (e.op, value) {
+                (Operator::Not, ConstValue::Bool(b)) => ConstValue::Bool(!b),
+                (Operator::Subtract, ConstValue::Integer(i)) => ConstValue::Integer(i.wrapping_neg()),
+                (Operator::Subtract, ConstValue::Float(f)) => ConstValue::Float(-f),
+                _ => unreachable!("operand type was checked"),
+            })
+        }
+        Expr::Binary(e) => {
+            // an integer literal operand has the type of the other operand, as it does at runtime
+            let (mut l, mut r) = (eval(&e.left, later)?, eval(&e.right, later)?);
+            if let Expr::IntLit(lit) = e.left.as_ref() {
+                l = int_lit_value(lit, &r.pine_type())?;
+            }
+            if let Expr::IntLit(lit) = e.right.as_ref() {
+                r = int_lit_value(lit, &l.pine_type())?;
+            }
+            if let Err(msg) = e.op.binary_pine_type(l.pine_type(), r.pine_type()) {
+                return Err(SemError::error(msg, e.span()));
+            }
+
+            eval_binary(e.op, l, r, e.span())
+        }
+        _ => Err(SemError::error("expression is not constant", expr.span())),
+    }
+}
+
+/// Gets the value of an integer literal that is used as a value of a type. The literal has that type if it is an
+/// integer type, and otherwise it is an int, unless its value only fits in a uint.
+pub(crate) fn int_lit_value(lit: &IntLitExpr, ty: &PineType) -> SemResult<ConstValue> {
+    let ty = match ty.resolve() {
+        ty @ (PineType::Integer | PineType::UInteger | PineType::Byte) => ty,
+        _ if lit.value > i64::MAX as i128 => PineType::UInteger,
+        _ => PineType::Integer,
+    };
+
+    let value = match ty {
+        PineType::Integer => i64::try_from(lit.value).ok().map(ConstValue::Integer),
+        PineType::UInteger => u64::try_from(lit.value).ok().map(ConstValue::UInteger),
+        _ => u8::try_from(lit.value).ok().map(ConstValue::Byte),
+    };
+    value.ok_or_else(|| SemError::error(format!("integer literal {} is out of range for {}", lit.value, ty), lit.span()))
+}
+
+/// Applies a wrapping arithmetic operator to integers of the same type.
+macro_rules! wrapping {
+    ($op:expr, $a:expr, $b:expr) => {
+        match $op {
+            Operator::Add => $a.wrapping_add($b),
+            Operator::Subtract => $a.wrapping_sub($b),
+            Operator::Multiply => $a.wrapping_mul($b),
+            Operator::Divide => $a.wrapping_div($b),
+            Operator::Modulo => $a.wrapping_rem($b),
+            _ => $a.wrapping_pow($b as u32),
+        }
+    };
+}
+
+/// Evaluates a binary operation on constant operands of the same type.
+fn eval_binary(op: Operator, l: ConstValue, r: ConstValue, span: Span) -> SemResult<ConstValue> {
+    if op.is_comparison() {
+        let ordering = match (&l, &r) {
+            (ConstValue::Integer(a), ConstValue::Integer(b)) => a.partial_cmp(b),
+            (ConstValue::UInteger(a), ConstValue::UInteger(b)) => a.partial_cmp(b),
+            (ConstValue::Byte(a), ConstValue::Byte(b)) => a.partial_cmp(b),
+            (ConstValue::Float(a), ConstValue::Float(b)) => a.partial_cmp(b),
+            (ConstValue::Bool(a), ConstValue::Bool(b)) => a.partial_cmp(b),
+            (ConstValue::Char(a), ConstValue::Char(b)) => a.partial_cmp(b),
+            (ConstValue::String(a), ConstValue::String(b)) => a.partial_cmp(b),
+            _ => unreachable!("operand types were checked"),
+        };
+
+        let result = match op {
+            Operator::Equals => ordering.is_some_and(|o| o.is_eq()),
+            Operator::NotEquals => !ordering.is_some_and(|o| o.is_eq()),
+            Operator::LessThan => ordering.is_some_and(|o| o.is_lt()),
+            Operator::LessThanOrEqual => ordering.is_some_and(|o| o.is_le()),
+            Operator::GreaterThan => ordering.is_some_and(|o| o.is_gt()),
+            _ => ordering.is_some_and(|o| o.is_ge()),
+        };
+        return Ok(ConstValue::Bool(result));
+    }
+
+    let value = match (op, l, r) {
+        (Operator::And, ConstValue::Bool(a), ConstValue::Bool(b)) => ConstValue::Bool(a && b),
+        (Operator::Or, ConstValue::Bool(a), ConstValue::Bool(b)) => ConstValue::Bool(a || b),
+        (Operator::Add, ConstValue::String(a), ConstValue::String(b)) => ConstValue::String(a + &b),
+        (Operator::Divide | Operator::Modulo, _, ConstValue::Integer(0) | ConstValue::UInteger(0) | ConstValue::Byte(0)) => {
+            return Err(SemError::error("division by zero in constant", span));
+        }
+        (op, ConstValue::Integer(a), ConstValue::Integer(b)) => ConstValue::Integer(wrapping!(op, a, b)),
+        (op, ConstValue::UInteger(a), ConstValue::UInteger(b)) => ConstValue::UInteger(wrapping!(op, a, b)),
+        (op, ConstValue::Byte(a), ConstValue::Byte(b)) => ConstValue::Byte(wrapping!(op, a, b)),
+        (op, ConstValue::Float(a), ConstValue::Float(b)) => ConstValue::Float(match op {
+            Operator::Add => a + b,
+            Operator::Subtract => a - b,
+            Operator::Multiply => a * b,
+            Operator::Divide => a / b,
+            Operator::Modulo => a % b,
+            _ => a.powf(b),
+        }),
+        _ => unreachable!("operand types were checked"),
+    };
+    Ok(value)
+}
@@ -7,19 +7,30 @@ pub type SemResult<T> = Result<T, Error>;
 /// A semantic error.
 pub struct SemError {
     pub msg: String,
-    pub span: Span
+    pub span: Span,
+    /// The span of the construct that conflicts with the one in error, if any.
+    pub other: Option<Span>
 }
 
 impl SemError {
     pub fn error<T>(msg: T, span: Span) -> Error
     where T: Into<String> {
-        Error::Sem(Self { msg: msg.into(), span })
+        Error::Sem(Self { msg: msg.into(), span, other: None })
+    }
+
+    /// Creates an error for a construct that conflicts with another construct.
+    pub fn conflict<T>(msg: T, span: Span, other: Span) -> Error
+    where T: Into<String> {
+        Error::Sem(Self { msg: msg.into(), span, other: Some(other) })
     }
 }
 
 impl Display for SemError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Semantic Error: {} at {}", self.msg, self.span)
+        match &self.other {
+            Some(other) => write!(f, "Semantic Error: {} at {}, conflicting with {}", self.msg, self.span, other),
+            None => write!(f, "Semantic Error: {} at {}", self.msg, self.span),
+        }
     }
 }
 
@@ -0,0 +1,351 @@
+use crate::ast::*;
+use crate::sem::{SemError, SemResult};
+use std::fmt;
+
+// Usefulness Alg: http://moscova.inria.fr/~maranget/papers/warn/warn.pdf
+
+/// Checks that every match is exhaustive, and that every arm of a match can be reached.
+pub(crate) fn check(program: &Program) -> SemResult<()> {
+    for m in &program.modules {
+        for g in &m.globals {
+            g.expr.check()?;
+        }
+
+        for f in &m.funs {
+            f.block.check()?;
+        }
+
+        for i in &m.impls {
+            for f in &i.funs {
+                f.block.check()?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A pattern reduced to the constructors it matches.
+#[derive(Clone)]
+enum Pat {
+    /// Matches any value, like a wildcard or binding pattern.
+    Any,
+    Ctor(Ctor, Vec<Pat>),
+}
+
+/// A constructor of values that patterns can match.
+#[derive(Clone, PartialEq)]
+enum Ctor {
+    /// A variant of an enum, by tag and name.
+    Variant(usize, String),
+    Bool(bool),
+    /// A tuple, by its number of elements.
+    Tuple(usize),
+    /// An int, float or string literal, by how it is written.
+    Lit(String),
+}
+
+impl fmt::Display for Pat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pat::Any => write!(f, "_"),
+            Pat::Ctor(Ctor::Variant(_, name), args) if args.is_empty() => write!(f, "{}", name),
+            Pat::Ctor(Ctor::Variant(_, name), args) => {
+                let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+            Pat::Ctor(Ctor::Bool(b), _) => write!(f, "{}", b),
+            Pat::Ctor(Ctor::Tuple(_), args) => {
+                let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+                write!(f, "({})", args.join(", "))
+            }
+            Pat::Ctor(Ctor::Lit(l), _) => write!(f, "{}", l),
+        }
+    }
+}
+
+impl Pat {
+    fn new(pattern: &Pattern) -> Self {
+        match pattern {
+            Pattern::Wildcard(_) | Pattern::Binding(_) => Pat::Any,
+            Pattern::Lit(p) => match p.expr.as_ref() {
+                Expr::BoolLit(e) => Pat::Ctor(Ctor::Bool(e.value), vec![]),
+                Expr::IntLit(e) => Pat::Ctor(Ctor::Lit(e.value.to_string()), vec![]),
+                Expr::FloatLit(e) => Pat::Ctor(Ctor::Lit(format!("{:?}", e.value)), vec![]),
+                Expr::CharLit(e) => Pat::Ctor(Ctor::Lit(format!("{:?}", e.value)), vec![]),
+                Expr::StringLit(e) => Pat::Ctor(Ctor::Lit(format!("{:?}", e.value)), vec![]),
+                _ => panic!("pattern is not a literal"),
+            },
+            Pattern::Variant(p) => {
+                let tag = p.variant().1;
+                let ctor = Ctor::Variant(tag, p.variant.ident.name.clone());
+                Pat::Ctor(ctor, p.args.iter().map(Pat::new).collect())
+            }
+            Pattern::Tuple(p) => Pat::Ctor(Ctor::Tuple(p.elems.len()), p.elems.iter().map(Pat::new).collect()),
+        }
+    }
+}
+
+/// Gets the types of the arguments of a constructor of a type.
+fn ctor_types(ctor: &Ctor, ty: &PineType) -> Vec<PineType> {
+    match (ctor, ty.resolve()) {
+        (Ctor::Variant(tag, _), PineType::Enum(e, args)) => e.borrow().instance_variant(*tag, &args),
+        (Ctor::Tuple(_), PineType::Tuple(elems)) => elems,
+        _ => vec![],
+    }
+}
+
+/// Gets the constructors of a type along with the types of their arguments, if the type has finitely many.
+fn signature(ty: &PineType) -> Option<Vec<(Ctor, Vec<PineType>)>> {
+    match ty.resolve() {
+        PineType::Bool => Some(vec![(Ctor::Bool(false), vec![]), (Ctor::Bool(true), vec![])]),
+        PineType::Enum(e, args) => {
+            let e = e.borrow();
+            let ctors = e.variants.iter()
+                .enumerate()
+                .map(|(tag, (name, _))| (Ctor::Variant(tag, name.clone()), e.instance_variant(tag, &args)))
+                .collect();
+            Some(ctors)
+        }
+        PineType::Tuple(elems) => Some(vec![(Ctor::Tuple(elems.len()), elems)]),
+        _ => None,
+    }
+}
+
+/// Keeps the rows of a matrix whose first pattern matches a constructor,
+/// replacing the first pattern with the patterns of the constructor arguments.
+fn specialize(matrix: &[Vec<Pat>], ctor: &Ctor, arity: usize) -> Vec<Vec<Pat>> {
+    matrix.iter()
+        .filter_map(|row| match &row[0] {
+            Pat::Ctor(c, args) if c == ctor => Some([args.clone(), row[1..].to_vec()].concat()),
+            Pat::Ctor(..) => None,
+            Pat::Any => Some([vec![Pat::Any; arity], row[1..].to_vec()].concat()),
+        })
+        .collect()
+}
+
+/// Rebuilds a row of patterns whose first patterns are the arguments of a constructor.
+fn rebuild(ctor: &Ctor, arity: usize, mut row: Vec<Pat>) -> Vec<Pat> {
+    let rest = row.split_off(arity);
+    [vec![Pat::Ctor(ctor.clone(), row)], rest].concat()
+}
+
+/// Finds the values that are matched by a row of patterns but by no row of a matrix.
+/// Returns one of the values as a row of patterns, or nothing if the row is not useful.
+fn useful(matrix: &[Vec<Pat>], row: &[Pat], tys: &[PineType]) -> Option<Vec<Pat>> {
+    let (head, ty) = match (row.first(), tys.first()) {
+        (Some(head), Some(ty)) => (head, ty),
+        _ => return if matrix.is_empty() { Some(vec![]) } else { None },
+    };
+
+    if let Pat::Ctor(ctor, args) = head {
+        let matrix = specialize(matrix, ctor, args.len());
+        let row = [args.clone(), row[1..].to_vec()].concat();
+        let tys = [ctor_types(ctor, ty), tys[1..].to_vec()].concat();
+        return useful(&matrix, &row, &tys).map(|w| rebuild(ctor, args.len(), w));
+    }
+
+    let used: Vec<Ctor> = matrix.iter()
+        .filter_map(|row| match &row[0] {
+            Pat::Ctor(c, _) => Some(c.clone()),
+            Pat::Any => None,
+        })
+        .collect();
+
+    match signature(ty) {
+        // when the matrix uses every constructor of the type, the row must be useful for one of them
+        Some(sig) if sig.iter().all(|(c, _)| used.contains(c)) => {
+            sig.into_iter().find_map(|(ctor, arg_tys)| {
+                let arity = arg_tys.len();
+                let matrix = specialize(matrix, &ctor, arity);
+                let row = [vec![Pat::Any; arity], row[1..].to_vec()].concat();
+                let tys = [arg_tys, tys[1..].to_vec()].concat();
+                useful(&matrix, &row, &tys).map(|w| rebuild(&ctor, arity, w))
+            })
+        }
+        // otherwise, the row is useful for the values of the constructors the matrix does not use
+        sig => {
+            let matrix: Vec<Vec<Pat>> = matrix.iter()
+                .filter(|row| matches!(row[0], Pat::Any))
+                .map(|row| row[1..].to_vec())
+                .collect();
+            let w = useful(&matrix, &row[1..], &tys[1..])?;
+            let missing = sig.and_then(|sig| sig.into_iter().find(|(c, _)| !used.contains(c)))
+                .map(|(c, arg_tys)| Pat::Ctor(c, vec![Pat::Any; arg_tys.len()]))
+                .unwrap_or(Pat::Any);
+            Some([vec![missing], w].concat())
+        }
+    }
+}
+
+/// Checks the arms of a match in order, and then checks that the arms match every value.
+fn check_match(expr: &MatchExpr) -> SemResult<()> {
+    let tys = [expr.expr.ty()];
+    let mut matrix = vec![];
+    for a in &expr.arms {
+        let row = vec![Pat::new(&a.pattern)];
+        if useful(&matrix, &row, &tys).is_none() {
+            return Err(SemError::error("match arm is unreachable", a.pattern.span()));
+        }
+
+        matrix.push(row);
+    }
+
+    match useful(&matrix, &[Pat::Any], &tys) {
+        Some(w) => Err(SemError::error(format!("match is not exhaustive, {} is not matched", w[0]), expr.span())),
+        None => Ok(()),
+    }
+}
+
+/// Checks that the pattern of a let statement matches every value.
+fn check_let_tuple(stmt: &LetTupleStmt) -> SemResult<()> {
+    let tys = [stmt.expr.ty()];
+    match useful(&[vec![Pat::new(&stmt.pattern)]], &[Pat::Any], &tys) {
+        Some(w) => Err(SemError::error(format!("let pattern does not match every value, {} is not matched", w[0]), stmt.pattern.span())),
+        None => Ok(()),
+    }
+}
+
+/// Checks the matches within an AST node.
+trait AstMatching {
+    fn check(&self) -> SemResult<()>;
+}
+
+impl AstMatching for Block {
+    fn check(&self) -> SemResult<()> {
+        for s in &self.stmts {
+            s.check()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AstMatching for Stmt {
+    fn check(&self) -> SemResult<()> {
+        match self {
+            Stmt::Let(s) => s.expr.check(),
+            Stmt::LetTuple(s) => {
+                s.expr.check()?;
+                check_let_tuple(s)
+            }
+            Stmt::Set(s) => {
+                s.target.check()?;
+                s.expr.check()
+            }
+            Stmt::If(s) => {
+                for c in &s.conds {
+                    c.check()?;
+                }
+
+                for b in &s.then_blocks {
+                    b.check()?;
+                }
+
+                match &s.else_block {
+                    Some(b) => b.check(),
+                    None => Ok(()),
+                }
+            }
+            Stmt::While(s) => {
+                s.cond.check()?;
+                s.block.check()
+            }
+            Stmt::For(s) => {
+                s.iter.check()?;
+                s.block.check()
+            }
+            Stmt::Return(s) => match &s.expr {
+                Some(e) => e.check(),
+                None => Ok(()),
+            },
+            Stmt::Break(_) | Stmt::Continue(_) => Ok(()),
+            Stmt::Expr(s) => s.expr.check(),
+            Stmt::Block(s) => s.check(),
+        }
+    }
+}
+
+impl AstMatching for Expr {
+    fn check(&self) -> SemResult<()> {
+        match self {
+            Expr::IntLit(_) | Expr::FloatLit(_) | Expr::CharLit(_) | Expr::BoolLit(_) | Expr::StringLit(_) | Expr::NoneLit(_) | Expr::Ident(_) => Ok(()),
+            Expr::Call(e) => {
+                e.fun.check()?;
+                for a in &e.args {
+                    a.check()?;
+                }
+
+                Ok(())
+            }
+            Expr::Unary(e) => e.expr.check(),
+            Expr::Binary(e) => {
+                e.left.check()?;
+                e.right.check()
+            }
+            Expr::Range(e) => {
+                e.start.check()?;
+                e.end.check()
+            }
+            Expr::ListLit(e) => {
+                for e in &e.elems {
+                    e.check()?;
+                }
+
+                Ok(())
+            }
+            Expr::Index(e) => {
+                e.expr.check()?;
+                e.index.check()
+            }
+            Expr::MapLit(e) => {
+                for (k, v) in &e.entries {
+                    k.check()?;
+                    v.check()?;
+                }
+
+                Ok(())
+            }
+            Expr::SetLit(e) => {
+                for e in &e.elems {
+                    e.check()?;
+                }
+
+                Ok(())
+            }
+            Expr::TupleLit(e) => {
+                for e in &e.elems {
+                    e.check()?;
+                }
+
+                Ok(())
+            }
+            Expr::InterpString(e) => {
+                for p in &e.parts {
+                    p.check()?;
+                }
+
+                Ok(())
+            }
+            Expr::Field(e) => e.expr.check(),
+            Expr::Cast(e) => e.expr.check(),
+            Expr::Is(e) => e.expr.check(),
+            Expr::Try(e) => e.expr.check(),
+            Expr::Match(e) => {
+                e.expr.check()?;
+                for a in &e.arms {
+                    match &a.body {
+                        ArmBody::Expr(e) => e.check()?,
+                        ArmBody::Block(b) => b.check()?,
+                    }
+                }
+
+                check_match(e)
+            }
+            Expr::Lambda(e) => match &e.body {
+                LambdaBody::Expr(e) => e.check(),
+                LambdaBody::Block(b) => b.check(),
+            },
+        }
+    }
+}
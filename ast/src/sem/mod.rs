@@ -3,15 +3,150 @@ pub(crate) mod scoping;
 pub(crate) mod typing;
 pub(crate) mod ret;
 pub(crate) mod modresv;
+pub(crate) mod matching;
+pub(crate) mod constant;
 
 pub use error::*;
+use crate::ast::{PineType, ScopedAst};
+use crate::symbol::ScopeRef;
+use crate::token::Span;
 use crate::Ast;
 
 /// Creates a symbol for the identifier.
 fn create_symbol(ident: &crate::ast::Ident, scope: &crate::symbol::ScopeRef) -> SemResult<()> {
     let symbol = crate::symbol::Symbol::new(ident.name.clone(), scope.clone());
+    symbol.borrow_mut().span = ident.span();
     match scope.borrow_mut().add(symbol.clone()) {
         Ok(()) => Ok(()),
         Err(()) => Err(SemError::error(format!("identifier {} has already been defined", ident.name), ident.span())),
     }
 }
+
+/// Creates symbols for the type parameters of a generic declaration. Returns the type parameters.
+fn create_type_params(type_params: &mut [crate::ast::Ident], scope: &ScopeRef) -> SemResult<Vec<PineType>> {
+    let mut generics = vec![];
+    for p in type_params {
+        create_symbol(p, scope)?;
+        let symbol = scope.borrow().lookup(&p.name).expect("type parameter has no symbol");
+        let generic = PineType::generic(p.name.clone());
+        symbol.borrow_mut().generic = Some(generic.clone());
+        p.symbol = symbol;
+        p.set_scope(scope.clone());
+        generics.push(generic);
+    }
+
+    Ok(generics)
+}
+
+/// Resolves a named type that does not refer to a declared type to a builtin collection type.
+fn resolve_collection_type(name: &str, mut args: Vec<PineType>, span: Span) -> SemResult<PineType> {
+    let arity = match name {
+        "Map" => 2,
+        "Set" => 1,
+        _ => return Err(SemError::error(format!("type {} does not exist", name), span)),
+    };
+
+    if args.len() != arity {
+        return Err(SemError::error(format!("type {} expects {} type arguments but was given {}", name, arity, args.len()), span));
+    }
+
+    let first = Box::new(args.remove(0));
+    match args.pop() {
+        Some(second) => Ok(PineType::Map(first, Box::new(second))),
+        None => Ok(PineType::Set(first)),
+    }
+}
+
+/// Resolves the named types within a type to the object types, enum types and interfaces they refer to.
+fn resolve_type(ty: &PineType, scope: &ScopeRef, span: Span) -> SemResult<PineType> {
+    match ty {
+        PineType::Named(path, args) => {
+            let type_name = path.join("::");
+            let (name, module_path) = path.split_last().expect("named type has no name");
+            let symbol = if module_path.is_empty() {
+                scope.borrow().lookup(name)
+            } else {
+                // resolve the module path through the global scopes of the imported modules
+                let mut module_scope = scope.clone();
+                for (i, m) in module_path.iter().enumerate() {
+                    let next_scope = if i == 0 {
+                        module_scope.borrow().lookup_module(m)
+                    } else {
+                        module_scope.borrow().lookup_path_module(m)
+                    };
+
+                    module_scope = match next_scope {
+                        Some(s) => s,
+                        None if module_scope.borrow().imports.contains_key(m) => {
+                            let msg = format!("module {} is not exported by module {}", m, module_path[..i].join("::"));
+                            return Err(SemError::error(msg, span));
+                        }
+                        None => return Err(SemError::error(format!("module {} does not exist", module_path[..=i].join("::")), span)),
+                    };
+                }
+
+                if !module_scope.borrow().exports.contains(name) {
+                    return Err(SemError::error(format!("{} is not exported by module {}", name, module_path.join("::")), span));
+                }
+
+                let symbol = module_scope.borrow().symbol_table.get(name);
+                symbol
+            };
+
+            let mut resolved_args = vec![];
+            for a in args {
+                resolved_args.push(resolve_type(a, scope, span)?);
+            }
+
+            let symbol = match symbol {
+                Some(s) => s,
+                None if module_path.is_empty() => return resolve_collection_type(name, resolved_args, span),
+                None => return Err(SemError::error(format!("type {} does not exist", type_name), span)),
+            };
+
+            let symbol = symbol.borrow();
+            let arity = match (&symbol.obj, &symbol.enum_def) {
+                (Some(o), _) => o.borrow().params.len(),
+                (_, Some(e)) => e.borrow().params.len(),
+                _ => 0,
+            };
+            if resolved_args.len() != arity {
+                return Err(SemError::error(format!("type {} expects {} type arguments but was given {}", type_name, arity, resolved_args.len()), span));
+            }
+
+            if let Some(o) = &symbol.obj {
+                Ok(PineType::Object(o.clone(), resolved_args))
+            } else if let Some(e) = &symbol.enum_def {
+                Ok(PineType::Enum(e.clone(), resolved_args))
+            } else if let Some(i) = &symbol.iface {
+                Ok(PineType::Interface(i.clone()))
+            } else if let Some(g) = &symbol.generic {
+                Ok(g.clone())
+            } else {
+                Err(SemError::error(format!("{} is not a type", type_name), span))
+            }
+        }
+        PineType::List(t) => Ok(PineType::List(Box::new(resolve_type(t, scope, span)?))),
+        PineType::Optional(t) => Ok(PineType::Optional(Box::new(resolve_type(t, scope, span)?))),
+        PineType::Tuple(elems) => {
+            let mut resolved_elems = vec![];
+            for e in elems {
+                resolved_elems.push(resolve_type(e, scope, span)?);
+            }
+
+            Ok(PineType::Tuple(resolved_elems))
+        }
+        PineType::Function { params, ret } => {
+            let mut resolved_params = vec![];
+            for p in params {
+                resolved_params.push(resolve_type(p, scope, span)?);
+            }
+
+            Ok(PineType::Function {
+                params: resolved_params,
+                ret: Box::new(resolve_type(ret, scope, span)?),
+            })
+        }
+        t => Ok(t.clone()),
+    }
+}
@@ -1,84 +1,145 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::rc::Rc;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
 use crate::ast::*;
+use crate::{parse_module, CORE_MODULE};
 use crate::sem::{SemError, SemResult};
+use crate::token::Span;
 
-pub(crate) fn resolve_modules(main_module: &Module) -> SemResult<Vec<Module>> {
-    // TODO produce graph of module references
+/// The extension of a Pine module file.
+const MODULE_EXTENSION: &str = "p";
 
-    let modules: Vec<ModuleNode> = main_module.imports
-        .iter()
-        .map(|i| ModuleNode::new(i.ident.name.clone()))
-        .collect();
+/// The name of the file that holds a directory module.
+const DIR_MODULE_FILE: &str = "mod.p";
 
+/// The source of the core module, which is compiled into the compiler.
+const CORE_SOURCE: &str = include_str!("../core.p");
 
+/// Resolves the module graph starting at the root module.
+/// Every module is parsed exactly once, and the modules are returned in dependency order,
+/// so that a module always comes after the modules it imports. The core module is first and the root module is last.
+pub(crate) fn resolve_modules(root_path: &Path) -> SemResult<Vec<Module>> {
+    let mut traverser = ModuleTraverser::new(root_path);
+    traverser.modules.push(core_module()?);
+    traverser.traverse(root_path.to_path_buf(), Span::default())?;
+    Ok(traverser.modules)
+}
 
-    // TODO analyze graph for cycles
-
-    // TODO parse unique modules
-
-    // TODO produce list of modules to be processed in order
-
-    todo!()
+/// Parses the core module.
+fn core_module() -> SemResult<Module> {
+    let mut module = match parse_module(CORE_SOURCE) {
+        Ok(m) => m,
+        Err(e) => return Err(e.in_module(CORE_MODULE)),
+    };
+    module.name = CORE_MODULE.to_string();
+    Ok(module)
 }
 
-struct ModuleNode {
-    name: String,
-    modules: Vec<ModuleNode>,
+/// Traverses the module graph depth first.
+struct ModuleTraverser {
+    /// The directory of the root module.
+    root_dir: PathBuf,
+    /// The names of the modules currently being traversed, used to detect cycles.
+    stack: Vec<String>,
+    /// The paths of the modules that have already been resolved.
+    visited: HashSet<PathBuf>,
+    /// The resolved modules in dependency order.
+    modules: Vec<Module>,
 }
 
-impl ModuleNode {
-    pub fn new(name: String) -> Self {
+impl ModuleTraverser {
+    pub fn new(root_path: &Path) -> Self {
+        let root_dir = match root_path.parent() {
+            Some(p) => p.to_path_buf(),
+            None => PathBuf::new(),
+        };
+
         Self {
-            name,
+            root_dir,
+            stack: vec![],
+            visited: HashSet::new(),
             modules: vec![],
         }
     }
-}
 
-struct ModuleTraverser {
-    pub curr_path: PathBuf,
-    pub modules: HashMap<String, Module>,
-    pub dependency_graph: ModuleNode
-}
+    /// Traverses the module at the path and all modules it imports.
+    /// Returns the name of the module.
+    pub fn traverse(&mut self, path: PathBuf, span: Span) -> SemResult<String> {
+        let name = self.module_name(&path);
+        if name == CORE_MODULE {
+            return Err(SemError::error(format!("module name {} is reserved", name), span));
+        }
 
-impl ModuleTraverser {
-    pub fn new(root_path: &str) -> Self {
-        Self {
-            curr_path: PathBuf::from(root_path),
-            modules: HashMap::new(),
-            dependency_graph: ModuleNode::new("root".to_string())
+        if let Some(i) = self.stack.iter().position(|n| *n == name) {
+            let cycle = self.stack[i..].join(" -> ");
+            return Err(SemError::error(format!("import cycle detected: {} -> {}", cycle, name), span));
         }
-    }
-    
-    pub fn traverse(&mut self, module: &Module) -> SemResult<()> {
-        for import in &module.imports {
-            let mut path = self.curr_path.join(import.ident.name.clone());
-            if path.exists() {
-                path.push("mod.p");
-            } else {
-                path.set_extension("p");
-            }
-            
-            if !path.exists() {
-                return Err(SemError::error("module does not exist", import.span()));
-            }
-            
-            let key = match path.to_str() {
-                Some(s) => s.to_string(),
-                None => return Err(SemError::error("module does not exist", import.span()))
-            };
-            
-            if self.modules.contains_key(&key) {
-                // TODO traverse imports tp build graph
-            } else {
-                // TODO parse module and add to modules
-                // TODO traverse imports tp build graph
+
+        if self.visited.contains(&path) {
+            return Ok(name);
+        }
+
+        let input = match fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(_) => return Err(SemError::error(format!("could not read module {}", path.display()), span)),
+        };
+
+        let mut module = match parse_module(input) {
+            Ok(m) => m,
+            Err(e) => return Err(e.in_module(&name)),
+        };
+        module.name = name.clone();
+
+        self.stack.push(name.clone());
+        let dir = match path.parent() {
+            Some(p) => p.to_path_buf(),
+            None => PathBuf::new(),
+        };
+
+        for import in &mut module.imports {
+            let import_path = Self::import_path(&dir, &import.ident.name);
+            if !import_path.exists() {
+                return Err(SemError::error(format!("module {} does not exist", import.ident.name), import.span()));
             }
+
+            import.module = self.traverse(import_path, import.span())?;
+        }
+
+        self.stack.pop();
+        self.visited.insert(path);
+        self.modules.push(module);
+        Ok(name)
+    }
+
+    /// Gets the path of an imported module.
+    /// A module `foo` is either the file `foo.p` or the directory module `foo/mod.p`.
+    fn import_path(dir: &Path, name: &str) -> PathBuf {
+        let mut path = dir.join(name);
+        if path.is_dir() {
+            path.push(DIR_MODULE_FILE);
+        } else {
+            path.set_extension(MODULE_EXTENSION);
+        }
+
+        path
+    }
+
+    /// Gets the qualified name of the module at the path, relative to the root directory.
+    /// For example `sub/util.p` is named `sub::util` and `sub/mod.p` is named `sub`.
+    fn module_name(&self, path: &Path) -> String {
+        let relative = path.strip_prefix(&self.root_dir).unwrap_or(path);
+        let mut parts: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        if parts.len() > 1 && parts.last().map(|p| p.as_str()) == Some(DIR_MODULE_FILE) {
+            parts.pop();
+        } else if let Some(stem) = relative.file_stem() {
+            parts.pop();
+            parts.push(stem.to_string_lossy().to_string());
         }
 
-        Ok(())
-    } 
+        parts.join("::")
+    }
 }
@@ -1,55 +1,143 @@
 use crate::ast::*;
 use crate::sem::{SemError, SemResult};
+use crate::token::Span;
 
 /// Checks that all functions with return types return values for every path,
 /// and inserts void returns for void functions.
 pub(crate) fn check(program: &mut Program) -> SemResult<()> {
-    for f in &mut program.main_module.funs {
-        check_fun(f)?;
+    for m in &mut program.modules {
+        for f in &mut m.funs {
+            check_fun(f)?;
+        }
+
+        for i in &mut m.impls {
+            for f in &mut i.funs {
+                check_fun(f)?;
+            }
+        }
     }
 
     Ok(())
 }
 
 fn check_fun(fun: &mut Fun) -> SemResult<()> {
-    let ret = match &fun.return_ty {
-        Some(ty) => ty.ty.clone(),
-        None => PineType::Void
+    let span = fun.span();
+    check_body(fun.return_ty.as_deref(), &mut fun.block, span)
+}
+
+/// Checks that a lambda with a block body returns a value for every path if it returns values,
+/// and inserts a void return otherwise. Lambdas are checked as they are scoped, since they are nested in expressions.
+pub(crate) fn check_lambda(lambda: &mut LambdaExpr) -> SemResult<()> {
+    let span = lambda.span();
+    match &mut lambda.body {
+        LambdaBody::Block(block) => check_body(lambda.return_ty.as_deref(), block, span),
+        LambdaBody::Expr(_) => Ok(()),
+    }
+}
+
+/// Checks the block of a function or lambda.
+fn check_body(return_ty: Option<&Ty>, block: &mut Block, span: Span) -> SemResult<()> {
+    // a function without a return type returns a value if any of its return statements return a value
+    let returns_value = match return_ty {
+        Some(ty) => ty.ty != PineType::Void,
+        None => returns_value(block),
     };
 
-    match ret {
-        PineType::Void => {
-            // insert return at the end of the block
-            match fun.block.stmts.last() {
-                Some(Stmt::Return(_)) => Ok(()),
-                _ => {
-                    let ret = Stmt::Return(ReturnStmt::new(None, fun.span()));
-                    fun.block.stmts.push(ret);
-                    Ok(())
-                }
-            }
+    if !returns_value {
+        // a function that does not return values cannot propagate errors
+        if let Some(span) = find_try(block) {
+            return Err(SemError::error("`?` can only be used in a function that returns a Result", span));
         }
-        _ => {
-            // ensure all paths return
-            if !fun.all_paths_return() {
-                Err(SemError::error("not all paths return", fun.span()))?
-            } else {
+
+        // insert return at the end of the block
+        match block.stmts.last() {
+            Some(Stmt::Return(_)) => Ok(()),
+            _ => {
+                let ret = Stmt::Return(ReturnStmt::new(None, span));
+                block.stmts.push(ret);
                 Ok(())
             }
         }
+    } else if !block.all_paths_return() {
+        // ensure all paths return
+        Err(SemError::error("not all paths return", span))
+    } else {
+        Ok(())
     }
 }
 
-trait AstAllPathsReturn {
-    fn all_paths_return(&self) -> bool;
+/// Determines if a block contains a return statement that returns a value.
+fn returns_value(block: &Block) -> bool {
+    block.stmts.iter().any(|s| match s {
+        Stmt::Return(r) => r.expr.is_some(),
+        Stmt::If(s) => s.then_blocks.iter().any(returns_value) || s.else_block.as_ref().is_some_and(|b| returns_value(b)),
+        Stmt::While(s) => returns_value(&s.block),
+        Stmt::For(s) => returns_value(&s.block),
+        Stmt::Block(b) => returns_value(b),
+        Stmt::Expr(s) => match_stmt(s).is_some_and(|m| m.arms.iter().any(|a| match &a.body {
+            ArmBody::Block(b) => returns_value(b),
+            ArmBody::Expr(_) => false,
+        })),
+        _ => false,
+    })
 }
 
-impl AstAllPathsReturn for Fun {
-    fn all_paths_return(&self) -> bool {
-        self.block.all_paths_return()
+/// Finds the span of the first `?` in a block, outside of the lambdas in the block.
+fn find_try(block: &Block) -> Option<Span> {
+    block.stmts.iter().find_map(|s| match s {
+        Stmt::Let(s) => find_try_expr(&s.expr),
+        Stmt::LetTuple(s) => find_try_expr(&s.expr),
+        Stmt::Set(s) => find_try_expr(&s.target).or_else(|| find_try_expr(&s.expr)),
+        Stmt::If(s) => s.conds.iter().find_map(find_try_expr)
+            .or_else(|| s.then_blocks.iter().find_map(find_try))
+            .or_else(|| s.else_block.as_ref().and_then(|b| find_try(b))),
+        Stmt::While(s) => find_try_expr(&s.cond).or_else(|| find_try(&s.block)),
+        Stmt::For(s) => find_try_expr(&s.iter).or_else(|| find_try(&s.block)),
+        Stmt::Return(s) => s.expr.as_ref().and_then(|e| find_try_expr(e)),
+        Stmt::Expr(s) => find_try_expr(&s.expr),
+        Stmt::Block(b) => find_try(b),
+        Stmt::Break(_) | Stmt::Continue(_) => None,
+    })
+}
+
+/// Finds the span of the first `?` in an expression, outside of the lambdas in the expression.
+fn find_try_expr(expr: &Expr) -> Option<Span> {
+    match expr {
+        Expr::Try(e) => Some(e.span()),
+        Expr::Call(e) => find_try_expr(&e.fun).or_else(|| e.args.iter().find_map(find_try_expr)),
+        Expr::Unary(e) => find_try_expr(&e.expr),
+        Expr::Binary(e) => find_try_expr(&e.left).or_else(|| find_try_expr(&e.right)),
+        Expr::Range(e) => find_try_expr(&e.start).or_else(|| find_try_expr(&e.end)),
+        Expr::ListLit(e) => e.elems.iter().find_map(find_try_expr),
+        Expr::MapLit(e) => e.entries.iter().find_map(|(k, v)| find_try_expr(k).or_else(|| find_try_expr(v))),
+        Expr::SetLit(e) => e.elems.iter().find_map(find_try_expr),
+        Expr::TupleLit(e) => e.elems.iter().find_map(find_try_expr),
+        Expr::InterpString(e) => e.parts.iter().find_map(find_try_expr),
+        Expr::Index(e) => find_try_expr(&e.expr).or_else(|| find_try_expr(&e.index)),
+        Expr::Field(e) => find_try_expr(&e.expr),
+        Expr::Cast(e) => find_try_expr(&e.expr),
+        Expr::Is(e) => find_try_expr(&e.expr),
+        Expr::Match(e) => find_try_expr(&e.expr).or_else(|| e.arms.iter().find_map(|a| match &a.body {
+            ArmBody::Expr(e) => find_try_expr(e),
+            ArmBody::Block(b) => find_try(b),
+        })),
+        Expr::IntLit(_) | Expr::FloatLit(_) | Expr::CharLit(_) | Expr::BoolLit(_) | Expr::StringLit(_) | Expr::NoneLit(_)
+        | Expr::Ident(_) | Expr::Lambda(_) => None,
     }
 }
 
+/// Gets the match of an expression statement, if it is a match statement.
+fn match_stmt(stmt: &ExprStmt) -> Option<&MatchExpr> {
+    match stmt.expr.as_ref() {
+        Expr::Match(m) if m.is_stmt => Some(m),
+        _ => None,
+    }
+}
+
+trait AstAllPathsReturn {
+    fn all_paths_return(&self) -> bool;
+}
+
 impl AstAllPathsReturn for IfStmt {
     fn all_paths_return(&self) -> bool {
         match &self.else_block {
@@ -65,15 +153,31 @@ impl AstAllPathsReturn for IfStmt {
     }
 }
 
+impl AstAllPathsReturn for MatchExpr {
+    /// A match returns on all paths if every arm does, since the match is checked to be exhaustive.
+    fn all_paths_return(&self) -> bool {
+        !self.arms.is_empty() && self.arms.iter().all(|a| match &a.body {
+            ArmBody::Block(b) => b.all_paths_return(),
+            ArmBody::Expr(_) => false,
+        })
+    }
+}
+
 impl AstAllPathsReturn for WhileStmt {
+    /// A loop that may be exited by a break does not return on that path.
     fn all_paths_return(&self) -> bool {
-        self.block.all_paths_return()
+        self.block.all_paths_return() && !breaks(&self.block)
     }
 }
 
 impl AstAllPathsReturn for Block {
+    /// A block returns on all paths if a statement does before the block is left by a break or continue.
     fn all_paths_return(&self) -> bool {
         for s in &self.stmts {
+            if matches!(s, Stmt::Break(_) | Stmt::Continue(_)) {
+                return false;
+            }
+
             if s.all_paths_return() {
                 return true;
             }
@@ -83,6 +187,26 @@ impl AstAllPathsReturn for Block {
     }
 }
 
+/// Determines if every path through a block leaves it by a return, break or continue statement.
+pub(crate) fn exits(block: &Block) -> bool {
+    block.stmts.iter().any(|s| matches!(s, Stmt::Break(_) | Stmt::Continue(_)) || s.all_paths_return())
+}
+
+/// Determines if a block contains a break statement that exits the loop the block belongs to,
+/// rather than a loop nested in the block.
+fn breaks(block: &Block) -> bool {
+    block.stmts.iter().any(|s| match s {
+        Stmt::Break(_) => true,
+        Stmt::If(s) => s.then_blocks.iter().any(breaks) || s.else_block.as_ref().is_some_and(|b| breaks(b)),
+        Stmt::Block(b) => breaks(b),
+        Stmt::Expr(s) => match_stmt(s).is_some_and(|m| m.arms.iter().any(|a| match &a.body {
+            ArmBody::Block(b) => breaks(b),
+            ArmBody::Expr(_) => false,
+        })),
+        _ => false,
+    })
+}
+
 impl AstAllPathsReturn for Stmt {
     fn all_paths_return(&self) -> bool {
         match self {
@@ -90,6 +214,7 @@ impl AstAllPathsReturn for Stmt {
             Stmt::While(s) => s.all_paths_return(),
             Stmt::Return(_) => true,
             Stmt::Block(b) => b.all_paths_return(),
+            Stmt::Expr(s) => match_stmt(s).is_some_and(|m| m.all_paths_return()),
             _ => false
         }
     }
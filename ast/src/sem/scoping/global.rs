@@ -1,12 +1,47 @@
+use std::collections::HashMap;
 use crate::ast::*;
+use crate::CORE_MODULE;
 use crate::sem::error::{SemError, SemResult};
 use crate::symbol::*;
-use crate::sem::create_symbol;
+use crate::sem::{create_symbol, create_type_params, resolve_type};
 
 /// Annotates global scopes.
+/// The core module sees the intrinsics, and every other module sees the exports of the core module
+/// through the prelude scope, where they can be shadowed by the symbols of the module.
 pub(crate) fn global(program: &mut Program) -> SemResult<()> {
-    let global_scope = Scope::new_global();
-    program.main_module.visit(global_scope)?;
+    let builtin_scope = Scope::new_builtin();
+    let intrinsic_scope = Scope::new_intrinsic(builtin_scope.clone());
+    let prelude_scope = Scope::new_global();
+    prelude_scope.borrow_mut().parent = Some(builtin_scope.clone());
+    let mut module_scopes: HashMap<String, ScopeRef> = HashMap::new();
+    for m in &mut program.modules {
+        let is_core = m.name == CORE_MODULE;
+        let global_scope = Scope::new_global();
+        global_scope.borrow_mut().parent = Some(if is_core { intrinsic_scope.clone() } else { prelude_scope.clone() });
+
+        // imported modules are resolved before the modules that import them
+        for i in &m.imports {
+            let import_scope = module_scopes[&i.module].clone();
+            global_scope.borrow_mut().imports.insert(i.ident.name.clone(), import_scope);
+            if i.module == format!("{}::{}", m.name, i.ident.name) {
+                global_scope.borrow_mut().submodules.insert(i.ident.name.clone());
+            }
+        }
+
+        m.visit(global_scope.clone())?;
+        if is_core {
+            // the core module can also be named, so that its exports can be used where they are shadowed
+            let mut prelude = prelude_scope.borrow_mut();
+            prelude.imports.insert(m.name.clone(), global_scope.clone());
+            for name in &global_scope.borrow().exports {
+                let symbol = global_scope.borrow().lookup(name).expect("exported symbol is not defined");
+                prelude.add(symbol).unwrap();
+            }
+        }
+
+        module_scopes.insert(m.name.clone(), global_scope);
+    }
+
     Ok(())
 }
 
@@ -17,9 +52,337 @@ trait AstScoping {
 impl AstScoping for Module {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
         self.set_scope(scope.clone());
+
+        // objects are visited before their fields, so that fields can refer to any object in the module
+        for o in &mut self.objs {
+            o.visit(scope.clone())?;
+
+            // constructors are called by their label, which is qualified by the module name
+            let label = format!("{}::{}", self.name, o.ident.name);
+            let obj = ObjDef::new(o.ident.name.clone(), self.name.clone());
+            {
+                let mut symbol = o.ident.symbol.borrow_mut();
+                symbol.dest = pvm::Operand::Label(label);
+                symbol.obj = Some(obj.clone());
+                symbol.module = Some(self.name.clone());
+            }
+
+            // the fields of a generic object are scoped to its type parameters
+            let obj_scope = Scope::new_global();
+            obj_scope.borrow_mut().parent = Some(scope.clone());
+            obj.borrow_mut().params = create_type_params(&mut o.type_params, &obj_scope)?;
+            o.set_scope(obj_scope);
+        }
+
+        // enums are visited before their payloads, like objects, and their variants are defined in the module scope
+        for e in &mut self.enums {
+            e.visit(scope.clone())?;
+            let enum_def = EnumDef::new(e.ident.name.clone(), self.name.clone());
+            e.ident.symbol.borrow_mut().enum_def = Some(enum_def.clone());
+            enum_def.borrow_mut().variants = e.variants.iter().map(|v| (v.ident.name.clone(), vec![])).collect();
+            for (tag, v) in e.variants.iter_mut().enumerate() {
+                create_symbol(&v.ident, &scope)?;
+                v.ident.visit(scope.clone())?;
+
+                // variants with payloads are constructed by calling them like constructors,
+                // while variants without payloads are values held in global variables
+                let name = format!("{}::{}", self.name, v.ident.name);
+                let mut symbol = v.ident.symbol.borrow_mut();
+                symbol.dest = if v.payload.is_empty() {
+                    pvm::Operand::Variable(name)
+                } else {
+                    pvm::Operand::Label(name)
+                };
+                symbol.variant = Some((enum_def.clone(), tag));
+            }
+
+            // the payloads of a generic enum are scoped to its type parameters
+            let enum_scope = Scope::new_global();
+            enum_scope.borrow_mut().parent = Some(scope.clone());
+            enum_def.borrow_mut().params = create_type_params(&mut e.type_params, &enum_scope)?;
+            e.set_scope(enum_scope);
+        }
+
+        for i in &mut self.interfaces {
+            i.visit(scope.clone())?;
+            let iface = IfaceDef::new(i.ident.name.clone(), self.name.clone());
+            i.ident.symbol.borrow_mut().iface = Some(iface.clone());
+
+            // interface methods are called through a function that dispatches on the vtable
+            for m in &i.methods {
+                let label = format!("{}::{}::{}", self.name, i.ident.name, m.ident.name);
+                m.ident.symbol.borrow_mut().dest = pvm::Operand::Label(label);
+                iface.borrow_mut().methods.push(m.ident.symbol.clone());
+            }
+        }
+
         for f in &mut self.funs {
             f.visit(scope.clone())?;
+
+            // functions are called by their label, which is qualified by the module name
+            let label = format!("{}::{}", self.name, f.ident.name);
+            let mut symbol = f.ident.symbol.borrow_mut();
+            symbol.dest = pvm::Operand::Label(label);
+            symbol.module = Some(self.name.clone());
+        }
+
+        // constants and global variables are held in global variables qualified by the module name,
+        // although int, float and bool constants are replaced by their values once they are evaluated
+        for c in &mut self.consts {
+            c.visit(scope.clone())?;
+            let name = format!("{}::{}", self.name, c.ident.name);
+            c.ident.symbol.borrow_mut().dest = pvm::Operand::Variable(name);
+        }
+
+        for g in &mut self.globals {
+            g.visit(scope.clone())?;
+            let name = format!("{}::{}", self.name, g.ident.name);
+            g.ident.symbol.borrow_mut().dest = pvm::Operand::Variable(name);
+        }
+
+        for e in &mut self.exports {
+            e.visit(scope.clone())?;
+        }
+
+        for o in &mut self.objs {
+            let obj_scope = o.scope();
+            for f in &mut o.fields {
+                f.visit(obj_scope.clone())?;
+            }
+        }
+
+        for e in &mut self.enums {
+            let enum_scope = e.scope();
+            for v in &mut e.variants {
+                v.set_scope(enum_scope.clone());
+                for t in &mut v.payload {
+                    t.visit(enum_scope.clone())?;
+                }
+            }
+        }
+
+        for i in &mut self.interfaces {
+            for m in &mut i.methods {
+                m.visit(scope.clone())?;
+            }
+        }
+
+        for i in &mut self.impls {
+            i.visit(scope.clone())?;
+            let obj = match &i.obj.ty {
+                PineType::Object(obj, _) => obj.clone(),
+                _ => panic!("impl is not for an object"),
+            };
+
+            let iface = match &i.iface {
+                Some(iface) => match &iface.ty {
+                    PineType::Interface(iface) => iface.clone(),
+                    _ => panic!("impl is not of an interface"),
+                },
+                None => {
+                    // methods are called by their label, which is qualified by the object
+                    let prefix = format!("{}::{}", self.name, obj.borrow().name);
+                    for f in &mut i.funs {
+                        if obj.borrow().method(&f.ident.name).is_some() {
+                            let msg = format!("method {} is already defined for {}", f.ident.name, obj.borrow().name);
+                            return Err(SemError::error(msg, f.ident.span()));
+                        }
+
+                        let label = format!("{}::{}", prefix, f.ident.name);
+                        let mut symbol = f.ident.symbol.borrow_mut();
+                        symbol.dest = pvm::Operand::Label(label);
+                        symbol.module = Some(self.name.clone());
+                        obj.borrow_mut().methods.push(f.ident.symbol.clone());
+                    }
+
+                    continue;
+                }
+            };
+
+            if iface.borrow().impl_for(&obj).is_some() {
+                let msg = format!("interface {} is already implemented for {}", iface.borrow().name, obj.borrow().name);
+                return Err(SemError::error(msg, i.span()));
+            }
+
+            // implementing functions are called by their label, which is qualified by the interface and object
+            let prefix = format!("{}::{}::{}", self.name, iface.borrow().name, obj.borrow().name);
+            for f in &mut i.funs {
+                let label = format!("{}::{}", prefix, f.ident.name);
+                f.ident.symbol.borrow_mut().dest = pvm::Operand::Label(label);
+            }
+
+            obj.borrow_mut().ifaces.push(iface.clone());
+            let impl_def = ImplDef { obj, prefix, methods: vec![] };
+            iface.borrow_mut().impls.push(impl_def);
+        }
+        Ok(())
+    }
+}
+
+impl AstScoping for Export {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.set_scope(scope.clone());
+        for i in &mut self.idents {
+            // an imported module is exported so that it can be named in paths through the module
+            if scope.borrow().imports.contains_key(&i.name) {
+                scope.borrow_mut().exports.insert(i.name.clone());
+                continue;
+            }
+
+            if !scope.borrow().symbol_table.contains(&i.name) {
+                return Err(SemError::error(format!("exported identifier {} does not exist", i.name), i.span()));
+            }
+
+            i.visit(scope.clone())?;
+            scope.borrow_mut().exports.insert(i.name.clone());
+
+            // exporting an enum exports its variants
+            let enum_def = i.symbol.borrow().enum_def.clone();
+            if let Some(e) = enum_def {
+                for (v, _) in &e.borrow().variants {
+                    scope.borrow_mut().exports.insert(v.clone());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for Obj {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.set_scope(scope.clone());
+        create_symbol(&self.ident, &scope)?;
+        self.ident.visit(scope.clone())?;
+        Ok(())
+    }
+}
+
+impl AstScoping for Enum {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.set_scope(scope.clone());
+        create_symbol(&self.ident, &scope)?;
+        self.ident.visit(scope.clone())?;
+        Ok(())
+    }
+}
+
+impl AstScoping for Const {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.set_scope(scope.clone());
+        create_symbol(&self.ident, &scope)?;
+        self.ident.visit(scope.clone())?;
+        if let Some(ty) = &mut self.ty {
+            ty.visit(scope)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for Global {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.set_scope(scope.clone());
+        create_symbol(&self.ident, &scope)?;
+        self.ident.visit(scope.clone())?;
+        if let Some(ty) = &mut self.ty {
+            ty.visit(scope)?;
         }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for Field {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.set_scope(scope.clone());
+        self.ty.visit(scope)?;
+        Ok(())
+    }
+}
+
+impl AstScoping for Interface {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.set_scope(scope.clone());
+        create_symbol(&self.ident, &scope)?;
+        self.ident.visit(scope.clone())?;
+
+        // the methods are scoped to the interface, so they do not clash with the methods of other interfaces,
+        // and are called in method syntax on the values of the interface
+        let iface_scope = Scope::new_global();
+        iface_scope.borrow_mut().parent = Some(scope);
+        for m in &mut self.methods {
+            create_symbol(&m.ident, &iface_scope)?;
+            m.ident.visit(iface_scope.clone())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for MethodSig {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.set_scope(scope.clone());
+        for p in &mut self.params {
+            p.set_scope(scope.clone());
+            if let Some(ty) = &mut p.ty {
+                ty.visit(scope.clone())?;
+            }
+        }
+
+        if let Some(ret_ty) = &mut self.return_ty {
+            ret_ty.visit(scope)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for Impl {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        if let Some(iface) = &mut self.iface {
+            iface.visit(scope.clone())?;
+            if !matches!(iface.ty, PineType::Interface(_)) {
+                return Err(SemError::error(format!("{} is not an interface", iface.ty), iface.span()));
+            }
+        }
+
+        // the implementing functions are scoped to the impl, so they do not clash with other functions
+        let impl_scope = Scope::new_global();
+        impl_scope.borrow_mut().parent = Some(scope);
+        self.set_scope(impl_scope.clone());
+
+        // the type arguments of a generic object are type parameters of the impl, as in `impl Box<T>`
+        let mut type_params = match &self.obj.ty {
+            PineType::Named(_, args) => args.iter()
+                .map(|a| match a {
+                    PineType::Named(path, a_args) if path.len() == 1 && a_args.is_empty() => Ok(Ident::new(path[0].clone(), self.obj.span())),
+                    _ => Err(SemError::error("the type arguments of an impl must be type parameters", self.obj.span())),
+                })
+                .collect::<SemResult<Vec<_>>>()?,
+            _ => vec![],
+        };
+        let generics = create_type_params(&mut type_params, &impl_scope)?;
+
+        self.obj.visit(impl_scope.clone())?;
+        let subject = if self.iface.is_some() { "interfaces" } else { "methods" };
+        match &self.obj.ty {
+            PineType::Object(o, _) if self.iface.is_some() && !o.borrow().params.is_empty() => {
+                return Err(SemError::error(format!("{} cannot be implemented for generic objects", subject), self.obj.span()));
+            }
+            PineType::Object(..) => {}
+            _ => return Err(SemError::error(format!("{} can only be implemented for objects", subject), self.obj.span())),
+        }
+
+        // the implementing functions are defined in a scope of their own, so they are not in scope in their bodies,
+        // which are scoped to the impl, and are only called in method syntax
+        let methods_scope = Scope::new_global();
+        methods_scope.borrow_mut().parent = Some(impl_scope);
+        for f in &mut self.funs {
+            f.impl_type_params = generics.clone();
+            f.visit(methods_scope.clone())?;
+        }
+
         Ok(())
     }
 }
@@ -46,3 +409,11 @@ impl AstScoping for Ident {
         Ok(())
     }
 }
+
+impl AstScoping for Ty {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.set_scope(scope.clone());
+        self.ty = resolve_type(&self.ty, &scope, self.span())?;
+        Ok(())
+    }
+}
@@ -1,11 +1,17 @@
 use crate::ast::*;
+use crate::builtin::Builtin;
+use crate::operator::Operator;
 use crate::sem::error::{SemError, SemResult};
 use crate::symbol::*;
-use crate::sem::create_symbol;
+use std::rc::Rc;
+use crate::sem::{constant, create_symbol, create_type_params, resolve_type, ret};
 
 /// Annotates local scopes.
 pub(crate) fn local(program: &mut Program) -> SemResult<()> {
-    program.main_module.visit(program.main_module.scope())?;
+    for m in &mut program.modules {
+        m.visit(m.scope())?;
+    }
+
     Ok(())
 }
 
@@ -15,18 +21,69 @@ trait AstScoping {
 
 impl AstScoping for Module {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        // constants are evaluated in the order they are declared, so they can refer to the constants before them
+        let symbols: Vec<SymbolRef> = self.consts.iter().map(|c| c.ident.symbol.clone()).collect();
+        for (i, c) in self.consts.iter_mut().enumerate() {
+            c.expr.visit(scope.clone())?;
+            define_const(c, &symbols[i..])?;
+        }
+
+        for g in &mut self.globals {
+            g.expr.visit(scope.clone())?;
+        }
+
         for f in &mut self.funs {
             f.visit(scope.clone())?;
         }
+
+        for i in &mut self.impls {
+            let impl_scope = i.scope();
+            for f in &mut i.funs {
+                f.visit(impl_scope.clone())?;
+            }
+        }
         Ok(())
     }
 }
 
+/// Evaluates a constant and defines its symbol. The constant cannot refer to the constants declared after it.
+fn define_const(c: &Const, later: &[SymbolRef]) -> SemResult<()> {
+    let mut value = constant::eval(&c.expr, later)?;
+    if let Some(ty) = &c.ty {
+        // an integer literal has the declared type, as it does in a variable declaration
+        if let Expr::IntLit(lit) = c.expr.as_ref() {
+            value = constant::int_lit_value(lit, &ty.ty)?;
+        }
+        if ty.ty != value.pine_type() {
+            let msg = format!("types do not match, expected {} but found {}", ty.ty, value.pine_type());
+            return Err(SemError::error(msg, c.expr.span()));
+        }
+    }
+
+    // only strings are held in global variables, since they are allocated
+    let mut symbol = c.ident.symbol.borrow_mut();
+    symbol.pine_type = value.pine_type();
+    match &value {
+        ConstValue::Integer(i) => symbol.dest = pvm::Operand::Constant(pvm::to_u64!(*i)),
+        ConstValue::UInteger(u) => symbol.dest = pvm::Operand::Constant(*u),
+        ConstValue::Byte(b) => symbol.dest = pvm::Operand::Constant(pvm::to_u64!(*b)),
+        ConstValue::Float(f) => symbol.dest = pvm::Operand::Constant(pvm::to_u64!(*f)),
+        ConstValue::Bool(b) => symbol.dest = pvm::Operand::Constant(pvm::to_u64!(*b as u8)),
+        ConstValue::Char(c) => symbol.dest = pvm::Operand::Constant(*c as u64),
+        ConstValue::String(_) => {}
+    }
+    symbol.constant = Some(value);
+    Ok(())
+}
+
 impl AstScoping for Fun {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
         // crate the block scope
         let block_scope = Scope::new_fun(scope.clone(), self.ident.symbol.clone());
 
+        // create the type parameters, which the params and return type may refer to
+        create_type_params(&mut self.type_params, &block_scope)?;
+
         // visit the params
         for p in &mut self.params {
             p.visit(block_scope.clone())?;
@@ -48,7 +105,10 @@ impl AstScoping for Param {
         self.set_scope(scope.clone());
         create_symbol(&self.ident, &scope)?;
         self.ident.visit(scope.clone())?;
-        self.ty.visit(scope.clone())?;
+        if let Some(ty) = &mut self.ty {
+            ty.visit(scope.clone())?;
+        }
+
         Ok(())
     }
 }
@@ -66,38 +126,116 @@ impl AstScoping for LetStmt {
     }
 }
 
+impl AstScoping for LetTupleStmt {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        if let Some(ty) = &mut self.ty {
+            ty.visit(scope.clone())?;
+        }
+
+        // the expression is visited first, since it cannot refer to the variables bound by the pattern
+        self.expr.visit(scope.clone())?;
+        self.pattern.visit(scope)?;
+        Ok(())
+    }
+}
+
 impl AstScoping for SetStmt {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
-        self.ident.visit(scope.clone())?;
+        // the expression is visited first, since it sees a variable checked by a loop condition before it is assigned
         self.expr.visit(scope.clone())?;
+        self.target.visit(scope.clone())?;
+
+        // a lambda holds copies of the variables it captures, so assigning them would not be seen outside the lambda
+        if let Expr::Ident(target) = self.target.as_mut() {
+            if target.narrowed {
+                if !scope.borrow_mut().unnarrow_loop(&target.ident.symbol) {
+                    return Err(SemError::error(format!("optional variable {} cannot be assigned after it is checked for none", target.ident.name), target.span()));
+                }
+                target.narrowed = false;
+            }
+
+
+            let captured = scope.borrow().owning_fun()
+                .is_some_and(|f| f.borrow().captures.iter().any(|c| Rc::ptr_eq(c, &target.ident.symbol)));
+            if captured {
+                return Err(SemError::error(format!("captured variable {} cannot be assigned", target.ident.name), target.span()));
+            }
+        }
+
         Ok(())
     }
 }
 
 impl AstScoping for IfStmt {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
-        for c in &mut self.conds {
-            c.visit(scope.clone())?;
-        }
-        
-        for b in &mut self.then_blocks {
-            let then_scope = Scope::new_local(scope.clone());
-            b.visit(then_scope)?;
+        // the optionals checked by a condition hold values in its block, and the optionals checked for none
+        // by the conditions before it hold values in the later conditions and blocks
+        let mut otherwise = vec![];
+        for (c, b) in self.conds.iter_mut().zip(&mut self.then_blocks) {
+            let cond_scope = if otherwise.is_empty() { scope.clone() } else { narrow(&scope, otherwise.clone()) };
+            c.visit(cond_scope)?;
+
+            let mut narrowed = otherwise.clone();
+            narrowed.extend(narrowed_by(c, true));
+            b.visit(narrow(&scope, narrowed))?;
+            otherwise.extend(narrowed_by(c, false));
         }
 
-        if let Some(else_block) = &mut self.else_block {
-            let else_scope = Scope::new_local(scope.clone());
-            else_block.visit(else_scope)?;
+        match &mut self.else_block {
+            Some(else_block) => else_block.visit(narrow(&scope, otherwise))?,
+            // the statements after an if statement whose blocks all exit are only reached when every condition is false
+            None if self.then_blocks.iter().all(ret::exits) => scope.borrow_mut().narrowed.extend(otherwise),
+            None => {}
         }
 
         Ok(())
     }
 }
 
+/// Creates a local scope in which the optional variables hold values.
+fn narrow(scope: &ScopeRef, symbols: Vec<SymbolRef>) -> ScopeRef {
+    let narrowed_scope = Scope::new_local(scope.clone());
+    narrowed_scope.borrow_mut().narrowed = symbols;
+    narrowed_scope
+}
+
+/// Gets the optional variables that hold values when a condition evaluates to a truth value.
+/// Only local variables are narrowed, since a global variable may be assigned by any call.
+fn narrowed_by(cond: &Expr, truth: bool) -> Vec<SymbolRef> {
+    match cond {
+        Expr::Is(e) if e.some == truth => match e.expr.as_ref() {
+            Expr::Ident(i) if i.ident.symbol.borrow().scope.borrow().depth != ScopeDepth::Global => vec![i.ident.symbol.clone()],
+            _ => vec![],
+        },
+        Expr::Unary(e) if e.op == Operator::Not => narrowed_by(&e.expr, !truth),
+        Expr::Binary(e) if (e.op == Operator::And && truth) || (e.op == Operator::Or && !truth) => {
+            let mut symbols = narrowed_by(&e.left, truth);
+            symbols.extend(narrowed_by(&e.right, truth));
+            symbols
+        }
+        _ => vec![],
+    }
+}
+
 impl AstScoping for WhileStmt {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        // the optionals checked by the condition hold values in the block, until they are assigned
         self.cond.visit(scope.clone())?;
-        let block_scope = Scope::new_local(scope.clone());
+        let block_scope = Scope::new_loop(scope.clone());
+        block_scope.borrow_mut().narrowed = narrowed_by(&self.cond, true);
+        self.block.visit(block_scope)?;
+        Ok(())
+    }
+}
+
+impl AstScoping for ForStmt {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.iter.visit(scope.clone())?;
+
+        // the loop variable is scoped to the block
+        let block_scope = Scope::new_loop(scope.clone());
+        create_symbol(&self.ident, &block_scope)?;
+        self.ident.visit(block_scope.clone())?;
         self.block.visit(block_scope)?;
         Ok(())
     }
@@ -135,16 +273,29 @@ impl AstScoping for Stmt {
         self.set_scope(scope.clone());
         match self {
             Stmt::Let(s) => s.visit(scope),
+            Stmt::LetTuple(s) => s.visit(scope),
             Stmt::Set(s) => s.visit(scope),
             Stmt::If(s) => s.visit(scope),
             Stmt::While(s) => s.visit(scope),
+            Stmt::For(s) => s.visit(scope),
             Stmt::Return(s) => s.visit(scope),
+            Stmt::Break(_) | Stmt::Continue(_) => in_loop(self, &scope),
             Stmt::Expr(s) => s.visit(scope),
             Stmt::Block(s) => s.visit(scope)
         }
     }
 }
 
+/// Checks that a break or continue statement is within a loop.
+fn in_loop(stmt: &Stmt, scope: &ScopeRef) -> SemResult<()> {
+    if scope.borrow().in_loop() {
+        return Ok(());
+    }
+
+    let keyword = if matches!(stmt, Stmt::Break(_)) { "break" } else { "continue" };
+    Err(SemError::error(format!("{} can only be used in a loop", keyword), stmt.span()))
+}
+
 impl AstScoping for IntLitExpr {
     fn visit(&mut self, _scope: ScopeRef) -> SemResult<()> {
         Ok(())
@@ -157,6 +308,12 @@ impl AstScoping for FloatLitExpr {
     }
 }
 
+impl AstScoping for CharLitExpr {
+    fn visit(&mut self, _scope: ScopeRef) -> SemResult<()> {
+        Ok(())
+    }
+}
+
 impl AstScoping for BoolLitExpr {
     fn visit(&mut self, _scope: ScopeRef) -> SemResult<()> {
         Ok(())
@@ -169,15 +326,79 @@ impl AstScoping for StringLitExpr {
     }
 }
 
+impl AstScoping for InterpStringExpr {
+    /// The embedded expressions are converted by the builtin `to_string`, even where a function of that name is in scope.
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        for p in &mut self.parts {
+            p.visit(scope.clone())?;
+            if let Expr::Call(call) = p {
+                if let Expr::Ident(e) = call.fun.as_mut() {
+                    e.ident.symbol = scope.borrow().lookup_builtin(Builtin::ToString.name()).expect("builtin is not defined");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl AstScoping for IdentExpr {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
-        self.ident.visit(scope)?;
+        if self.path.is_empty() {
+            self.ident.visit(scope.clone())?;
+            self.narrowed = scope.borrow().is_narrowed(&self.ident.symbol);
+            return Ok(());
+        }
+
+        // resolve the module path through the global scopes of the imported modules
+        let mut module_scope = scope.clone();
+        let mut module_path = vec![];
+        for p in &mut self.path {
+            p.set_scope(scope.clone());
+            module_path.push(p.name.clone());
+            let next_scope = if module_path.len() == 1 {
+                module_scope.borrow().lookup_module(&p.name)
+            } else {
+                module_scope.borrow().lookup_path_module(&p.name)
+            };
+
+            module_scope = match next_scope {
+                Some(s) => s,
+                None if module_scope.borrow().imports.contains_key(&p.name) => {
+                    let msg = format!("module {} is not exported by module {}", p.name, module_path[..module_path.len() - 1].join("::"));
+                    return Err(SemError::error(msg, p.span()));
+                }
+                None => return Err(SemError::error(format!("module {} does not exist", module_path.join("::")), p.span())),
+            };
+        }
+
+        let module_name = module_path.join("::");
+        let symbol = match module_scope.borrow().symbol_table.get(&self.ident.name) {
+            Some(s) => s,
+            None => return Err(SemError::error(format!("identifier {} does not exist in module {}", self.ident.name, module_name), self.span())),
+        };
+
+        if !module_scope.borrow().exports.contains(&self.ident.name) {
+            return Err(SemError::error(format!("{} is not exported by module {}", self.ident.name, module_name), self.span()));
+        }
+
+        self.ident.set_scope(scope);
+        self.ident.symbol = symbol;
         Ok(())
     }
 }
 
 impl AstScoping for CallExpr {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        // a call in method syntax is resolved against the type of the receiver during typing,
+        // and falls back to the function in scope
+        if let Expr::Field(f) = self.fun.as_ref() {
+            self.fallback = scope.borrow().lookup(&f.field.name);
+            if let Some(symbol) = &self.fallback {
+                Scope::capture(&scope, symbol);
+            }
+        }
+
         self.fun.visit(scope.clone())?;
         for a in &mut self.args {
             a.visit(scope.clone())?;
@@ -186,6 +407,7 @@ impl AstScoping for CallExpr {
         Ok(())
     }
 }
+
 impl AstScoping for UnaryExpr {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
         self.expr.visit(scope)?;
@@ -196,23 +418,233 @@ impl AstScoping for UnaryExpr {
 impl AstScoping for BinaryExpr {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
         self.left.visit(scope.clone())?;
-        self.right.visit(scope.clone())?;
+
+        // the right operand of `and` is only evaluated when the left is true, and that of `or` when the left is false
+        let narrowed = match self.op {
+            Operator::And => narrowed_by(&self.left, true),
+            Operator::Or => narrowed_by(&self.left, false),
+            _ => vec![],
+        };
+        let right_scope = if narrowed.is_empty() { scope } else { narrow(&scope, narrowed) };
+        self.right.visit(right_scope)?;
+        Ok(())
+    }
+}
+
+impl AstScoping for RangeExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.start.visit(scope.clone())?;
+        self.end.visit(scope)?;
         Ok(())
     }
 }
 
+impl AstScoping for ListLitExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        for e in &mut self.elems {
+            e.visit(scope.clone())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for MapLitExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        for (k, v) in &mut self.entries {
+            k.visit(scope.clone())?;
+            v.visit(scope.clone())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for SetLitExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        for e in &mut self.elems {
+            e.visit(scope.clone())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for TupleLitExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        for e in &mut self.elems {
+            e.visit(scope.clone())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for IndexExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.expr.visit(scope.clone())?;
+        self.index.visit(scope)?;
+        Ok(())
+    }
+}
+
+impl AstScoping for FieldExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        // the field is resolved against the type of the object during typing
+        self.set_scope(scope.clone());
+        self.field.set_scope(scope.clone());
+        self.expr.visit(scope)?;
+        Ok(())
+    }
+}
+
+impl AstScoping for CastExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.expr.visit(scope)?;
+        Ok(())
+    }
+}
+
+impl AstScoping for NoneLitExpr {
+    fn visit(&mut self, _scope: ScopeRef) -> SemResult<()> {
+        Ok(())
+    }
+}
+
+impl AstScoping for IsExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        // an optional variable that is checked again is checked as the optional itself
+        self.expr.visit(scope)?;
+        if let Expr::Ident(e) = self.expr.as_mut() {
+            e.narrowed = false;
+        }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for TryExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.expr.visit(scope)?;
+        Ok(())
+    }
+}
+
+impl AstScoping for MatchExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        self.expr.visit(scope.clone())?;
+
+        // the variables bound by the pattern of an arm are scoped to the arm
+        for a in &mut self.arms {
+            let arm_scope = Scope::new_local(scope.clone());
+            a.set_scope(arm_scope.clone());
+            a.pattern.visit(arm_scope.clone())?;
+            match &mut a.body {
+                ArmBody::Expr(e) => e.visit(arm_scope)?,
+                ArmBody::Block(b) => b.visit(arm_scope)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AstScoping for LambdaExpr {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        ret::check_lambda(self)?;
+
+        // the lambda owns the scope of its body like a function, so the variables its body refers to
+        // from outside of the lambda are captured by its symbol
+        self.symbol = Symbol::new("lambda".to_string(), scope.clone());
+        self.symbol.borrow_mut().span = self.span();
+        let block_scope = Scope::new_fun(scope, self.symbol.clone());
+        for p in &mut self.params {
+            p.visit(block_scope.clone())?;
+        }
+
+        if let Some(ret_ty) = &mut self.return_ty {
+            ret_ty.visit(block_scope.clone())?;
+        }
+
+        match &mut self.body {
+            LambdaBody::Expr(e) => e.visit(block_scope),
+            LambdaBody::Block(b) => b.visit(block_scope),
+        }
+    }
+}
+
+impl AstScoping for Pattern {
+    fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
+        // a binding whose name is a variant matches the variant
+        if let Pattern::Binding(p) = self {
+            let is_variant = scope.borrow().lookup(&p.ident.name).is_some_and(|s| s.borrow().variant.is_some());
+            if is_variant {
+                let span = p.span();
+                let ident = std::mem::replace(&mut p.ident, Box::new(Ident::new(String::new(), span)));
+                let variant = IdentExpr::new(vec![], ident, span);
+                *self = Pattern::Variant(VariantPattern::new(Box::new(variant), vec![], span));
+            }
+        }
+
+        self.set_scope(scope.clone());
+        match self {
+            Pattern::Wildcard(_) => Ok(()),
+            Pattern::Lit(p) => p.expr.visit(scope),
+            Pattern::Binding(p) => {
+                create_symbol(&p.ident, &scope)?;
+                p.ident.visit(scope)
+            }
+            Pattern::Variant(p) => {
+                p.variant.set_scope(scope.clone());
+                p.variant.visit(scope.clone())?;
+                if p.variant.ident.symbol.borrow().variant.is_none() {
+                    return Err(SemError::error(format!("{} is not a variant", p.variant.ident.name), p.variant.span()));
+                }
+
+                for a in &mut p.args {
+                    a.visit(scope.clone())?;
+                }
+
+                Ok(())
+            }
+            Pattern::Tuple(p) => {
+                for e in &mut p.elems {
+                    e.visit(scope.clone())?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
 impl AstScoping for Expr {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
         self.set_scope(scope.clone());
         match self {
             Expr::IntLit(e) => e.visit(scope),
             Expr::FloatLit(e) => e.visit(scope),
+            Expr::CharLit(e) => e.visit(scope),
             Expr::BoolLit(e) => e.visit(scope),
             Expr::StringLit(e) => e.visit(scope),
+            Expr::InterpString(e) => e.visit(scope),
             Expr::Ident(e) => e.visit(scope),
             Expr::Call(e) => e.visit(scope),
             Expr::Unary(e) => e.visit(scope),
             Expr::Binary(e) => e.visit(scope),
+            Expr::Range(e) => e.visit(scope),
+            Expr::ListLit(e) => e.visit(scope),
+            Expr::MapLit(e) => e.visit(scope),
+            Expr::SetLit(e) => e.visit(scope),
+            Expr::TupleLit(e) => e.visit(scope),
+            Expr::Index(e) => e.visit(scope),
+            Expr::Field(e) => e.visit(scope),
+            Expr::Cast(e) => e.visit(scope),
+            Expr::NoneLit(e) => e.visit(scope),
+            Expr::Is(e) => e.visit(scope),
+            Expr::Try(e) => e.visit(scope),
+            Expr::Match(e) => e.visit(scope),
+            Expr::Lambda(e) => e.visit(scope),
         }
     }
 }
@@ -224,13 +656,15 @@ impl AstScoping for Ident {
             Some(s) => Ok(s),
             None => Err(SemError::error(format!("identifier {} does not exist in scope", self.name), self.span()))
         }?;
+        Scope::capture(&scope, &self.symbol);
         Ok(())
     }
 }
 
 impl AstScoping for Ty {
     fn visit(&mut self, scope: ScopeRef) -> SemResult<()> {
-        self.set_scope(scope);
+        self.set_scope(scope.clone());
+        self.ty = resolve_type(&self.ty, &scope, self.span())?;
         Ok(())
     }
 }
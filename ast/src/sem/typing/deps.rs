@@ -0,0 +1,325 @@
+use crate::ast::*;
+use crate::symbol::SymbolRef;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/// Identifies a binding of a program, which is either a function or a global variable.
+#[derive(Clone, Copy)]
+pub(crate) enum BindingId {
+    /// A function, by its module, the impl it belongs to if any, and its index.
+    Fun { module: usize, imp: Option<usize>, fun: usize },
+    /// A global variable, by its module and its index.
+    Global { module: usize, global: usize },
+}
+
+/// A binding of a program.
+pub(crate) enum Binding<'a> {
+    Fun(&'a mut Fun),
+    Global(&'a mut Global),
+}
+
+impl BindingId {
+    /// Gets the binding from the program.
+    pub fn get_mut<'a>(&self, program: &'a mut Program) -> Binding<'a> {
+        match *self {
+            BindingId::Fun { module, imp: Some(i), fun } => Binding::Fun(&mut program.modules[module].impls[i].funs[fun]),
+            BindingId::Fun { module, imp: None, fun } => Binding::Fun(&mut program.modules[module].funs[fun]),
+            BindingId::Global { module, global } => Binding::Global(&mut program.modules[module].globals[global]),
+        }
+    }
+}
+
+/// Groups the bindings of a program into groups of mutually recursive bindings.
+/// Every group comes after the groups of the bindings it refers to, so that the types of the functions
+/// can be inferred and generalised before they are used.
+pub(crate) fn binding_groups(program: &Program) -> Vec<Vec<BindingId>> {
+    let methods = methods(program);
+    let mut ids = vec![];
+    let mut bindings: Vec<(&SymbolRef, Vec<SymbolRef>)> = vec![];
+    for (m, module) in program.modules.iter().enumerate() {
+        for (f, fun) in module.funs.iter().enumerate() {
+            ids.push(BindingId::Fun { module: m, imp: None, fun: f });
+            bindings.push((&fun.ident.symbol, refs(fun.block.as_ref(), &methods)));
+        }
+
+        for (i, imp) in module.impls.iter().enumerate() {
+            for (f, fun) in imp.funs.iter().enumerate() {
+                ids.push(BindingId::Fun { module: m, imp: Some(i), fun: f });
+                bindings.push((&fun.ident.symbol, refs(fun.block.as_ref(), &methods)));
+            }
+        }
+
+        for (g, global) in module.globals.iter().enumerate() {
+            ids.push(BindingId::Global { module: m, global: g });
+            bindings.push((&global.ident.symbol, refs(global.expr.as_ref(), &methods)));
+        }
+    }
+
+    // the bindings each binding refers to
+    let indices: HashMap<*const _, usize> = bindings.iter()
+        .enumerate()
+        .map(|(i, (s, _))| (Rc::as_ptr(s), i))
+        .collect();
+    let edges: Vec<Vec<usize>> = bindings.iter()
+        .map(|(_, refs)| refs.iter().filter_map(|s| indices.get(&Rc::as_ptr(s)).copied()).collect())
+        .collect();
+
+    let mut tarjan = Tarjan {
+        edges: &edges,
+        index: vec![None; bindings.len()],
+        low: vec![0; bindings.len()],
+        on_stack: vec![false; bindings.len()],
+        stack: vec![],
+        next: 0,
+        groups: vec![],
+    };
+    for v in 0..bindings.len() {
+        if tarjan.index[v].is_none() {
+            tarjan.connect(v);
+        }
+    }
+
+    tarjan.groups.into_iter()
+        .map(|g| g.into_iter().map(|v| ids[v]).collect())
+        .collect()
+}
+
+/// Finds a global variable of a module that the initializer of a global variable before it refers to,
+/// either directly or through the functions it calls. Returns the global variable whose initializer refers to it,
+/// and its name.
+pub(crate) fn uninitialized_global<'a>(program: &Program, module: &'a Module) -> Option<(&'a Global, String)> {
+    let methods = methods(program);
+    let blocks: HashMap<*const _, &Block> = program.modules.iter()
+        .flat_map(|m| m.funs.iter().chain(m.impls.iter().flat_map(|i| i.funs.iter())))
+        .map(|f| (Rc::as_ptr(&f.ident.symbol), f.block.as_ref()))
+        .collect();
+
+    for (i, g) in module.globals.iter().enumerate() {
+        // the initializer also refers to the symbols the bodies of the functions it refers to refer to
+        let mut refs = refs(g.expr.as_ref(), &methods);
+        let mut visited = HashSet::new();
+        let mut next = 0;
+        while next < refs.len() {
+            let symbol = Rc::as_ptr(&refs[next]);
+            if let Some(block) = blocks.get(&symbol).filter(|_| visited.insert(symbol)) {
+                refs.extend(self::refs(*block, &methods));
+            }
+            next += 1;
+        }
+
+        let later = module.globals[i..].iter().find(|l| refs.iter().any(|r| Rc::ptr_eq(r, &l.ident.symbol)));
+        if let Some(l) = later {
+            return Some((g, l.ident.name.clone()));
+        }
+    }
+
+    None
+}
+
+/// Collects the symbols an AST node refers to, where a call in method syntax refers to every method of that name.
+fn refs(node: &impl AstDeps, methods: &HashMap<&str, Vec<SymbolRef>>) -> Vec<SymbolRef> {
+    let mut deps = Deps::default();
+    node.deps(&mut deps);
+    let mut refs = deps.symbols;
+    for m in &deps.methods {
+        refs.extend(methods.get(m.as_str()).into_iter().flatten().cloned());
+    }
+
+    refs
+}
+
+/// Gets the methods of a program by name, which are the functions declared in impls.
+fn methods(program: &Program) -> HashMap<&str, Vec<SymbolRef>> {
+    let mut methods: HashMap<&str, Vec<SymbolRef>> = HashMap::new();
+    for f in program.modules.iter().flat_map(|m| m.impls.iter()).flat_map(|i| i.funs.iter()) {
+        methods.entry(f.ident.name.as_str()).or_default().push(f.ident.symbol.clone());
+    }
+
+    methods
+}
+
+/// Tarjan's strongly connected components algorithm, which finds the groups in reverse topological order.
+struct Tarjan<'a> {
+    edges: &'a [Vec<usize>],
+    index: Vec<Option<usize>>,
+    low: Vec<usize>,
+    on_stack: Vec<bool>,
+    stack: Vec<usize>,
+    next: usize,
+    groups: Vec<Vec<usize>>,
+}
+
+impl Tarjan<'_> {
+    fn connect(&mut self, v: usize) {
+        self.index[v] = Some(self.next);
+        self.low[v] = self.next;
+        self.next += 1;
+        self.stack.push(v);
+        self.on_stack[v] = true;
+
+        for &w in &self.edges[v] {
+            match self.index[w] {
+                None => {
+                    self.connect(w);
+                    self.low[v] = self.low[v].min(self.low[w]);
+                }
+                Some(i) if self.on_stack[w] => self.low[v] = self.low[v].min(i),
+                _ => {}
+            }
+        }
+
+        if Some(self.low[v]) == self.index[v] {
+            let mut group = vec![];
+            loop {
+                let w = self.stack.pop().expect("stack is empty");
+                self.on_stack[w] = false;
+                group.push(w);
+                if w == v {
+                    break;
+                }
+            }
+
+            group.reverse();
+            self.groups.push(group);
+        }
+    }
+}
+
+/// The symbols an AST node refers to, and the names of the methods it calls in method syntax,
+/// which are only resolved once the types of their receivers are inferred.
+#[derive(Default)]
+struct Deps {
+    symbols: Vec<SymbolRef>,
+    methods: Vec<String>,
+}
+
+/// Collects the symbols an AST node refers to.
+trait AstDeps {
+    fn deps(&self, deps: &mut Deps);
+}
+
+impl AstDeps for Block {
+    fn deps(&self, deps: &mut Deps) {
+        for s in &self.stmts {
+            s.deps(deps);
+        }
+    }
+}
+
+impl AstDeps for Stmt {
+    fn deps(&self, deps: &mut Deps) {
+        match self {
+            Stmt::Let(s) => s.expr.deps(deps),
+            Stmt::LetTuple(s) => s.expr.deps(deps),
+            Stmt::Set(s) => {
+                s.target.deps(deps);
+                s.expr.deps(deps);
+            }
+            Stmt::If(s) => {
+                for c in &s.conds {
+                    c.deps(deps);
+                }
+
+                for b in &s.then_blocks {
+                    b.deps(deps);
+                }
+
+                if let Some(b) = &s.else_block {
+                    b.deps(deps);
+                }
+            }
+            Stmt::While(s) => {
+                s.cond.deps(deps);
+                s.block.deps(deps);
+            }
+            Stmt::For(s) => {
+                s.iter.deps(deps);
+                s.block.deps(deps);
+            }
+            Stmt::Return(s) => {
+                if let Some(e) = &s.expr {
+                    e.deps(deps);
+                }
+            }
+            Stmt::Break(_) | Stmt::Continue(_) => {}
+            Stmt::Expr(s) => s.expr.deps(deps),
+            Stmt::Block(s) => s.deps(deps),
+        }
+    }
+}
+
+impl AstDeps for Expr {
+    fn deps(&self, deps: &mut Deps) {
+        match self {
+            Expr::IntLit(_) | Expr::FloatLit(_) | Expr::CharLit(_) | Expr::BoolLit(_) | Expr::StringLit(_) | Expr::NoneLit(_) => {}
+            Expr::Ident(e) => deps.symbols.push(e.ident.symbol.clone()),
+            Expr::Call(e) => {
+                if let Expr::Field(f) = e.fun.as_ref() {
+                    deps.methods.push(f.field.name.clone());
+                    deps.symbols.extend(e.fallback.clone());
+                }
+
+                e.fun.deps(deps);
+                for a in &e.args {
+                    a.deps(deps);
+                }
+            }
+            Expr::Unary(e) => e.expr.deps(deps),
+            Expr::Binary(e) => {
+                e.left.deps(deps);
+                e.right.deps(deps);
+            }
+            Expr::Range(e) => {
+                e.start.deps(deps);
+                e.end.deps(deps);
+            }
+            Expr::ListLit(e) => {
+                for e in &e.elems {
+                    e.deps(deps);
+                }
+            }
+            Expr::MapLit(e) => {
+                for (k, v) in &e.entries {
+                    k.deps(deps);
+                    v.deps(deps);
+                }
+            }
+            Expr::SetLit(e) => {
+                for e in &e.elems {
+                    e.deps(deps);
+                }
+            }
+            Expr::TupleLit(e) => {
+                for e in &e.elems {
+                    e.deps(deps);
+                }
+            }
+            Expr::InterpString(e) => {
+                for p in &e.parts {
+                    p.deps(deps);
+                }
+            }
+            Expr::Index(e) => {
+                e.expr.deps(deps);
+                e.index.deps(deps);
+            }
+            Expr::Field(e) => e.expr.deps(deps),
+            Expr::Cast(e) => e.expr.deps(deps),
+            Expr::Is(e) => e.expr.deps(deps),
+            Expr::Try(e) => e.expr.deps(deps),
+            Expr::Match(e) => {
+                e.expr.deps(deps);
+                for a in &e.arms {
+                    match &a.body {
+                        ArmBody::Expr(e) => e.deps(deps),
+                        ArmBody::Block(b) => b.deps(deps),
+                    }
+                }
+            }
+            Expr::Lambda(e) => match &e.body {
+                LambdaBody::Expr(e) => e.deps(deps),
+                LambdaBody::Block(b) => b.deps(deps),
+            },
+        }
+    }
+}
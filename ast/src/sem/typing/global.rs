@@ -1,38 +1,250 @@
 use crate::ast::*;
-use crate::sem::SemResult;
+use crate::symbol::SymbolRef;
+use crate::sem::{SemError, SemResult};
+use crate::sem::typing::infer::Infer;
 
 /// Annotates global types.
 pub(crate) fn global(program: &mut Program) -> SemResult<()> {
-    program.main_module.visit()?;
+    // the types of functions that are not annotated are inferred within the bindings of the functions
+    let mut infer = Infer::new();
+    infer.enter();
+    for m in &mut program.modules {
+        m.visit(&mut infer)?;
+    }
+
     Ok(())
 }
 
 trait AstTyping {
-    fn visit(&mut self) -> SemResult<PineType>;
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType>;
 }
 
 impl AstTyping for Module {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        for o in &mut self.objs {
+            o.visit(infer)?;
+        }
+
+        for e in &mut self.enums {
+            e.visit(infer)?;
+        }
+
+        for i in &mut self.interfaces {
+            i.visit(infer)?;
+        }
+
         for f in &mut self.funs {
-            f.visit()?;
+            f.visit(infer)?;
+        }
+
+        for i in &mut self.impls {
+            i.visit(infer)?;
+        }
+
+        for g in &mut self.globals {
+            g.visit(infer)?;
         }
 
         Ok(PineType::Void)
     }
 }
 
-impl AstTyping for Fun {
-    fn visit(&mut self) -> SemResult<PineType> {
+impl AstTyping for Obj {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let obj = self.ident.symbol.borrow().obj.clone().expect("object has no definition");
+        let mut fields: Vec<(String, PineType)> = vec![];
+        for f in &mut self.fields {
+            if fields.iter().any(|(n, _)| *n == f.ident.name) {
+                return Err(SemError::error(format!("field {} has already been defined", f.ident.name), f.ident.span()));
+            }
+
+            fields.push((f.ident.name.clone(), f.ty.visit(infer)?));
+        }
+
+        // the object is constructed by calling it with a value for each field,
+        // and the constructor of a generic object is generic over its type parameters
+        let constructor_type = PineType::Function {
+            params: fields.iter().map(|(_, t)| t.clone()).collect(),
+            ret: Box::new(PineType::Object(obj.clone(), obj.borrow().params.clone())),
+        };
+        obj.borrow_mut().fields = fields;
+        self.ident.symbol.borrow_mut().pine_type = constructor_type.clone();
+        Ok(constructor_type)
+    }
+}
+
+impl AstTyping for Enum {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let enum_def = self.ident.symbol.borrow().enum_def.clone().expect("enum has no definition");
+        let enum_type = PineType::Enum(enum_def.clone(), enum_def.borrow().params.clone());
+        let mut variants = vec![];
+        for v in &mut self.variants {
+            let mut payload = vec![];
+            for t in &mut v.payload {
+                payload.push(t.visit(infer)?);
+            }
+
+            // a variant with a payload is constructed by calling it with a value for each part of the payload,
+            // and the constructor of a variant of a generic enum is generic over its type parameters
+            v.ident.symbol.borrow_mut().pine_type = if payload.is_empty() {
+                enum_type.clone()
+            } else {
+                PineType::Function { params: payload.clone(), ret: Box::new(enum_type.clone()) }
+            };
+            variants.push((v.ident.name.clone(), payload));
+        }
+
+        enum_def.borrow_mut().variants = variants;
+        Ok(enum_type)
+    }
+}
+
+impl AstTyping for Interface {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let iface = self.ident.symbol.borrow().iface.clone().expect("interface has no definition");
+        let iface_type = PineType::Interface(iface);
+        for m in &mut self.methods {
+            let method_type = m.visit(infer)?;
+            let self_type = match &method_type {
+                PineType::Function { params, .. } => params.first().cloned(),
+                _ => None,
+            };
+
+            if self_type != Some(iface_type.clone()) {
+                let msg = format!("the first parameter of method {} must have type {}", m.ident.name, iface_type);
+                return Err(SemError::error(msg, m.span()));
+            }
+        }
+
+        Ok(PineType::Void)
+    }
+}
+
+impl AstTyping for MethodSig {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
         let mut param_types: Vec<PineType> = vec![];
         for p in &mut self.params {
-            param_types.push(p.visit()?);
+            match &mut p.ty {
+                Some(t) => param_types.push(t.visit(infer)?),
+                None => {
+                    let msg = format!("parameter {} of method {} must have a type", p.ident.name, self.ident.name);
+                    return Err(SemError::error(msg, p.span()));
+                }
+            }
         }
 
         let return_type = match &mut self.return_ty {
-            Some(t) => t.visit()?,
+            Some(t) => t.visit(infer)?,
             None => PineType::Void,
         };
 
+        let method_type = PineType::Function {
+            params: param_types,
+            ret: Box::new(return_type),
+        };
+
+        self.ident.symbol.borrow_mut().pine_type = method_type.clone();
+        Ok(method_type)
+    }
+}
+
+impl AstTyping for Impl {
+    /// Checks that the implementing functions match the methods of the interface,
+    /// with the object in place of the interface as the first parameter.
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let (iface, obj) = match (self.iface.as_ref().map(|i| &i.ty), &self.obj.ty) {
+            (Some(PineType::Interface(iface)), PineType::Object(obj, _)) => (iface.clone(), obj.clone()),
+            (None, obj_type @ PineType::Object(..)) => return check_methods(&mut self.funs, obj_type, infer),
+            _ => panic!("impl is not of an interface for an object"),
+        };
+
+        let iface_name = iface.borrow().name.clone();
+        let mut methods: Vec<Option<SymbolRef>> = vec![None; iface.borrow().methods.len()];
+        for f in &mut self.funs {
+            let fun_type = f.visit(infer)?;
+            let (i, method_type) = match iface.borrow().method(&f.ident.name) {
+                Some((i, m)) => (i, m.borrow().pine_type.clone()),
+                None => return Err(SemError::error(format!("method {} is not a member of interface {}", f.ident.name, iface_name), f.ident.span())),
+            };
+
+            let expected = match method_type {
+                PineType::Function { mut params, ret } => {
+                    params[0] = PineType::Object(obj.clone(), vec![]);
+                    PineType::Function { params, ret }
+                }
+                _ => panic!("method is not a function"),
+            };
+
+            if !infer.unifies(&fun_type, &expected) {
+                return Err(SemError::error(format!("method {} must have type {}", f.ident.name, expected), f.ident.span()));
+            }
+
+            methods[i] = Some(f.ident.symbol.clone());
+        }
+
+        if let Some(i) = methods.iter().position(|m| m.is_none()) {
+            let msg = format!("method {} of interface {} is not implemented", iface.borrow().methods[i].borrow().name, iface_name);
+            return Err(SemError::error(msg, self.span()));
+        }
+
+        let mut iface = iface.borrow_mut();
+        let impl_def = iface.impls.iter_mut().find(|d| d.obj == obj).expect("impl is not registered");
+        impl_def.methods = methods.into_iter().flatten().collect();
+        Ok(PineType::Void)
+    }
+}
+
+/// Checks that the methods of an object take the object as the first parameter,
+/// and that the methods that overload operators or convert the object to a string have the types they are used with.
+fn check_methods(funs: &mut [Fun], obj_type: &PineType, infer: &mut Infer) -> SemResult<PineType> {
+    for f in funs {
+        let (params, ret) = match f.visit(infer)? {
+            PineType::Function { params, ret } => (params, *ret),
+            _ => panic!("method is not a function"),
+        };
+
+        let name = f.ident.name.clone();
+        if params.is_empty() || !infer.unifies(&params[0], obj_type) {
+            return Err(SemError::error(format!("the first parameter of method {} must have type {}", name, obj_type), f.ident.span()));
+        }
+
+        let arity = match name.as_str() {
+            "neg" | "to_string" => 1,
+            "eq" | "lt" | "add" | "sub" | "mul" | "div" | "pow" | "mod" => 2,
+            _ => continue,
+        };
+
+        // the types of operator methods must be annotated, since operators are typed before the methods are inferred
+        let expected = match name.as_str() {
+            "eq" | "lt" => PineType::Function { params: vec![obj_type.clone(), obj_type.clone()], ret: Box::new(PineType::Bool) },
+            "to_string" => PineType::Function { params: vec![obj_type.clone()], ret: Box::new(PineType::String) },
+            _ => PineType::Function {
+                params: std::iter::once(obj_type.clone()).chain((1..arity).map(|_| infer.fresh())).collect(),
+                ret: Box::new(infer.fresh()),
+            },
+        };
+        let known = params.iter().chain([&ret]).all(|t| !t.is_unknown()) && ret != PineType::Void;
+        let fun_type = PineType::Function { params, ret: Box::new(ret) };
+        if !known || !infer.unifies(&fun_type, &expected) {
+            return Err(SemError::error(format!("method {} must have type {}", name, expected), f.ident.span()));
+        }
+    }
+
+    Ok(PineType::Void)
+}
+
+impl AstTyping for Fun {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let mut param_types: Vec<PineType> = vec![];
+        for p in &mut self.params {
+            param_types.push(p.visit(infer)?);
+        }
+
+        let return_type = match &mut self.return_ty {
+            Some(t) => t.visit(infer)?,
+            None => infer.fresh(),
+        };
+
         let function_type = PineType::Function {
             params: param_types,
             ret: Box::new(return_type),
@@ -43,16 +255,30 @@ impl AstTyping for Fun {
     }
 }
 
+impl AstTyping for Global {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let global_type = match &mut self.ty {
+            Some(t) => t.visit(infer)?,
+            None => infer.fresh(),
+        };
+        self.ident.symbol.borrow_mut().pine_type = global_type.clone();
+        Ok(global_type)
+    }
+}
+
 impl AstTyping for Param {
-    fn visit(&mut self) -> SemResult<PineType> {
-        let param_type = self.ty.visit()?;
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let param_type = match &mut self.ty {
+            Some(t) => t.visit(infer)?,
+            None => infer.fresh(),
+        };
         self.ident.symbol.borrow_mut().pine_type = param_type.clone();
         Ok(param_type)
     }
 }
 
 impl AstTyping for Ty {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, _infer: &mut Infer) -> SemResult<PineType> {
         Ok(self.ty.clone())
     }
 }
@@ -0,0 +1,492 @@
+use crate::ast::*;
+use crate::builtin::{self, Builtin};
+use crate::operator::Operator;
+use crate::sem::{SemError, SemResult};
+use crate::token::Span;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// Simple Alg: https://pfudke.wordpress.com/2014/11/20/hindley-milner-type-inference-a-practical-example-2/
+// Efficient Alg: https://okmij.org/ftp/ML/generalization.html
+
+/// Infers types by unifying type variables.
+///
+/// Type variables are created at the let level of the binding they are inferred in.
+/// When a binding is complete, the type variables at deeper levels do not escape it, and are generalised.
+pub(crate) struct Infer {
+    /// The current let level.
+    level: usize,
+    /// The checks that wait for a type to be inferred.
+    deferred: Vec<Deferred>,
+    /// The operations that the type arguments of each type parameter must support, by the id of the type parameter.
+    constraints: HashMap<usize, Vec<Constraint>>,
+    /// The type parameters of the function being typed, which stand for a single unknown type within it.
+    rigid: Vec<usize>,
+    /// The spans of the constructs that bound type variables, by the address of the type variable,
+    /// which a construct that conflicts with the type a variable was bound to is reported against.
+    origins: RefCell<HashMap<*const RefCell<TypeVar>, Span>>,
+}
+
+/// An operation on a value of a type parameter, which is checked for every type argument of the type parameter.
+#[derive(Clone, Copy, PartialEq)]
+enum Constraint {
+    Unary(Operator),
+    Binary(Operator),
+    Builtin(Builtin),
+    /// Used as the key of a map or the element of a set.
+    Key,
+}
+
+/// A check that is deferred until the type it depends on has been inferred.
+enum Deferred {
+    /// A unary operator applied to an operand of the type.
+    Unary { op: Operator, ty: PineType, span: Span },
+    /// A binary operator applied to operands of the type.
+    Binary { op: Operator, ty: PineType, span: Span },
+    /// A builtin called with arguments of the types.
+    Builtin { builtin: Builtin, args: Vec<PineType>, span: Span },
+    /// A key of a map or an element of a set, which must be hashable.
+    Key { ty: PineType, span: Span },
+    /// A field of a value of the type, which has the field type.
+    Field { ty: PineType, field: String, field_ty: PineType, span: Span },
+}
+
+impl Deferred {
+    /// Determines if the type the check depends on is still unknown.
+    fn is_unknown(&self) -> bool {
+        match self {
+            Deferred::Unary { ty, .. }
+            | Deferred::Binary { ty, .. }
+            | Deferred::Key { ty, .. }
+            | Deferred::Field { ty, .. } => ty.is_unknown(),
+            Deferred::Builtin { args, .. } => args.iter().any(|a| a.is_unknown()),
+        }
+    }
+
+    /// Gets the unknown types that default to int when nothing else is inferred about them.
+    fn defaults(&self) -> Vec<PineType> {
+        match self {
+            Deferred::Unary { ty, .. } | Deferred::Binary { ty, .. } => vec![ty.clone()],
+            Deferred::Builtin { builtin: Builtin::Print | Builtin::Println, args, .. } => {
+                args.iter().filter(|a| a.is_unknown()).cloned().collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            Deferred::Unary { span, .. }
+            | Deferred::Binary { span, .. }
+            | Deferred::Builtin { span, .. }
+            | Deferred::Key { span, .. }
+            | Deferred::Field { span, .. } => *span,
+        }
+    }
+}
+
+impl Infer {
+    pub fn new() -> Self {
+        Self { level: 0, deferred: vec![], constraints: HashMap::new(), rigid: vec![], origins: RefCell::new(HashMap::new()) }
+    }
+
+    /// Sets the type parameters of the function being typed, which are not instantiated within it.
+    pub fn set_rigid(&mut self, type_params: Vec<usize>) {
+        self.rigid = type_params;
+    }
+
+    /// Enters a binding.
+    pub fn enter(&mut self) {
+        self.level += 1;
+    }
+
+    /// Leaves a binding.
+    pub fn leave(&mut self) {
+        self.level -= 1;
+    }
+
+    /// Creates a fresh type variable at the current let level.
+    pub fn fresh(&self) -> PineType {
+        PineType::var(self.level)
+    }
+
+    /// Replaces the type parameters of a generic type with fresh type variables.
+    /// The operations on values of the type parameters are checked for the types the type variables are inferred to.
+    pub fn instantiate(&mut self, ty: &PineType, span: Span) -> PineType {
+        let mut generics = HashMap::new();
+        for id in ty.generics().into_iter().filter(|id| !self.rigid.contains(id)) {
+            let var = self.fresh();
+            for c in self.constraints.get(&id).cloned().unwrap_or_default() {
+                self.deferred.push(match c {
+                    Constraint::Unary(op) => Deferred::Unary { op, ty: var.clone(), span },
+                    Constraint::Binary(op) => Deferred::Binary { op, ty: var.clone(), span },
+                    Constraint::Builtin(builtin) => Deferred::Builtin { builtin, args: vec![var.clone()], span },
+                    Constraint::Key => Deferred::Key { ty: var.clone(), span },
+                });
+            }
+
+            generics.insert(id, var);
+        }
+
+        ty.substitute(&generics)
+    }
+
+    /// Adds an operation that the type arguments of a type parameter must support.
+    fn constrain(&mut self, generic: &PineType, constraint: Constraint) {
+        if let PineType::Generic(id, _) = generic.resolve() {
+            let constraints = self.constraints.entry(id).or_default();
+            if !constraints.contains(&constraint) {
+                constraints.push(constraint);
+            }
+        }
+    }
+
+    /// Generalises the type variables of a type that were created within the binding that was left.
+    pub fn generalise(&self, ty: &PineType) {
+        match ty.resolve() {
+            PineType::Var(v) => {
+                let id = match *v.borrow() {
+                    TypeVar::Unbound { id, level } if level > self.level => Some(id),
+                    _ => None,
+                };
+
+                if let Some(id) = id {
+                    *v.borrow_mut() = TypeVar::Bound(PineType::Generic(id, format!("T{}", id)));
+                }
+            }
+            PineType::List(t) | PineType::Set(t) | PineType::Optional(t) => self.generalise(&t),
+            PineType::Map(k, v) => {
+                self.generalise(&k);
+                self.generalise(&v);
+            }
+            PineType::Object(_, args) | PineType::Enum(_, args) | PineType::Tuple(args) => {
+                for a in &args {
+                    self.generalise(a);
+                }
+            }
+            PineType::Function { params, ret } => {
+                for p in &params {
+                    self.generalise(p);
+                }
+
+                self.generalise(&ret);
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves the type variables of a type that were created within the binding that was left to the current level,
+    /// for a binding that cannot be generalised.
+    pub fn restrict(&self, ty: &PineType) {
+        adjust_levels(ty, self.level);
+    }
+
+    /// Unifies the type of a construct with the type expected of it.
+    /// A type that was bound by another construct conflicts with that construct rather than the expected one.
+    pub fn unify(&self, expected: &PineType, expected_span: Span, actual: &PineType, actual_span: Span) -> SemResult<()> {
+        let expected_span = self.origin(expected).unwrap_or(expected_span);
+        let (expected_var, actual_var) = (unbound_var(expected), unbound_var(actual));
+        let (expected, actual) = (expected.resolve_all(), actual.resolve_all());
+        if self.unifies(&expected, &actual) {
+            let mut origins = self.origins.borrow_mut();
+            match (expected_var, actual_var) {
+                (Some(v), None) => origins.insert(v, actual_span),
+                (None, Some(v)) => origins.insert(v, expected_span),
+                _ => None,
+            };
+            return Ok(());
+        }
+
+        // an optional that is used where a value is expected has not been checked for none, wherever the value is expected
+        if matches!(actual, PineType::Optional(_)) && !matches!(expected, PineType::Optional(_)) {
+            return Err(SemError::error(unchecked(&actual), actual_span));
+        }
+
+        let msg = format!("types do not match, expected {} but found {}", expected, actual);
+        if expected_span == actual_span {
+            Err(SemError::error(msg, actual_span))
+        } else {
+            Err(SemError::conflict(msg, actual_span, expected_span))
+        }
+    }
+
+    /// Gets the span of the construct that bound a type variable, if the type is a bound type variable.
+    fn origin(&self, ty: &PineType) -> Option<Span> {
+        let v = match ty {
+            PineType::Var(v) => v,
+            _ => return None,
+        };
+
+        if let Some(span) = self.origins.borrow().get(&Rc::as_ptr(v)) {
+            return Some(*span);
+        }
+
+        match &*v.borrow() {
+            TypeVar::Bound(t) => self.origin(t),
+            TypeVar::Unbound { .. } => None,
+        }
+    }
+
+    /// Unifies two types, returning whether they could be unified.
+    pub fn unifies(&self, a: &PineType, b: &PineType) -> bool {
+        match (a.resolve(), b.resolve()) {
+            (PineType::Var(x), PineType::Var(y)) if Rc::ptr_eq(&x, &y) => true,
+            (PineType::Var(v), t) | (t, PineType::Var(v)) => bind(&v, t),
+            (PineType::List(x), PineType::List(y)) | (PineType::Set(x), PineType::Set(y)) | (PineType::Optional(x), PineType::Optional(y)) => self.unifies(&x, &y),
+            (PineType::Map(xk, xv), PineType::Map(yk, yv)) => self.unifies(&xk, &yk) && self.unifies(&xv, &yv),
+            (PineType::Object(x, xa), PineType::Object(y, ya)) => {
+                x == y && xa.len() == ya.len() && xa.iter().zip(&ya).all(|(x, y)| self.unifies(x, y))
+            }
+            (PineType::Enum(x, xa), PineType::Enum(y, ya)) => {
+                x == y && xa.len() == ya.len() && xa.iter().zip(&ya).all(|(x, y)| self.unifies(x, y))
+            }
+            (PineType::Tuple(x), PineType::Tuple(y)) => {
+                x.len() == y.len() && x.iter().zip(&y).all(|(x, y)| self.unifies(x, y))
+            }
+            (PineType::Function { params: xp, ret: xr }, PineType::Function { params: yp, ret: yr }) => {
+                xp.len() == yp.len()
+                    && xp.iter().zip(&yp).all(|(x, y)| self.unifies(x, y))
+                    && self.unifies(&xr, &yr)
+            }
+            (x, y) => x == y,
+        }
+    }
+
+    /// Defers checking a unary operator until the type of its operand is inferred.
+    pub fn defer_unary(&mut self, op: Operator, ty: PineType, span: Span) {
+        self.deferred.push(Deferred::Unary { op, ty, span });
+    }
+
+    /// Defers checking a binary operator until the type of its operands is inferred.
+    pub fn defer_binary(&mut self, op: Operator, ty: PineType, span: Span) {
+        self.deferred.push(Deferred::Binary { op, ty, span });
+    }
+
+    /// Defers checking that a type can be used as a key until it is inferred.
+    pub fn defer_key(&mut self, ty: PineType, span: Span) {
+        self.deferred.push(Deferred::Key { ty, span });
+    }
+
+    /// Defers checking a builtin call until the types of its arguments are inferred.
+    pub fn defer_builtin(&mut self, builtin: Builtin, args: Vec<PineType>, span: Span) {
+        self.deferred.push(Deferred::Builtin { builtin, args, span });
+    }
+
+    /// Defers looking up a field until the type of the value is inferred. Returns the type of the field.
+    pub fn defer_field(&mut self, ty: PineType, field: String, span: Span) -> PineType {
+        let field_ty = self.fresh();
+        self.deferred.push(Deferred::Field { ty, field, field_ty: field_ty.clone(), span });
+        field_ty
+    }
+
+    /// Runs the deferred checks. The operands of operators and the values that are printed default to int
+    /// if nothing else is inferred about them.
+    pub fn solve(&mut self) -> SemResult<()> {
+        loop {
+            let deferred = std::mem::take(&mut self.deferred);
+            let count = deferred.len();
+            for d in deferred {
+                if d.is_unknown() {
+                    self.deferred.push(d);
+                } else {
+                    self.check(d)?;
+                }
+            }
+
+            if self.deferred.is_empty() {
+                return Ok(());
+            }
+
+            if self.deferred.len() < count {
+                continue;
+            }
+
+            let defaults: Vec<PineType> = self.deferred.iter().flat_map(|d| d.defaults()).collect();
+            if defaults.is_empty() {
+                let span = self.deferred[0].span();
+                return Err(SemError::error("the type of the expression cannot be inferred", span));
+            }
+
+            for t in defaults {
+                self.unifies(&t, &PineType::Integer);
+            }
+        }
+    }
+
+    /// Checks a deferred check whose type has been inferred.
+    /// A check on a value of a type parameter becomes a constraint on the type parameter.
+    fn check(&mut self, deferred: Deferred) -> SemResult<()> {
+        match deferred {
+            Deferred::Unary { op, ty, .. } if ty.is_generic() => self.constrain(&ty, Constraint::Unary(op)),
+            Deferred::Binary { op, ty, .. } if ty.is_generic() => self.constrain(&ty, Constraint::Binary(op)),
+            Deferred::Builtin { builtin: builtin @ (Builtin::Print | Builtin::Println), args, .. } if args.iter().any(|a| a.is_generic()) => {
+                for a in &args {
+                    self.constrain(a, Constraint::Builtin(builtin));
+                }
+            }
+            // a precision is only checked once the type of the value is known,
+            // and the elements of a collection are checked once their types are
+            Deferred::Builtin { builtin: Builtin::ToString, args, span } if args.len() == 1 => {
+                for t in builtin::converted_types(&args[0]) {
+                    if t.is_unknown() {
+                        self.defer_builtin(Builtin::ToString, vec![t], span);
+                    } else if t.is_generic() {
+                        self.constrain(&t, Constraint::Builtin(Builtin::ToString));
+                    } else {
+                        self.check_known(Deferred::Builtin { builtin: Builtin::ToString, args: vec![t], span })?;
+                    }
+                }
+            }
+            Deferred::Key { ty, .. } if ty.is_generic() => self.constrain(&ty, Constraint::Key),
+            deferred => return self.check_known(deferred),
+        }
+
+        Ok(())
+    }
+
+    /// Checks a deferred check whose type is a known type.
+    fn check_known(&self, deferred: Deferred) -> SemResult<()> {
+        match deferred {
+            Deferred::Unary { op, ty, span } if matches!(ty.resolve(), PineType::Object(..)) => {
+                let (_, ret, def_span) = operator_method(&ty, op, op.unary_method(), span)?;
+                self.unify(&ret, def_span, &ty, span)
+            }
+            Deferred::Binary { op, ty, span } if matches!(ty.resolve(), PineType::Object(..)) => {
+                // the operands of a deferred operator have the same type, as does the result of arithmetic
+                let (params, ret, def_span) = operator_method(&ty, op, op.binary_method(), span)?;
+                self.unify(&params[1], def_span, &ty, span)?;
+                if op.is_comparison() {
+                    Ok(())
+                } else {
+                    self.unify(&ret, def_span, &ty, span)
+                }
+            }
+            Deferred::Unary { op, ty, span } => op.unary_pine_type(ty.resolve_all()).map(|_| ()).map_err(|e| SemError::error(e, span)),
+            Deferred::Binary { op, ty, span } => op.binary_pine_type(ty.resolve_all(), ty.resolve_all()).map(|_| ()).map_err(|e| SemError::error(e, span)),
+            Deferred::Builtin { builtin, args, span } => {
+                let args: Vec<PineType> = args.iter().map(|a| a.resolve_all()).collect();
+                builtin.call_pine_type(&args).map(|_| ()).map_err(|e| SemError::error(e, span))
+            }
+            Deferred::Key { ty, span } => match ty.resolve() {
+                PineType::Integer | PineType::UInteger | PineType::Byte | PineType::Char | PineType::String | PineType::Bool => Ok(()),
+                t => Err(SemError::error(format!("type {} cannot be used as a key", t), span)),
+            },
+            Deferred::Field { ty, field, field_ty, span } => {
+                let ty = ty.resolve();
+                let found = match &ty {
+                    PineType::Object(o, args) => o.borrow().instance_field(&field, args),
+                    _ => None,
+                };
+
+                match found {
+                    Some((_, t)) => self.unify(&t, span, &field_ty, span),
+                    None => Err(SemError::error(format!("type {} has no field {}", ty, field), span)),
+                }
+            }
+        }
+    }
+}
+
+/// Gets the parameter types, return type and definition span of the method that overloads an operator for an object type.
+pub(crate) fn operator_method(ty: &PineType, op: Operator, method: Option<&str>, span: Span) -> SemResult<(Vec<PineType>, PineType, Span)> {
+    let symbol = match (ty.resolve(), method) {
+        (PineType::Object(o, _), Some(m)) => o.borrow().method(m),
+        _ => None,
+    };
+
+    let symbol = match symbol {
+        Some(s) => s,
+        None => return Err(SemError::error(format!("Operator `{}` is not defined for type {}", op, ty), span)),
+    };
+
+    let symbol = symbol.borrow();
+    let (params, ret) = match &symbol.pine_type {
+        PineType::Function { params, ret } => (params, ret),
+        _ => panic!("method is not a function"),
+    };
+
+    // the type parameters of a method of a generic object stand for the type arguments of the object
+    let subst: HashMap<usize, PineType> = match (params[0].resolve(), ty.resolve()) {
+        (PineType::Object(_, generics), PineType::Object(_, args)) => generics.iter().zip(args)
+            .filter_map(|(g, a)| match g.resolve() {
+                PineType::Generic(id, _) => Some((id, a)),
+                _ => None,
+            })
+            .collect(),
+        _ => HashMap::new(),
+    };
+
+    let params = params.iter().map(|p| p.substitute(&subst)).collect();
+    Ok((params, ret.substitute(&subst), symbol.span))
+}
+
+/// Gets the address of the type variable a type resolves to, if it is an unbound type variable.
+fn unbound_var(ty: &PineType) -> Option<*const RefCell<TypeVar>> {
+    match ty.resolve() {
+        PineType::Var(v) => Some(Rc::as_ptr(&v)),
+        _ => None,
+    }
+}
+
+/// Binds a type variable to a type, unless the type contains the type variable.
+fn bind(v: &TypeVarRef, t: PineType) -> bool {
+    let level = match *v.borrow() {
+        TypeVar::Unbound { level, .. } => level,
+        TypeVar::Bound(_) => panic!("type variable is already bound"),
+    };
+
+    if occurs(v, &t) {
+        return false;
+    }
+
+    adjust_levels(&t, level);
+    *v.borrow_mut() = TypeVar::Bound(t);
+    true
+}
+
+/// Gets the message for an optional that is used as a value before it is checked for none.
+pub(crate) fn unchecked(ty: &PineType) -> String {
+    format!("value of type {} must be checked for none before it is used", ty)
+}
+
+/// Determines if a type variable occurs within a type.
+fn occurs(v: &TypeVarRef, ty: &PineType) -> bool {
+    match ty.resolve() {
+        PineType::Var(u) => Rc::ptr_eq(v, &u),
+        PineType::List(t) | PineType::Set(t) | PineType::Optional(t) => occurs(v, &t),
+        PineType::Map(k, t) => occurs(v, &k) || occurs(v, &t),
+        PineType::Object(_, args) | PineType::Enum(_, args) | PineType::Tuple(args) => args.iter().any(|a| occurs(v, a)),
+        PineType::Function { params, ret } => params.iter().any(|p| occurs(v, p)) || occurs(v, &ret),
+        _ => false,
+    }
+}
+
+/// Moves the type variables of a type that are deeper than a let level to the level.
+fn adjust_levels(ty: &PineType, level: usize) {
+    match ty.resolve() {
+        PineType::Var(v) => {
+            if let TypeVar::Unbound { level: l, .. } = &mut *v.borrow_mut() {
+                *l = (*l).min(level);
+            }
+        }
+        PineType::List(t) | PineType::Set(t) | PineType::Optional(t) => adjust_levels(&t, level),
+        PineType::Map(k, v) => {
+            adjust_levels(&k, level);
+            adjust_levels(&v, level);
+        }
+        PineType::Object(_, args) | PineType::Enum(_, args) | PineType::Tuple(args) => {
+            for a in &args {
+                adjust_levels(a, level);
+            }
+        }
+        PineType::Function { params, ret } => {
+            for p in &params {
+                adjust_levels(p, level);
+            }
+
+            adjust_levels(&ret, level);
+        }
+        _ => {}
+    }
+}
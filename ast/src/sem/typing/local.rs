@@ -1,99 +1,388 @@
 use crate::ast::*;
-use crate::sem;
-use crate::sem::{SemError, SemResult};
-
-// Simple Alg: https://pfudke.wordpress.com/2014/11/20/hindley-milner-type-inference-a-practical-example-2/
-// Efficient Alg: https://okmij.org/ftp/ML/generalization.html
+use crate::builtin::{self, Builtin};
+use crate::operator::Operator;
+use crate::sem::{constant, SemError, SemResult};
+use crate::sem::typing::deps::{self, Binding};
+use crate::sem::typing::infer::{operator_method, unchecked, Infer};
+use crate::symbol::SymbolRef;
+use crate::token::Span;
 
 /// Annotates local types.
+///
+/// The functions and global variables are typed in groups of mutually recursive bindings, and the types of
+/// the functions in a group are generalised before the functions are used by the groups that follow.
 pub(crate) fn local(program: &mut Program) -> SemResult<()> {
-    program.main_module.visit()?;
+    // global variables are initialized in order, so they cannot refer to the global variables after them
+    for m in &program.modules {
+        if let Some((g, name)) = deps::uninitialized_global(program, m) {
+            let msg = format!("global variable {} is used before it is initialized", name);
+            return Err(SemError::error(msg, g.expr.span()));
+        }
+    }
+
+    let mut infer = Infer::new();
+    for group in deps::binding_groups(program) {
+        infer.enter();
+        for b in &group {
+            match b.get_mut(program) {
+                Binding::Fun(f) => f.visit(&mut infer)?,
+                Binding::Global(g) => g.visit(&mut infer)?,
+            };
+        }
 
-    let main_fun = match program.main_module.scope().borrow().lookup("main") {
+        infer.solve()?;
+        infer.leave();
+
+        // global variables may be assigned, so their types are not generalised,
+        // and are restricted before the types of the functions that share them are generalised
+        for b in &group {
+            if let Binding::Global(g) = b.get_mut(program) {
+                infer.restrict(&g.ident.symbol.borrow().pine_type);
+            }
+        }
+
+        for b in &group {
+            if let Binding::Fun(f) = b.get_mut(program) {
+                infer.generalise(&f.ident.symbol.borrow().pine_type);
+            }
+        }
+    }
+
+    let main_module = program.main_module();
+    let main_fun = match main_module.scope().borrow().lookup("main") {
         Some(main_symbol) => main_symbol,
-        None => return Err(SemError::error("no main function found", program.main_module.span())),
+        None => return Err(SemError::error("no main function found", main_module.span())),
     };
 
-    match &main_fun.borrow().pine_type {
+    match main_fun.borrow().pine_type.resolve() {
         PineType::Function { ret, .. } => { // TODO ensure params make sense too
-            if **ret != PineType::Void && **ret != PineType::Integer {
-                return Err(SemError::error("main must return void or int", program.main_module.span()))
+            if *ret != PineType::Void && *ret != PineType::Integer {
+                return Err(SemError::error("main must return void or int", main_module.span()))
             }
         },
-        _ => return Err(SemError::error("main must be a function", program.main_module.span()))
+        _ => return Err(SemError::error("main must be a function", main_module.span()))
     }
 
+    program.main_fun = main_fun;
     Ok(())
 }
 
 trait AstTyping {
-    fn visit(&mut self) -> SemResult<PineType>;
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType>;
 }
 
-impl AstTyping for Module {
-    fn visit(&mut self) -> SemResult<PineType> {
-        for f in &mut self.funs {
-            f.visit()?;
-        }
+impl AstTyping for Fun {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let type_params = self.type_params.iter()
+            .filter_map(|p| p.symbol.borrow().generic.clone())
+            .chain(self.impl_type_params.iter().cloned())
+            .flat_map(|g| g.generics())
+            .collect();
+        infer.set_rigid(type_params);
+        let fun_type = self.ident.visit(infer)?;
+        self.block.visit(infer)?;
+        Ok(fun_type)
+    }
+}
+
+impl AstTyping for Global {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        infer.enter();
+        let e_type = self.expr.visit(infer)?;
+        infer.leave();
 
+        let ty = self.ident.symbol.borrow().pine_type.clone();
+        let span = match &self.ty {
+            Some(t) => t.span(),
+            None => self.ident.span(),
+        };
+        coerce(infer, &ty, span, &mut self.expr, e_type)?;
+        infer.restrict(&ty);
+        self.ident.visit(infer)?;
         Ok(PineType::Void)
     }
 }
 
-impl AstTyping for Fun {
-    fn visit(&mut self) -> SemResult<PineType> {
-        let fun_type = self.ident.visit()?;
-        self.block.visit()?;
-        Ok(fun_type)
+impl AstTyping for LetStmt {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        infer.enter();
+        let e_type = self.expr.visit(infer)?;
+        infer.leave();
+
+        let ty = if let Some(ty) = &mut self.ty {
+            let n_type = ty.visit(infer)?;
+            coerce(infer, &n_type, ty.span(), &mut self.expr, e_type)?;
+            n_type
+        } else {
+            e_type
+        };
+
+        // only values are generalised, since the variable may be assigned
+        if matches!(self.expr.as_ref(), Expr::Ident(_)) {
+            infer.generalise(&ty);
+        } else {
+            infer.restrict(&ty);
+        }
+
+        self.ident.symbol.borrow_mut().pine_type = ty;
+        self.ident.visit(infer)?;
+        Ok(PineType::Void)
     }
 }
 
-impl AstTyping for LetStmt {
-    fn visit(&mut self) -> SemResult<PineType> {
-        let e_type = self.expr.visit()?;
-        self.ident.symbol.borrow_mut().pine_type = if let Some(ty) = &mut self.ty {
-            let n_type = ty.visit()?;
-            if n_type != e_type {
-                return Err(SemError::error("types do not match", self.span()));
-            }
+impl AstTyping for LetTupleStmt {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        infer.enter();
+        let e_type = self.expr.visit(infer)?;
+        infer.leave();
 
+        let ty = if let Some(ty) = &mut self.ty {
+            let n_type = ty.visit(infer)?;
+            coerce(infer, &n_type, ty.span(), &mut self.expr, e_type)?;
             n_type
         } else {
             e_type
         };
 
-        self.ident.visit()?;
+        infer.restrict(&ty);
+        check_pattern(&mut self.pattern, &ty, self.expr.span(), infer)?;
         Ok(PineType::Void)
     }
 }
 
 impl AstTyping for SetStmt {
-    fn visit(&mut self) -> SemResult<PineType> {
-        let e_type = self.expr.visit()?;
-        let i_type = self.ident.visit()?;
-        if e_type != i_type {
-           Err(SemError::error("types do not match", self.span()))
-        } else {
-            Ok(PineType::Void)
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        if !is_place(&self.target) {
+            return Err(SemError::error("expression cannot be assigned", self.target.span()));
+        }
+
+        let e_type = self.expr.visit(infer)?;
+        let t_type = self.target.visit(infer)?;
+        let (t_type, t_span) = match self.target.as_ref() {
+            // a generalised variable can only be assigned values that are as general as it
+            Expr::Ident(e) => {
+                let symbol = e.ident.symbol.borrow();
+                (symbol.pine_type.clone(), symbol.span)
+            }
+            _ => (t_type, self.target.span()),
+        };
+
+        match self.op {
+            // a compound assignment assigns the result of the operation, which must have the type of the target
+            Some(op) => {
+                let e_type = int_lit_as(&mut self.expr, e_type, &t_type)?;
+                let r_type = binary_type(infer, op, (t_type.clone(), self.target.span()), (e_type, self.expr.span()), self.span())?;
+                infer.unify(&t_type, t_span, &r_type, self.span())?;
+            }
+            None => coerce(infer, &t_type, t_span, &mut self.expr, e_type)?,
+        }
+
+        Ok(PineType::Void)
+    }
+}
+
+/// Determines if an expression is a place that can be assigned.
+fn is_place(expr: &Expr) -> bool {
+    match expr {
+        Expr::Ident(e) => {
+            let symbol = e.ident.symbol.borrow();
+            symbol.variant.is_none() && symbol.constant.is_none() && matches!(symbol.dest, pvm::Operand::Variable(_))
+        }
+        Expr::Index(_) => true,
+        Expr::Field(_) => true,
+        _ => false,
+    }
+}
+
+/// Unifies the type of an expression with the type expected of it.
+/// An object that implements the expected interface is cast to the interface,
+/// and a value that is expected to be an optional is cast to the optional.
+/// The elements of a list literal are coerced to the element type expected of the list,
+/// and an integer literal that is expected to have an unsigned integer type has that type.
+fn coerce(infer: &Infer, expected: &PineType, expected_span: Span, expr: &mut Expr, actual: PineType) -> SemResult<()> {
+    let actual = int_lit_as(expr, actual, expected)?;
+    if let PineType::Optional(t) = expected.resolve() {
+        if !matches!(actual.resolve(), PineType::Optional(_) | PineType::Var(_)) {
+            coerce(infer, &t, expected_span, expr, actual)?;
+            cast(expr, expected.clone());
+            return Ok(());
+        }
+    }
+
+    if let (PineType::List(t), Expr::ListLit(l)) = (expected.resolve(), &mut *expr) {
+        if is_coerced_to(&t) {
+            for e in &mut l.elems {
+                let e_type = e.ty();
+                coerce(infer, &t, expected_span, e, e_type)?;
+            }
+
+            l.ty = expected.clone();
+            return Ok(());
         }
     }
+
+    if let (PineType::Interface(i), PineType::Object(o, _)) = (expected.resolve(), actual.resolve()) {
+        if i.borrow().impl_for(&o).is_some() {
+            cast(expr, expected.clone());
+            return Ok(());
+        }
+    }
+
+    infer.unify(expected, expected_span, &actual, expr.span())
+}
+
+/// Determines if values are coerced to a type when they are expected to have it, which is the case for optionals
+/// and interfaces, which values are cast to, unsigned integer types, which integer literals take on, and lists of them.
+fn is_coerced_to(ty: &PineType) -> bool {
+    match ty.resolve() {
+        PineType::Optional(_) | PineType::Interface(_) | PineType::UInteger | PineType::Byte => true,
+        PineType::List(t) => is_coerced_to(&t),
+        _ => false,
+    }
+}
+
+/// Gives an integer literal that is used as a value of an integer type that type, since an integer literal
+/// can have any integer type that holds its value. Returns the type of the expression.
+fn int_lit_as(expr: &mut Expr, e_type: PineType, ty: &PineType) -> SemResult<PineType> {
+    let (lit, ty) = match (expr, ty.resolve()) {
+        (Expr::IntLit(lit), ty @ (PineType::Integer | PineType::UInteger | PineType::Byte)) => (lit, ty),
+        _ => return Ok(e_type),
+    };
+
+    constant::int_lit_value(lit, &ty)?;
+    lit.ty = ty.clone();
+    Ok(ty)
+}
+
+/// Wraps an expression in a cast to a type.
+fn cast(expr: &mut Expr, ty: PineType) {
+    let span = expr.span();
+    let scope = expr.scope();
+    let inner = std::mem::replace(expr, Expr::IntLit(IntLitExpr::new(0, span)));
+    let mut cast = CastExpr::new(Box::new(inner), span);
+    cast.set_scope(scope);
+    cast.ty = ty;
+    *expr = Expr::Cast(cast);
+}
+
+/// Gets the span of the definition of a called function, or of the called expression if it is not a function.
+fn definition_span(fun: &Expr) -> Span {
+    match fun {
+        Expr::Ident(e) => e.ident.symbol.borrow().span,
+        e => e.span(),
+    }
+}
+
+/// The function a call in method syntax calls.
+enum Callee {
+    /// A method or function that takes the receiver, of the given type, as its first argument.
+    Method(PineType),
+    /// A field of the receiver, of the given type, that holds a function.
+    Field(PineType),
+}
+
+/// Resolves a call in method syntax, as in `o.f(a)`, against the type of the receiver.
+/// A field that holds a function is called as it is. Otherwise the call is rewritten into a call with the receiver
+/// as its first argument, as in `f(o, a)`, of the method of the receiver's type or of the interfaces it implements,
+/// and otherwise of the function in scope. A receiver whose type is not known yet is taken to have the method
+/// of an object type if only one has it.
+fn method_call(call: &mut CallExpr, infer: &mut Infer) -> SemResult<Callee> {
+    let field = match call.fun.as_mut() {
+        Expr::Field(f) => f,
+        _ => panic!("call is not in method syntax"),
+    };
+
+    let self_type = field.expr.visit(infer)?;
+    let name = field.field.name.clone();
+    let methods = field.scope().borrow().lookup_method(&name);
+    let method = match self_type.resolve() {
+        PineType::Object(o, _) if o.borrow().field(&name).is_some() => None,
+        PineType::Object(o, _) => match object_method(&o, &name) {
+            Ok(m) => m.or_else(|| call.fallback.clone()),
+            Err(msg) => return Err(SemError::error(msg, field.field.span())),
+        },
+        PineType::Interface(i) => i.borrow().method(&name).map(|(_, m)| m).or_else(|| call.fallback.clone()),
+        t if t.is_unknown() => call.fallback.clone().or_else(|| match methods.as_slice() {
+            [m] => Some(m.clone()),
+            _ => None,
+        }),
+        _ => call.fallback.clone(),
+    };
+
+    let symbol = match method {
+        Some(m) => m,
+        None if !self_type.is_unknown() && !methods.is_empty() && !has_field(&self_type, &name) => {
+            return Err(SemError::error(format!("type {} has no method {}", self_type, name), field.field.span()));
+        }
+        None => {
+            let field_type = field_type(field, self_type, infer)?;
+            call.fun.set_ty(field_type.clone());
+            return Ok(Callee::Field(field_type));
+        }
+    };
+
+    let span = field.field.span();
+    let placeholder = Expr::BoolLit(BoolLitExpr::new(false, span));
+    let field = match std::mem::replace(call.fun.as_mut(), placeholder) {
+        Expr::Field(f) => f,
+        _ => panic!("call is not in method syntax"),
+    };
+
+    let scope = field.scope();
+    let mut ident = field.field;
+    ident.symbol = symbol;
+    let mut fun = Expr::Ident(IdentExpr::new(vec![], ident, span));
+    fun.set_scope(scope);
+    call.args.insert(0, *field.expr);
+    *call.fun = fun;
+    Ok(Callee::Method(self_type))
+}
+
+/// Gets the method of an object type by name, which is either declared for the object
+/// or implements a method of one of its interfaces.
+fn object_method(obj: &ObjRef, name: &str) -> Result<Option<SymbolRef>, String> {
+    if let Some(m) = obj.borrow().method(name) {
+        return Ok(Some(m));
+    }
+
+    let obj_def = obj.borrow();
+    let impl_methods: Vec<SymbolRef> = obj_def.ifaces.iter()
+        .filter_map(|i| {
+            let iface = i.borrow();
+            let (index, _) = iface.method(name)?;
+            iface.impl_for(obj).map(|d| d.methods[index].clone())
+        })
+        .collect();
+    match impl_methods.as_slice() {
+        [] => Ok(None),
+        [m] => Ok(Some(m.clone())),
+        _ => Err(format!("method {} of type {} is declared by more than one interface", name, obj_def.name)),
+    }
+}
+
+/// Determines if a value of a type has a field by name.
+fn has_field(ty: &PineType, name: &str) -> bool {
+    match ty.resolve() {
+        PineType::Object(o, _) => o.borrow().field(name).is_some(),
+        _ => false,
+    }
 }
 
 impl AstTyping for IfStmt {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
         for c in &mut self.conds {
-            let c_type = c.visit()?;
-            if c_type != PineType::Bool {
+            let c_type = c.visit(infer)?;
+            if !infer.unifies(&c_type, &PineType::Bool) {
                 return Err(SemError::error("condition must have type bool", self.span()))
             }
         }
         
         for b in &mut self.then_blocks {
-            b.visit()?;
+            b.visit(infer)?;
         }
         
         if let Some(else_block) = &mut self.else_block {
-            else_block.visit()?;
+            else_block.visit(infer)?;
         }
 
         Ok(PineType::Void)
@@ -101,37 +390,64 @@ impl AstTyping for IfStmt {
 }
 
 impl AstTyping for WhileStmt {
-    fn visit(&mut self) -> SemResult<PineType> {
-        let c_type = self.cond.visit()?;
-        if c_type != PineType::Bool {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let c_type = self.cond.visit(infer)?;
+        if !infer.unifies(&c_type, &PineType::Bool) {
             return Err(SemError::error("condition must have type bool", self.span()))
         }
 
-        self.block.visit()?;
+        self.block.visit(infer)?;
+        Ok(PineType::Void)
+    }
+}
+
+impl AstTyping for ForStmt {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let elem_type = match self.iter.as_mut() {
+            Expr::Range(r) => {
+                range_bounds(r, infer)?;
+                PineType::Integer
+            }
+            // iterating over a map iterates over its keys
+            e => match list_type(e.visit(infer)?, infer) {
+                PineType::List(t) | PineType::Map(t, _) | PineType::Set(t) => *t,
+                _ => return Err(SemError::error("for loops can only iterate over ranges, lists, maps and sets", self.iter.span()))
+            }
+        };
+
+        self.ident.symbol.borrow_mut().pine_type = elem_type;
+        self.block.visit(infer)?;
         Ok(PineType::Void)
     }
 }
 
+/// Infers a type that is not known yet to be a list, since only lists can be iterated and indexed.
+fn list_type(ty: PineType, infer: &Infer) -> PineType {
+    if ty.is_unknown() {
+        infer.unifies(&ty, &PineType::List(Box::new(infer.fresh())));
+    }
+
+    ty.resolve()
+}
+
 impl AstTyping for ReturnStmt {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
         let fun_sym = match self.scope().borrow().owning_fun() {
             Some(f) => f,
             None => return Err(SemError::error("could not find enclosing function", self.span()))
         };
-        let fun_ret_ty = match &fun_sym.borrow().pine_type {
-            PineType::Function { ret, .. } => ret.as_ref().clone(),
+        let fun_ret_ty = match fun_sym.borrow().pine_type.resolve() {
+            PineType::Function { ret, .. } => *ret,
             _ => return Err(SemError::error("could not find enclosing function", self.span()))
         };
+        let fun_span = fun_sym.borrow().span;
 
-        if let Some(expr) = &mut self.expr {
-            let e_type = expr.visit()?;
-            if e_type != fun_ret_ty {
-                return Err(SemError::error("types do not match", self.span()))
-            }
-        } else {
-            if fun_ret_ty != PineType::Void {
-                return Err(SemError::error("types do not match", self.span()))
+        match &mut self.expr {
+            Some(expr) => {
+                let e_type = expr.visit(infer)?;
+                coerce(infer, &fun_ret_ty, fun_span, expr, e_type)?;
             }
+            None => infer.unify(&fun_ret_ty, fun_span, &PineType::Void, self.span())?,
         }
 
         Ok(PineType::Void)
@@ -139,16 +455,16 @@ impl AstTyping for ReturnStmt {
 }
 
 impl AstTyping for ExprStmt {
-    fn visit(&mut self) -> SemResult<PineType> {
-        self.expr.visit()?;
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        self.expr.visit(infer)?;
         Ok(PineType::Void)
     }
 }
 
 impl AstTyping for Block {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
         for s in &mut self.stmts {
-            s.visit()?;
+            s.visit(infer)?;
         }
 
         Ok(PineType::Void)
@@ -156,104 +472,641 @@ impl AstTyping for Block {
 }
 
 impl AstTyping for Stmt {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
         match self {
-            Stmt::Let(s) => s.visit(),
-            Stmt::Set(s) => s.visit(),
-            Stmt::If(s) => s.visit(),
-            Stmt::While(s) => s.visit(),
-            Stmt::Return(s) => s.visit(),
-            Stmt::Expr(s) => s.visit(),
-            Stmt::Block(s) => s.visit()
+            Stmt::Let(s) => s.visit(infer),
+            Stmt::LetTuple(s) => s.visit(infer),
+            Stmt::Set(s) => s.visit(infer),
+            Stmt::If(s) => s.visit(infer),
+            Stmt::While(s) => s.visit(infer),
+            Stmt::For(s) => s.visit(infer),
+            Stmt::Return(s) => s.visit(infer),
+            Stmt::Break(_) | Stmt::Continue(_) => Ok(PineType::Void),
+            Stmt::Expr(s) => s.visit(infer),
+            Stmt::Block(s) => s.visit(infer)
         }
     }
 }
 
 impl AstTyping for IntLitExpr {
-    fn visit(&mut self) -> SemResult<PineType> {
+    /// A literal is an int, unless its value only fits in a uint.
+    fn visit(&mut self, _infer: &mut Infer) -> SemResult<PineType> {
+        if self.value > i64::MAX as i128 {
+            return Ok(PineType::UInteger);
+        }
+
         Ok(PineType::Integer)
     }
 }
 
 impl AstTyping for FloatLitExpr {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, _infer: &mut Infer) -> SemResult<PineType> {
         Ok(PineType::Float)
     }
 }
 
+impl AstTyping for CharLitExpr {
+    fn visit(&mut self, _infer: &mut Infer) -> SemResult<PineType> {
+        Ok(PineType::Char)
+    }
+}
+
 impl AstTyping for BoolLitExpr {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, _infer: &mut Infer) -> SemResult<PineType> {
         Ok(PineType::Bool)
     }
 }
 
 impl AstTyping for StringLitExpr {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, _infer: &mut Infer) -> SemResult<PineType> {
+        Ok(PineType::String)
+    }
+}
+
+impl AstTyping for InterpStringExpr {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        for p in &mut self.parts {
+            p.visit(infer)?;
+        }
+
         Ok(PineType::String)
     }
 }
 
 impl AstTyping for IdentExpr {
-    fn visit(&mut self) -> SemResult<PineType> {
-        self.ident.visit()
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        if let Some(builtin) = self.ident.symbol.borrow().builtin {
+            return Err(SemError::error(format!("builtin `{}` can only be called", builtin.name()), self.span()));
+        }
+
+        if let Some(iface) = &self.ident.symbol.borrow().iface {
+            return Err(SemError::error(format!("interface {} cannot be used as a value", iface.borrow().name), self.span()));
+        }
+
+        // a variable that has been checked to hold a value is the value rather than the optional
+        let ty = self.ident.visit(infer)?;
+        if self.narrowed {
+            let value_type = infer.fresh();
+            let def_span = self.ident.symbol.borrow().span;
+            infer.unify(&PineType::Optional(Box::new(value_type.clone())), def_span, &ty, self.span())?;
+            return Ok(value_type);
+        }
+
+        Ok(ty)
     }
 }
 
 impl AstTyping for CallExpr {
-    fn visit(&mut self) -> SemResult<PineType> {
-        let fun_type = self.fun.visit()?;
-        match fun_type {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let callee = match self.fun.as_ref() {
+            Expr::Field(_) => Some(method_call(self, infer)?),
+            _ => None,
+        };
+
+        // the receiver of a method has been typed as it was resolved
+        let mut arg_types = vec![];
+        if let Some(Callee::Method(self_type)) = &callee {
+            arg_types.push(self_type.clone());
+        }
+
+        for a in self.args.iter_mut().skip(arg_types.len()) {
+            arg_types.push(a.visit(infer)?);
+        }
+
+        if let Some(builtin) = self.builtin() {
+            let ret = collection_builtin(builtin, &mut self.args, &mut arg_types, infer);
+            // the elements of a collection that is converted to a string are checked once their types are known
+            let deferred = match builtin {
+                Builtin::ToString => arg_types.iter().flat_map(builtin::converted_types).any(|t| t.is_unknown() || t.is_generic()),
+                _ => arg_types.iter().any(|t| t.is_unknown() || t.is_generic()),
+            };
+            if deferred {
+                infer.defer_builtin(builtin, arg_types, self.span());
+                return Ok(ret.unwrap_or_else(|| builtin.return_pine_type()));
+            }
+
+            let arg_types: Vec<PineType> = arg_types.iter().map(|t| t.resolve_all()).collect();
+            return match builtin.call_pine_type(&arg_types) {
+                Ok(t) => Ok(t),
+                Err(e) => Err(SemError::error(e, self.span()))
+            };
+        }
+
+        let fun_type = match callee {
+            Some(Callee::Field(t)) => t,
+            _ => self.fun.visit(infer)?,
+        };
+        if fun_type.is_unknown() {
+            let params = arg_types.iter().map(|_| infer.fresh()).collect();
+            infer.unifies(&fun_type, &PineType::Function { params, ret: Box::new(infer.fresh()) });
+        }
+
+        match fun_type.resolve() {
             PineType::Function { params, ret } => {
                 if self.args.len() != params.len() {
                     return Err(SemError::error("number of arguments does not match number of parameters", self.span()))
                 }
 
-                for (a, p_type) in self.args.iter_mut().zip(&params) {
-                    let a_type = a.visit()?;
-                    if a_type != *p_type {
-                        return Err(SemError::error("argument types do not match parameters", self.span()))
-                    }
+                let fun_span = definition_span(&self.fun);
+                for ((a, a_type), p_type) in self.args.iter_mut().zip(arg_types).zip(&params) {
+                    coerce(infer, p_type, fun_span, a, a_type)?;
                 }
 
-                Ok(ret.as_ref().clone())
+                Ok(*ret)
             },
             _ => Err(SemError::error("expression cannot be called", self.span()))
         }
     }
 }
 
+/// Infers the types of the arguments of a builtin that is called on a collection from the element types of the collection,
+/// or the collection type from the arguments if it is not known yet.
+/// Returns the type of the call if it depends on the element types.
+fn collection_builtin(builtin: Builtin, args: &mut [Expr], arg_types: &mut [PineType], infer: &Infer) -> Option<PineType> {
+    let (collection_type, collection_span) = match (arg_types.first(), args.first()) {
+        (Some(t), Some(a)) => (t.resolve(), a.span()),
+        _ => return None,
+    };
+
+    // a collection that is not known yet is inferred from how it is used
+    if collection_type.is_unknown() {
+        let fresh = || Box::new(infer.fresh());
+        let ty = match (builtin, arg_types.len()) {
+            (Builtin::Push, 2) => PineType::List(Box::new(arg_types[1].clone())),
+            (Builtin::Get, 2) | (Builtin::Insert, 3) => PineType::Map(fresh(), fresh()),
+            (Builtin::Insert, 2) => PineType::Set(fresh()),
+            _ => return None,
+        };
+        infer.unifies(&collection_type, &ty);
+    }
+
+    // the arguments take on the element types of the collection
+    let elem_types = match (builtin, collection_type.resolve()) {
+        (Builtin::Push, PineType::List(t)) => vec![*t],
+        (Builtin::Get | Builtin::Remove | Builtin::Contains, PineType::Map(k, _)) => vec![*k],
+        (Builtin::Insert, PineType::Map(k, v)) => vec![*k, *v],
+        (Builtin::Insert | Builtin::Remove | Builtin::Contains, PineType::Set(t)) => vec![*t],
+        _ => vec![],
+    };
+
+    for ((a, a_type), t) in args.iter_mut().zip(arg_types.iter_mut()).skip(1).zip(elem_types) {
+        if coerce(infer, &t, collection_span, a, a_type.clone()).is_ok() {
+            *a_type = t;
+        }
+    }
+
+    match (builtin, collection_type.resolve()) {
+        (Builtin::Get, PineType::Map(_, v)) => Some(*v),
+        _ => None,
+    }
+}
+
 impl AstTyping for UnaryExpr {
-    fn visit(&mut self) -> SemResult<PineType> {
-        let t = self.expr.visit()?;
-        match self.op.unary_pine_type(t) {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let t = self.expr.visit(infer)?;
+        checked(&t, self.expr.span())?;
+        if matches!(t.resolve(), PineType::Object(..)) {
+            let (_, ret, _) = operator_method(&t, self.op, self.op.unary_method(), self.span())?;
+            return Ok(ret);
+        }
+
+        if t.is_unknown() || t.is_generic() {
+            if self.op == Operator::Not {
+                infer.unifies(&t, &PineType::Bool);
+            } else {
+                infer.defer_unary(self.op, t.clone(), self.span());
+                return Ok(t);
+            }
+        }
+
+        match self.op.unary_pine_type(t.resolve_all()) {
             Ok(t) => Ok(t),
             Err(e) => Err(SemError::error(e, self.span()))
-        } // TODO use type env?
+        }
     }
 }
 
 impl AstTyping for BinaryExpr {
-    fn visit(&mut self) -> SemResult<PineType> {
-        let l = self.left.visit()?;
-        let r = self.right.visit()?;
-        match self.op.binary_pine_type(l, r) {
-            Ok(t) => Ok(t),
-            Err(e) => Err(SemError::error(e, self.span()))
-        } // TODO use type env?
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let l = self.left.visit(infer)?;
+        let r = self.right.visit(infer)?;
+        let l = int_lit_as(&mut self.left, l, &r)?;
+        let r = int_lit_as(&mut self.right, r, &l)?;
+        binary_type(infer, self.op, (l, self.left.span()), (r, self.right.span()), self.span())
+    }
+}
+
+/// Gets the type resulting from a binary operation on operands of the given types and spans.
+fn binary_type(infer: &mut Infer, op: Operator, (l, l_span): (PineType, Span), (r, r_span): (PineType, Span), span: Span) -> SemResult<PineType> {
+    checked(&l, l_span)?;
+    checked(&r, r_span)?;
+    if matches!(op, Operator::And | Operator::Or) {
+        for t in [&l, &r] {
+            if t.is_unknown() {
+                infer.unifies(t, &PineType::Bool);
+            }
+        }
+    } else if matches!(l.resolve(), PineType::Object(..)) {
+        // the operator is a call to the method that overloads it
+        let (params, ret, def_span) = operator_method(&l, op, op.binary_method(), span)?;
+        infer.unify(&params[1], def_span, &r, r_span)?;
+        return Ok(ret);
+    } else {
+        infer.unify(&l, l_span, &r, r_span)?;
+        if l.is_unknown() || l.is_generic() {
+            infer.defer_binary(op, l.clone(), span);
+            return Ok(if op.is_comparison() { PineType::Bool } else { l });
+        }
+    }
+
+    match op.binary_pine_type(l.resolve_all(), r.resolve_all()) {
+        Ok(t) => Ok(t),
+        Err(e) => Err(SemError::error(e, span))
+    }
+}
+
+impl AstTyping for ListLitExpr {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let elem_type = infer.fresh();
+        let mut first_span = None;
+        for e in &mut self.elems {
+            let e_type = e.visit(infer)?;
+            match first_span {
+                Some(span) => coerce(infer, &elem_type, span, e, e_type)?,
+                None => {
+                    infer.unifies(&elem_type, &e_type);
+                    first_span = Some(e.span());
+                }
+            }
+        }
+
+        Ok(PineType::List(Box::new(elem_type)))
+    }
+}
+
+impl AstTyping for MapLitExpr {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let (key_type, value_type) = (infer.fresh(), infer.fresh());
+        infer.defer_key(key_type.clone(), self.span());
+        let mut first_spans = None;
+        for (k, v) in &mut self.entries {
+            let k_type = k.visit(infer)?;
+            let v_type = v.visit(infer)?;
+            match first_spans {
+                Some((k_span, v_span)) => {
+                    coerce(infer, &key_type, k_span, k, k_type)?;
+                    coerce(infer, &value_type, v_span, v, v_type)?;
+                }
+                None => {
+                    infer.unifies(&key_type, &k_type);
+                    infer.unifies(&value_type, &v_type);
+                    first_spans = Some((k.span(), v.span()));
+                }
+            }
+        }
+
+        Ok(PineType::Map(Box::new(key_type), Box::new(value_type)))
+    }
+}
+
+impl AstTyping for SetLitExpr {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let elem_type = infer.fresh();
+        infer.defer_key(elem_type.clone(), self.span());
+        let mut first_span = None;
+        for e in &mut self.elems {
+            let e_type = e.visit(infer)?;
+            match first_span {
+                Some(span) => coerce(infer, &elem_type, span, e, e_type)?,
+                None => {
+                    infer.unifies(&elem_type, &e_type);
+                    first_span = Some(e.span());
+                }
+            }
+        }
+
+        Ok(PineType::Set(Box::new(elem_type)))
+    }
+}
+
+impl AstTyping for TupleLitExpr {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let mut elem_types = vec![];
+        for e in &mut self.elems {
+            elem_types.push(e.visit(infer)?);
+        }
+
+        Ok(PineType::Tuple(elem_types))
+    }
+}
+
+impl AstTyping for IndexExpr {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let e_type = self.expr.visit(infer)?;
+        let i_type = self.index.visit(infer)?;
+        if !infer.unifies(&i_type, &PineType::Integer) {
+            return Err(SemError::error("index must have type int", self.index.span()));
+        }
+
+        match list_type(e_type, infer) {
+            PineType::List(t) => Ok(*t),
+            _ => Err(SemError::error("expression cannot be indexed", self.expr.span()))
+        }
+    }
+}
+
+impl AstTyping for FieldExpr {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let e_type = self.expr.visit(infer)?;
+        field_type(self, e_type, infer)
+    }
+}
+
+/// Infers the type of a field of a value whose type has been inferred.
+fn field_type(field: &FieldExpr, e_type: PineType, infer: &mut Infer) -> SemResult<PineType> {
+    if e_type.is_unknown() {
+        // a value whose type is not known yet has the object type that has the field, if only one does
+        match field.scope().borrow().lookup_field(&field.field.name).as_slice() {
+            [o] => {
+                let args = o.borrow().params.iter().map(|_| infer.fresh()).collect();
+                infer.unifies(&e_type, &PineType::Object(o.clone(), args));
+            }
+            _ => return Ok(infer.defer_field(e_type, field.field.name.clone(), field.field.span())),
+        }
+    }
+
+    let field_type = match e_type.resolve() {
+        PineType::Object(o, args) => o.borrow().instance_field(&field.field.name, &args),
+        _ => None,
+    };
+
+    match field_type {
+        Some((_, t)) => Ok(t),
+        None => Err(SemError::error(format!("type {} has no field {}", e_type, field.field.name), field.field.span()))
+    }
+}
+
+/// Checks that a value is not an optional, since an optional must be checked for none before its value is used.
+fn checked(ty: &PineType, span: Span) -> SemResult<()> {
+    match ty.resolve() {
+        PineType::Optional(_) => Err(SemError::error(unchecked(&ty.resolve_all()), span)),
+        _ => Ok(()),
+    }
+}
+
+impl AstTyping for NoneLitExpr {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        Ok(PineType::Optional(Box::new(infer.fresh())))
+    }
+}
+
+impl AstTyping for IsExpr {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let t = self.expr.visit(infer)?;
+        if !infer.unifies(&t, &PineType::Optional(Box::new(infer.fresh()))) {
+            return Err(SemError::error(format!("only optionals can be checked for none, but found {}", t.resolve_all()), self.expr.span()));
+        }
+
+        Ok(PineType::Bool)
+    }
+}
+
+impl AstTyping for TryExpr {
+    /// The error of the result is returned from the enclosing function,
+    /// which must return a result with errors of the same type.
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let t = self.expr.visit(infer)?;
+        let result = self.scope().borrow().lookup_builtin("Result")
+            .and_then(|s| s.borrow().enum_def.clone())
+            .expect("builtin Result is not defined");
+        let (value_type, error_type) = (infer.fresh(), infer.fresh());
+        let result_type = PineType::Enum(result.clone(), vec![value_type.clone(), error_type.clone()]);
+        if !infer.unifies(&t, &result_type) {
+            return Err(SemError::error(format!("`?` can only be applied to a Result, but found {}", t.resolve_all()), self.expr.span()));
+        }
+
+        let fun_sym = match self.scope().borrow().owning_fun() {
+            Some(f) => f,
+            None => return Err(SemError::error("could not find enclosing function", self.span()))
+        };
+        let fun_ret_ty = match fun_sym.borrow().pine_type.resolve() {
+            PineType::Function { ret, .. } => *ret,
+            _ => return Err(SemError::error("could not find enclosing function", self.span()))
+        };
+
+        let fun_result_type = PineType::Enum(result, vec![infer.fresh(), infer.fresh()]);
+        if !infer.unifies(&fun_ret_ty, &fun_result_type) {
+            let msg = format!("`?` can only be used in a function that returns a Result, but the function returns {}", fun_ret_ty.resolve_all());
+            return Err(SemError::conflict(msg, self.span(), fun_sym.borrow().span));
+        }
+
+        let fun_error_type = match fun_result_type.resolve() {
+            PineType::Enum(_, args) => args[1].clone(),
+            _ => panic!("typing bug"),
+        };
+        if !infer.unifies(&fun_error_type, &error_type) {
+            let msg = format!("`?` propagates errors of type {} but the function returns errors of type {}", error_type.resolve_all(), fun_error_type.resolve_all());
+            return Err(SemError::conflict(msg, self.span(), fun_sym.borrow().span));
+        }
+
+        Ok(value_type)
+    }
+}
+
+impl AstTyping for CastExpr {
+    /// An implicit cast already has its type, while an explicit cast converts between numbers,
+    /// and between characters and integers.
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let Some(target) = &self.target else {
+            return Ok(self.ty.clone());
+        };
+
+        let t = self.expr.visit(infer)?;
+        checked(&t, self.expr.span())?;
+        if t.is_unknown() {
+            return Err(SemError::error("the type of a value that is cast must be known", self.expr.span()));
+        }
+
+        if !castable(&t.resolve(), &target.ty) {
+            return Err(SemError::error(format!("a value of type {} cannot be cast to {}", t.resolve_all(), target.ty), self.span()));
+        }
+
+        Ok(target.ty.clone())
+    }
+}
+
+/// Determines if a value of a type can be explicitly cast to another type.
+fn castable(from: &PineType, to: &PineType) -> bool {
+    let integer = |t: &PineType| matches!(t, PineType::Integer | PineType::UInteger | PineType::Byte);
+    match (from, to) {
+        (PineType::Float, t) | (t, PineType::Float) => integer(t) || *t == PineType::Float,
+        (PineType::Char, t) | (t, PineType::Char) => integer(t) || *t == PineType::Char,
+        (f, t) => integer(f) && integer(t),
+    }
+}
+
+impl AstTyping for RangeExpr {
+    fn visit(&mut self, _infer: &mut Infer) -> SemResult<PineType> {
+        Err(SemError::error("ranges can only be used in for loops", self.span()))
+    }
+}
+
+/// Checks that the bounds of a range are integers.
+fn range_bounds(range: &mut RangeExpr, infer: &mut Infer) -> SemResult<()> {
+    let start_type = range.start.visit(infer)?;
+    let end_type = range.end.visit(infer)?;
+    if !infer.unifies(&start_type, &PineType::Integer) || !infer.unifies(&end_type, &PineType::Integer) {
+        return Err(SemError::error("range bounds must have type int", range.span()))
+    }
+
+    Ok(())
+}
+
+impl AstTyping for MatchExpr {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let ty = self.expr.visit(infer)?;
+        let span = self.expr.span();
+
+        // the arms of a match expression have the same type, while the values of the arms of a match statement are discarded
+        let match_type = if self.is_stmt { PineType::Void } else { infer.fresh() };
+        let mut first_span = None;
+        for a in &mut self.arms {
+            check_pattern(&mut a.pattern, &ty, span, infer)?;
+            let (e, e_type) = match &mut a.body {
+                ArmBody::Block(b) => {
+                    b.visit(infer)?;
+                    continue;
+                }
+                ArmBody::Expr(e) => {
+                    let e_type = e.visit(infer)?;
+                    (e, e_type)
+                }
+            };
+
+            if self.is_stmt {
+                continue;
+            }
+
+            match first_span {
+                Some(span) => coerce(infer, &match_type, span, e, e_type)?,
+                None => {
+                    infer.unifies(&match_type, &e_type);
+                    first_span = Some(e.span());
+                }
+            }
+        }
+
+        Ok(match_type)
+    }
+}
+
+impl AstTyping for LambdaExpr {
+    /// Types a lambda like a function, whose parameter and return types are inferred from its body
+    /// and its uses if they are not annotated.
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        let mut param_types = vec![];
+        for p in &mut self.params {
+            let param_type = match &mut p.ty {
+                Some(t) => t.visit(infer)?,
+                None => infer.fresh(),
+            };
+            p.ident.symbol.borrow_mut().pine_type = param_type.clone();
+            param_types.push(param_type);
+        }
+
+        let ret = match &mut self.return_ty {
+            Some(t) => t.visit(infer)?,
+            None => infer.fresh(),
+        };
+
+        let lambda_type = PineType::Function { params: param_types, ret: Box::new(ret.clone()) };
+        self.symbol.borrow_mut().pine_type = lambda_type.clone();
+        let span = self.span();
+        match &mut self.body {
+            LambdaBody::Expr(e) => {
+                let e_type = e.visit(infer)?;
+                coerce(infer, &ret, span, e, e_type)?;
+            }
+            LambdaBody::Block(b) => {
+                b.visit(infer)?;
+            }
+        }
+
+        Ok(lambda_type)
+    }
+}
+
+/// Checks that a pattern matches values of a type, and types the variables it binds.
+fn check_pattern(pattern: &mut Pattern, ty: &PineType, span: Span, infer: &mut Infer) -> SemResult<()> {
+    match pattern {
+        Pattern::Wildcard(_) => Ok(()),
+        Pattern::Binding(p) => {
+            p.ident.symbol.borrow_mut().pine_type = ty.clone();
+            Ok(())
+        }
+        Pattern::Lit(p) => {
+            let l_type = p.expr.visit(infer)?;
+            infer.unify(ty, span, &l_type, p.span())
+        }
+        Pattern::Variant(p) => {
+            let v_type = p.variant.visit(infer)?;
+            p.variant.ty = v_type.clone();
+            let (params, ret) = match v_type.resolve() {
+                PineType::Function { params, ret } => (params, *ret),
+                t => (vec![], t),
+            };
+
+            if params.len() != p.args.len() {
+                let msg = format!("variant {} expects {} patterns but was given {}", p.variant.ident.name, params.len(), p.args.len());
+                return Err(SemError::error(msg, p.span()));
+            }
+
+            infer.unify(ty, span, &ret, p.variant.span())?;
+            let def_span = p.variant.ident.symbol.borrow().span;
+            for (a, t) in p.args.iter_mut().zip(&params) {
+                check_pattern(a, t, def_span, infer)?;
+            }
+
+            Ok(())
+        }
+        Pattern::Tuple(p) => {
+            let elem_types: Vec<PineType> = p.elems.iter().map(|_| infer.fresh()).collect();
+            infer.unify(ty, span, &PineType::Tuple(elem_types.clone()), p.span())?;
+            for (e, t) in p.elems.iter_mut().zip(&elem_types) {
+                check_pattern(e, t, span, infer)?;
+            }
+
+            Ok(())
+        }
     }
 }
 
 impl AstTyping for Expr {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
         let ty = match self {
-            Expr::IntLit(e) => e.visit(),
-            Expr::FloatLit(e) => e.visit(),
-            Expr::BoolLit(e) => e.visit(),
-            Expr::StringLit(e) => e.visit(),
-            Expr::Ident(e) => e.visit(),
-            Expr::Call(e) => e.visit(),
-            Expr::Unary(e) => e.visit(),
-            Expr::Binary(e) => e.visit(),
+            Expr::IntLit(e) => e.visit(infer),
+            Expr::FloatLit(e) => e.visit(infer),
+            Expr::CharLit(e) => e.visit(infer),
+            Expr::BoolLit(e) => e.visit(infer),
+            Expr::StringLit(e) => e.visit(infer),
+            Expr::InterpString(e) => e.visit(infer),
+            Expr::Ident(e) => e.visit(infer),
+            Expr::Call(e) => e.visit(infer),
+            Expr::Unary(e) => e.visit(infer),
+            Expr::Binary(e) => e.visit(infer),
+            Expr::Range(e) => e.visit(infer),
+            Expr::ListLit(e) => e.visit(infer),
+            Expr::MapLit(e) => e.visit(infer),
+            Expr::SetLit(e) => e.visit(infer),
+            Expr::TupleLit(e) => e.visit(infer),
+            Expr::Index(e) => e.visit(infer),
+            Expr::Field(e) => e.visit(infer),
+            Expr::Cast(e) => e.visit(infer),
+            Expr::NoneLit(e) => e.visit(infer),
+            Expr::Is(e) => e.visit(infer),
+            Expr::Try(e) => e.visit(infer),
+            Expr::Match(e) => e.visit(infer),
+            Expr::Lambda(e) => e.visit(infer),
         }?;
         self.set_ty(ty.clone());
         Ok(ty)
@@ -261,13 +1114,13 @@ impl AstTyping for Expr {
 }
 
 impl AstTyping for Ident {
-    fn visit(&mut self) -> SemResult<PineType> {
-        Ok(self.symbol.borrow().pine_type.clone())
+    fn visit(&mut self, infer: &mut Infer) -> SemResult<PineType> {
+        Ok(infer.instantiate(&self.symbol.borrow().pine_type, self.span()))
     }
 }
 
 impl AstTyping for Ty {
-    fn visit(&mut self) -> SemResult<PineType> {
+    fn visit(&mut self, _infer: &mut Infer) -> SemResult<PineType> {
         Ok(self.ty.clone())
     }
 }
@@ -1,5 +1,7 @@
 mod global;
 mod local;
+mod infer;
+mod deps;
 
 pub(crate) use global::*;
-pub(crate) use local::*;
\ No newline at end of file
+pub(crate) use local::*;
@@ -1,6 +1,8 @@
-use crate::ast::PineType;
+use crate::ast::{ConstValue, EnumDef, EnumRef, IfaceRef, ObjRef, PineType};
+use crate::builtin::Builtin;
+use crate::token::Span;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
 /// The symbol reference type.
@@ -15,6 +17,26 @@ pub struct Symbol {
     pub name: String,
     pub dest: pvm::Operand,
     pub pine_type: PineType,
+    /// The builtin the symbol refers to, if any.
+    pub builtin: Option<Builtin>,
+    /// The object type the symbol refers to, if any.
+    pub obj: Option<ObjRef>,
+    /// The enum type the symbol refers to, if any.
+    pub enum_def: Option<EnumRef>,
+    /// The enum and tag of the variant the symbol refers to, if any.
+    pub variant: Option<(EnumRef, usize)>,
+    /// The interface the symbol refers to, if any.
+    pub iface: Option<IfaceRef>,
+    /// The type parameter the symbol refers to, if any.
+    pub generic: Option<PineType>,
+    /// The local variables captured by the lambda the symbol refers to.
+    pub captures: Vec<SymbolRef>,
+    /// The value of the constant the symbol refers to, if any.
+    pub constant: Option<ConstValue>,
+    /// The name of the module that defines the function, method or constructor the symbol refers to, if any.
+    pub module: Option<String>,
+    /// The span of the identifier that defines the symbol.
+    pub span: Span,
     pub(crate) scope: ScopeRef,
 }
 
@@ -38,6 +60,16 @@ pub struct Scope {
     pub symbol_table: SymbolTable,
     pub owner: Option<SymbolRef>,
     pub depth: ScopeDepth,
+    /// Whether the scope is the block of a loop.
+    pub is_loop: bool,
+    /// The global scopes of the imported modules, by import name.
+    pub imports: HashMap<String, ScopeRef>,
+    /// The names of the exported symbols and modules.
+    pub exports: HashSet<String>,
+    /// The import names of the imported modules that are submodules of the module, such as `util` in `sub::util`.
+    pub submodules: HashSet<String>,
+    /// The optional variables that have been checked to hold a value in this scope.
+    pub narrowed: Vec<SymbolRef>,
 }
 
 impl Symbol {
@@ -46,6 +78,16 @@ impl Symbol {
             name: String::default(),
             dest: pvm::Operand::default(),
             pine_type: PineType::Unknown,
+            builtin: None,
+            obj: None,
+            enum_def: None,
+            variant: None,
+            iface: None,
+            generic: None,
+            captures: Vec::new(),
+            constant: None,
+            module: None,
+            span: Span::default(),
             scope: Scope::default(),
         }))
     }
@@ -55,6 +97,36 @@ impl Symbol {
             name: name.clone(),
             dest: pvm::Operand::Variable(format!("${}_{}", name, scope.borrow().depth)),
             pine_type: PineType::Unknown,
+            builtin: None,
+            obj: None,
+            enum_def: None,
+            variant: None,
+            iface: None,
+            generic: None,
+            captures: Vec::new(),
+            constant: None,
+            module: None,
+            span: Span::default(),
+            scope
+        }))
+    }
+
+    /// Creates a symbol for a builtin.
+    pub fn new_builtin(builtin: Builtin, scope: ScopeRef) -> SymbolRef {
+        Rc::new(RefCell::new(Self {
+            name: builtin.name().to_string(),
+            dest: pvm::Operand::default(),
+            pine_type: PineType::Unknown,
+            builtin: Some(builtin),
+            obj: None,
+            enum_def: None,
+            variant: None,
+            iface: None,
+            generic: None,
+            captures: Vec::new(),
+            constant: None,
+            module: None,
+            span: Span::default(),
             scope
         }))
     }
@@ -106,6 +178,11 @@ impl Scope {
             symbol_table: SymbolTable::new(),
             owner: None,
             depth: ScopeDepth::Global,
+            is_loop: false,
+            imports: HashMap::new(),
+            exports: HashSet::new(),
+            submodules: HashSet::new(),
+            narrowed: Vec::new(),
         }))
     }
 
@@ -116,9 +193,56 @@ impl Scope {
             symbol_table: SymbolTable::new(),
             owner: None,
             depth: ScopeDepth::Global,
+            is_loop: false,
+            imports: HashMap::new(),
+            exports: HashSet::new(),
+            submodules: HashSet::new(),
+            narrowed: Vec::new(),
         }))
     }
 
+    /// Creates the builtin scope, which holds the builtin symbols and the builtin `Result` enum.
+    /// It is the root of the scopes of every module.
+    pub fn new_builtin() -> ScopeRef {
+        let scope = Scope::new_global();
+        for b in Builtin::all().into_iter().filter(|b| !b.is_intrinsic()) {
+            let symbol = Symbol::new_builtin(b, scope.clone());
+            scope.borrow_mut().add(symbol).unwrap();
+        }
+
+        // the variants of the result are constructed by runtime routines
+        let result = EnumDef::new_result();
+        let symbol = Symbol::new("Result".to_string(), scope.clone());
+        symbol.borrow_mut().enum_def = Some(result.clone());
+        scope.borrow_mut().add(symbol).unwrap();
+
+        let result_type = PineType::Enum(result.clone(), result.borrow().params.clone());
+        for (tag, (name, payload)) in result.borrow().variants.iter().enumerate() {
+            let symbol = Symbol::new(name.clone(), scope.clone());
+            {
+                let mut s = symbol.borrow_mut();
+                s.dest = pvm::Operand::Label(format!("rt.result_{}", name.to_lowercase()));
+                s.pine_type = PineType::Function { params: payload.clone(), ret: Box::new(result_type.clone()) };
+                s.variant = Some((result.clone(), tag));
+            }
+            scope.borrow_mut().add(symbol).unwrap();
+        }
+
+        scope
+    }
+
+    /// Creates the intrinsic scope, which holds the intrinsic builtins and is the parent of the global scope of the core module.
+    pub fn new_intrinsic(parent: ScopeRef) -> ScopeRef {
+        let scope = Scope::new_global();
+        scope.borrow_mut().parent = Some(parent);
+        for b in Builtin::all().into_iter().filter(|b| b.is_intrinsic()) {
+            let symbol = Symbol::new_builtin(b, scope.clone());
+            scope.borrow_mut().add(symbol).unwrap();
+        }
+
+        scope
+    }
+
     /// Creates a new local scope.
     pub fn new_local(parent: ScopeRef) -> ScopeRef {
         let level = match parent.borrow().depth {
@@ -131,6 +255,11 @@ impl Scope {
             symbol_table: SymbolTable::new(),
             owner: None,
             depth: level,
+            is_loop: false,
+            imports: HashMap::new(),
+            exports: HashSet::new(),
+            submodules: HashSet::new(),
+            narrowed: Vec::new(),
         }))
     }
 
@@ -143,6 +272,13 @@ impl Scope {
         scope
     }
 
+    /// Creates a new loop scope, which is the block of a loop.
+    pub fn new_loop(parent: ScopeRef) -> ScopeRef {
+        let scope = Scope::new_local(parent);
+        scope.borrow_mut().is_loop = true;
+        scope
+    }
+
     pub fn add(&mut self, symbol: SymbolRef) -> Result<(), ()> {
         self.symbol_table.add(symbol)
     }
@@ -158,6 +294,61 @@ impl Scope {
         }
     }
 
+    /// Looks a symbol up by name in the builtin scope, which is the root of every scope.
+    pub fn lookup_builtin(&self, name: &str) -> Option<SymbolRef> {
+        match &self.parent {
+            Some(parent) => parent.borrow().lookup_builtin(name),
+            None => self.symbol_table.get(name),
+        }
+    }
+
+    /// Finds the object types with a field in this scope and all ancestor scopes recursively.
+    pub fn lookup_field(&self, field: &str) -> Vec<ObjRef> {
+        let mut objs: Vec<ObjRef> = self.symbol_table.symbols.values()
+            .filter_map(|s| s.borrow().obj.clone())
+            .filter(|o| o.borrow().field(field).is_some())
+            .collect();
+        if let Some(parent) = &self.parent {
+            objs.extend(parent.borrow().lookup_field(field));
+        }
+
+        objs
+    }
+
+    /// Finds the methods with a name of the object types in this scope and all ancestor scopes recursively.
+    pub fn lookup_method(&self, name: &str) -> Vec<SymbolRef> {
+        let mut methods: Vec<SymbolRef> = self.symbol_table.symbols.values()
+            .filter_map(|s| s.borrow().obj.clone())
+            .filter_map(|o| o.borrow().method(name))
+            .collect();
+        if let Some(parent) = &self.parent {
+            methods.extend(parent.borrow().lookup_method(name));
+        }
+
+        methods
+    }
+
+    /// Looks an imported module up by name in this scope and all ancestor scopes recursively.
+    pub fn lookup_module(&self, name: &str) -> Option<ScopeRef> {
+        match self.imports.get(name) {
+            Some(scope) => Some(scope.clone()),
+            None => match &self.parent {
+                Some(parent) => parent.borrow().lookup_module(name),
+                None => None,
+            },
+        }
+    }
+
+    /// Looks a module up by name within a module path, as `util` in `sub::util::inc`, in the global scope of a module.
+    /// Only the submodules of the module and the imported modules it exports can be named through it.
+    pub fn lookup_path_module(&self, name: &str) -> Option<ScopeRef> {
+        if !self.submodules.contains(name) && !self.exports.contains(name) {
+            return None;
+        }
+
+        self.imports.get(name).cloned()
+    }
+
     /// Finds the function that owns this scope.
     pub fn owning_fun(&self) -> Option<SymbolRef> {
         match &self.owner {
@@ -170,4 +361,83 @@ impl Scope {
             }
         }
     }
+
+    /// Determines if this scope is within a loop of the function that owns it.
+    pub fn in_loop(&self) -> bool {
+        if self.is_loop {
+            return true;
+        }
+
+        match (&self.owner, &self.parent) {
+            (None, Some(p)) => p.borrow().in_loop(),
+            _ => false,
+        }
+    }
+
+    /// Determines if an optional variable has been checked to hold a value in this scope or an ancestor scope
+    /// of the same function. A lambda does not see the checks outside of it.
+    pub fn is_narrowed(&self, symbol: &SymbolRef) -> bool {
+        if self.narrowed.iter().any(|s| Rc::ptr_eq(s, symbol)) {
+            return true;
+        }
+
+        match (&self.owner, &self.parent) {
+            (None, Some(p)) => p.borrow().is_narrowed(symbol),
+            _ => false,
+        }
+    }
+
+    /// Stops an optional variable that was checked by the condition of a loop from holding a value in the rest of the
+    /// loop block, once it is assigned there, since the condition checks it again before the next iteration.
+    /// Returns false if the variable was checked by a condition that is not checked again, so that it cannot be assigned.
+    pub fn unnarrow_loop(&mut self, symbol: &SymbolRef) -> bool {
+        if let Some(i) = self.narrowed.iter().position(|s| Rc::ptr_eq(s, symbol)) {
+            if self.is_loop {
+                self.narrowed.remove(i);
+            }
+            return self.is_loop;
+        }
+
+        match (&self.owner, &self.parent) {
+            (None, Some(p)) => p.borrow_mut().unnarrow_loop(symbol),
+            _ => false,
+        }
+    }
+
+    /// Records a local variable that is referred to from this scope as captured
+    /// by every lambda between this scope and the scope of the variable.
+    pub fn capture(scope: &ScopeRef, symbol: &SymbolRef) {
+        let (symbol_scope, is_variable) = {
+            let s = symbol.borrow();
+            (s.scope.clone(), matches!(s.dest, pvm::Operand::Variable(_)) && s.generic.is_none())
+        };
+        if symbol_scope.borrow().depth == ScopeDepth::Global || !is_variable {
+            return;
+        }
+
+        let mut lambdas = vec![];
+        let mut current = scope.clone();
+        while !Rc::ptr_eq(&current, &symbol_scope) {
+            let parent = {
+                let s = current.borrow();
+                if let Some(owner) = &s.owner {
+                    lambdas.push(owner.clone());
+                }
+
+                s.parent.clone()
+            };
+
+            current = match parent {
+                Some(p) => p,
+                None => return,
+            };
+        }
+
+        for l in lambdas {
+            let captured = l.borrow().captures.iter().any(|c| Rc::ptr_eq(c, symbol));
+            if !captured {
+                l.borrow_mut().captures.push(symbol.clone());
+            }
+        }
+    }
 }
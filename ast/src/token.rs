@@ -46,20 +46,34 @@ impl PartialEq for Token {
 pub enum TokenType {
     Keyword(Keyword),
     Identifier(String),
-    Integer(i64),
+    Integer(u64),
     Float(f64),
+    Char(char),
     String(String),
+    /// A string with embedded expressions, as in `"x = {x}"`, split into its segments.
+    InterpString(Vec<StrSegment>),
     Punctuation(Punctuation),
     Operator(Operator),
 }
 
+/// Represents a segment of an interpolated string.
+#[derive(Debug, PartialEq, Clone)]
+pub enum StrSegment {
+    Text(String),
+    /// An embedded expression as its tokens, which end with the closing brace, along with the number of decimal places given by its format specifier,
+    /// as in `{y:.2}`.
+    Expr(Vec<Token>, Option<usize>),
+}
+
 /// Represents a token type match.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum TokenTypeMatch {
     Identifier,
     Integer,
     Float,
+    Char,
     String,
+    InterpString,
     Operator,
 }
 
@@ -79,7 +93,9 @@ impl TokenMatch for TokenTypeMatch {
             TokenType::Identifier(_) => self == &TokenTypeMatch::Identifier,
             TokenType::Integer(_) => self == &TokenTypeMatch::Integer,
             TokenType::Float(_) => self == &TokenTypeMatch::Float,
+            TokenType::Char(_) => self == &TokenTypeMatch::Char,
             TokenType::String(_) => self == &TokenTypeMatch::String,
+            TokenType::InterpString(_) => self == &TokenTypeMatch::InterpString,
             TokenType::Operator(_) => self == &TokenTypeMatch::Operator,
             _ => false,
         }
@@ -91,14 +107,26 @@ impl TokenMatch for TokenTypeMatch {
 pub enum Keyword {
     #[strum(serialize = "fun")]
     Fun,
+    #[strum(serialize = "obj")]
+    Obj,
+    #[strum(serialize = "interface")]
+    Interface,
+    #[strum(serialize = "impl")]
+    Impl,
+    #[strum(serialize = "enum")]
+    Enum,
     #[strum(serialize = "import")]
     Import,
+    #[strum(serialize = "export")]
+    Export,
     #[strum(serialize = "begin")]
     Begin,
     #[strum(serialize = "end")]
     End,
     #[strum(serialize = "let")]
     Let,
+    #[strum(serialize = "const")]
+    Const,
     #[strum(serialize = "set")]
     Set,
     #[strum(serialize = "if")]
@@ -111,14 +139,30 @@ pub enum Keyword {
     Else,
     #[strum(serialize = "for")]
     For,
+    #[strum(serialize = "in")]
+    In,
     #[strum(serialize = "while")]
     While,
     #[strum(serialize = "do")]
     Do,
     #[strum(serialize = "return")]
     Return,
+    #[strum(serialize = "break")]
+    Break,
+    #[strum(serialize = "continue")]
+    Continue,
+    #[strum(serialize = "match")]
+    Match,
+    #[strum(serialize = "case")]
+    Case,
     #[strum(serialize = "int")]
     Int,
+    #[strum(serialize = "uint")]
+    Uint,
+    #[strum(serialize = "byte")]
+    Byte,
+    #[strum(serialize = "char")]
+    Char,
     #[strum(serialize = "float")]
     Float,
     #[strum(serialize = "bool")]
@@ -131,6 +175,14 @@ pub enum Keyword {
     True,
     #[strum(serialize = "false")]
     False,
+    #[strum(serialize = "none")]
+    None,
+    #[strum(serialize = "is")]
+    Is,
+    #[strum(serialize = "some")]
+    Some,
+    #[strum(serialize = "as")]
+    As,
 }
 
 impl TokenMatch for Keyword {
@@ -161,10 +213,36 @@ pub enum Punctuation {
     Comma,
     #[strum(serialize = ":", props(Value = ":"))]
     Colon,
+    #[strum(serialize = "::", props(Value = "::"))]
+    DoubleColon,
     #[strum(serialize = "->", props(Value = "->"))]
     Arrow,
     #[strum(serialize = "=", props(Value = "="))]
     EqualSign,
+    #[strum(serialize = ".", props(Value = "."))]
+    Dot,
+    #[strum(serialize = "..", props(Value = ".."))]
+    DotDot,
+    #[strum(serialize = "..=", props(Value = "..="))]
+    DotDotEqual,
+    #[strum(serialize = "=>", props(Value = "=>"))]
+    FatArrow,
+    #[strum(serialize = "_", props(Value = "_"))]
+    Underscore,
+    #[strum(serialize = "?", props(Value = "?"))]
+    QuestionMark,
+    #[strum(serialize = "+=", props(Value = "+="))]
+    PlusEqual,
+    #[strum(serialize = "-=", props(Value = "-="))]
+    MinusEqual,
+    #[strum(serialize = "*=", props(Value = "*="))]
+    StarEqual,
+    #[strum(serialize = "/=", props(Value = "/="))]
+    SlashEqual,
+    #[strum(serialize = "%=", props(Value = "%="))]
+    PercentEqual,
+    #[strum(serialize = "**=", props(Value = "**="))]
+    StarStarEqual,
 }
 
 impl Punctuation {
@@ -183,6 +261,19 @@ impl Punctuation {
             .unwrap()
             .len()
     }
+
+    /// Gets the binary operator applied by a compound assignment, such as `+=`.
+    pub fn compound_op(&self) -> Option<Operator> {
+        match self {
+            Punctuation::PlusEqual => Some(Operator::Add),
+            Punctuation::MinusEqual => Some(Operator::Subtract),
+            Punctuation::StarEqual => Some(Operator::Multiply),
+            Punctuation::SlashEqual => Some(Operator::Divide),
+            Punctuation::PercentEqual => Some(Operator::Modulo),
+            Punctuation::StarStarEqual => Some(Operator::Power),
+            _ => None,
+        }
+    }
 }
 
 impl TokenMatch for Punctuation {
@@ -2,31 +2,67 @@ use ast::*;
 use std::str::FromStr;
 
 pub(crate) fn parse_token_ref(token_ref: String) -> Vec<Token> {
-    token_ref
-        .lines()
-        .into_iter()
-        .filter(|line| !line.is_empty())
-        .map(|line| parse_token_ref_line(line))
-        .collect()
+    let mut lines = token_ref.lines().filter(|line| !line.is_empty());
+    let mut tokens = vec![];
+    while let Some(line) = lines.next() {
+        tokens.push(parse_token_ref_line(line, &mut lines));
+    }
+
+    tokens
 }
 
-fn parse_token_ref_line(line: &str) -> Token {
-    let parts = line.split(':').collect::<Vec<&str>>();
-    if parts.len() != 2 {
-        panic!("invalid line: {}", line);
-    }
+fn parse_token_ref_line<'a>(line: &str, lines: &mut impl Iterator<Item = &'a str>) -> Token {
+    let (token_type, token_value) = match line.split_once(':') {
+        Some(parts) => parts,
+        None => panic!("invalid line: {}", line),
+    };
 
-    let token_type = parts[0];
-    let token_value = parts[1];
     let token_type = match token_type {
         // TODO finish
         "key" => TokenType::Keyword(Keyword::from_str(token_value).unwrap()),
         "id" => TokenType::Identifier(String::from(token_value)),
         "int" => TokenType::Integer(token_value.parse().unwrap()),
         "flt" => TokenType::Float(token_value.parse().unwrap()),
+        "chr" => TokenType::Char(token_value.parse().unwrap()),
+        "str" => TokenType::String(String::from(token_value)),
+        "istr" => TokenType::InterpString(parse_segments(lines)),
         "pun" => TokenType::Punctuation(Punctuation::from_str(token_value).unwrap()),
         "op" => TokenType::Operator(Operator::from_str(token_value).unwrap()),
         _ => panic!("invalid token type: {}", token_type),
     };
     Token::new(token_type, Span::default())
 }
+
+/// Parses the segments of an interpolated string, which follow its `istr:` line up to an `end:` line.
+/// A text segment is a `txt:` line, and an embedded expression is an `expr:` line, followed by the number of
+/// decimal places of its format specifier if it has one, and then by the tokens of the expression up to its closing brace.
+fn parse_segments<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Vec<StrSegment> {
+    let mut segments = vec![];
+    while let Some(line) = lines.next() {
+        match line.split_once(':') {
+            Some(("end", _)) => return segments,
+            Some(("txt", text)) => segments.push(StrSegment::Text(String::from(text))),
+            Some(("expr", precision)) => {
+                let precision = precision.parse().ok();
+                let mut tokens = vec![];
+                let mut depth = 0;
+                while let Some(line) = lines.next() {
+                    let token = parse_token_ref_line(line, lines);
+                    let token_type = token.token_type.clone();
+                    tokens.push(token);
+                    match token_type {
+                        TokenType::Punctuation(Punctuation::OpenBrace) => depth += 1,
+                        TokenType::Punctuation(Punctuation::CloseBrace) if depth == 0 => break,
+                        TokenType::Punctuation(Punctuation::CloseBrace) => depth -= 1,
+                        _ => {}
+                    }
+                }
+
+                segments.push(StrSegment::Expr(tokens, precision));
+            }
+            _ => panic!("invalid line: {}", line),
+        }
+    }
+
+    panic!("unterminated interpolated string");
+}
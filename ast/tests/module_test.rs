@@ -0,0 +1,32 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use test_util::{generate_module_tests, test_file_content};
+
+generate_module_tests!(ast);
+
+fn test(mut test_base_path: PathBuf) {
+    let pine_path = test_base_path.with_extension("p");
+    let test_content = test_file_content(&mut test_base_path);
+    let result = ast::parse(pine_path);
+    if let Some(err_ref) = test_content.err_ref_content {
+        match result {
+            Ok(_) => panic!("expected the program to fail"),
+            Err(e) => assert_eq!(err_ref.trim(), format!("{}", e)),
+        }
+        return;
+    }
+
+    let program = result.unwrap();
+
+    // every module is parsed exactly once
+    let names: HashSet<&String> = program.modules.iter().map(|m| &m.name).collect();
+    assert_eq!(names.len(), program.modules.len());
+
+    // every module comes after the modules it imports
+    for (i, m) in program.modules.iter().enumerate() {
+        for import in &m.imports {
+            let j = program.modules.iter().position(|d| d.name == import.module).unwrap();
+            assert!(j < i, "{} must come before {}", import.module, m.name);
+        }
+    }
+}
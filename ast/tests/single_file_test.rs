@@ -6,6 +6,7 @@ use test_util::{generate_single_file_tests, test_file_content};
 generate_single_file_tests!(ast);
 
 fn test(mut test_base_path: PathBuf) {
+    let pine_path = test_base_path.with_extension("p");
     let test_content = test_file_content(&mut test_base_path);
     let pine_content = test_content.pine_content.expect("pine test input is required");
     if let Some(token_ref) = test_content.tok_ref_content {
@@ -15,7 +16,16 @@ fn test(mut test_base_path: PathBuf) {
         assert_eq!(expected, actual);
     }
 
-    let program = ast::parse(pine_content).unwrap();
-    assert!(program.main_module.funs.len() > 0);
+    let result = ast::parse(pine_path);
+    if let Some(err_ref) = test_content.err_ref_content {
+        match result {
+            Ok(_) => panic!("expected the program to fail"),
+            Err(e) => assert_eq!(err_ref.trim(), format!("{}", e)),
+        }
+        return;
+    }
+
+    let program = result.unwrap();
+    assert!(program.main_module().funs.len() > 0);
     // TODO test AST structure
 }
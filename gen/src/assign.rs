@@ -3,9 +3,15 @@ use crate::temp::TempStore;
 // TODO need to free temps when possilbe
 // TODO need to worry about fun calls, maybe make PVM have local fun vars?
 
-pub(crate) fn assign(program: &mut ast::Program) {
+/// Assigns destinations to expressions.
+/// The temp store is returned so that codegen can keep drawing unique temps from it.
+pub(crate) fn assign(program: &mut ast::Program) -> TempStore {
     let mut temp_store = TempStore::new();
-    program.main_module.assign(&mut temp_store)
+    for m in &mut program.modules {
+        m.assign(&mut temp_store);
+    }
+
+    temp_store
 }
 
 trait AstAssign {
@@ -14,9 +20,19 @@ trait AstAssign {
 
 impl AstAssign for ast::Module {
     fn assign(&mut self, temp_store: &mut TempStore) {
+        for g in &mut self.globals {
+            g.expr.assign(temp_store);
+        }
+
         for f in &mut self.funs {
             f.assign(temp_store);
         }
+
+        for i in &mut self.impls {
+            for f in &mut i.funs {
+                f.assign(temp_store);
+            }
+        }
     }
 }
 
@@ -32,8 +48,16 @@ impl AstAssign for ast::LetStmt {
     }
 }
 
+impl AstAssign for ast::LetTupleStmt {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        self.expr.assign(temp_store);
+        self.pattern.assign(temp_store);
+    }
+}
+
 impl AstAssign for ast::SetStmt {
     fn assign(&mut self, temp_store: &mut TempStore) {
+        self.target.assign(temp_store);
         self.expr.assign(temp_store);
     }
 }
@@ -61,6 +85,13 @@ impl AstAssign for ast::WhileStmt {
     }
 }
 
+impl AstAssign for ast::ForStmt {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        self.iter.assign(temp_store);
+        self.block.assign(temp_store);
+    }
+}
+
 impl AstAssign for ast::ReturnStmt {
     fn assign(&mut self, temp_store: &mut TempStore) {
         if let Some(e) = &mut self.expr {
@@ -87,10 +118,13 @@ impl AstAssign for ast::Stmt {
     fn assign(&mut self, temp_store: &mut TempStore) {
         match self {
             ast::Stmt::Let(s) => s.assign(temp_store),
+            ast::Stmt::LetTuple(s) => s.assign(temp_store),
             ast::Stmt::Set(s) => s.assign(temp_store),
             ast::Stmt::If(s) => s.assign(temp_store),
             ast::Stmt::While(s) => s.assign(temp_store),
+            ast::Stmt::For(s) => s.assign(temp_store),
             ast::Stmt::Return(s) => s.assign(temp_store),
+            ast::Stmt::Break(_) | ast::Stmt::Continue(_) => {}
             ast::Stmt::Expr(s) => s.assign(temp_store),
             ast::Stmt::Block(s) => s.assign(temp_store),
         }
@@ -111,6 +145,13 @@ impl AstAssign for ast::FloatLitExpr {
     }
 }
 
+impl AstAssign for ast::CharLitExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
 impl AstAssign for ast::BoolLitExpr {
     fn assign(&mut self, temp_store: &mut TempStore) {
         let t = temp_store.temp();
@@ -125,9 +166,26 @@ impl AstAssign for ast::StringLitExpr {
     }
 }
 
+impl AstAssign for ast::InterpStringExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        for p in &mut self.parts {
+            p.assign(temp_store);
+        }
+
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
 impl AstAssign for ast::IdentExpr {
-    fn assign(&mut self, _temp_store: &mut TempStore) {
-        self.dest = self.ident.symbol.borrow().dest.clone();
+    /// A function that is used as a value is wrapped in a closure, which is held in a temp,
+    /// and the value of an optional that has been checked is loaded into a temp.
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        self.dest = match self.ident.dest() {
+            pvm::Operand::Label(_) => pvm::Operand::Variable(temp_store.temp()),
+            _ if self.narrowed => pvm::Operand::Variable(temp_store.temp()),
+            dest => dest,
+        };
     }
 }
 
@@ -160,17 +218,179 @@ impl AstAssign for ast::BinaryExpr {
     }
 }
 
+impl AstAssign for ast::ListLitExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        for e in &mut self.elems {
+            e.assign(temp_store);
+        }
+
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::MapLitExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        for (k, v) in &mut self.entries {
+            k.assign(temp_store);
+            v.assign(temp_store);
+        }
+
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::SetLitExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        for e in &mut self.elems {
+            e.assign(temp_store);
+        }
+
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::TupleLitExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        for e in &mut self.elems {
+            e.assign(temp_store);
+        }
+
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::IndexExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        self.expr.assign(temp_store);
+        self.index.assign(temp_store);
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::FieldExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        self.expr.assign(temp_store);
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::CastExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        self.expr.assign(temp_store);
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::NoneLitExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::IsExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        self.expr.assign(temp_store);
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::TryExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        self.expr.assign(temp_store);
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::RangeExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        self.start.assign(temp_store);
+        self.end.assign(temp_store);
+    }
+}
+
+impl AstAssign for ast::MatchExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        self.expr.assign(temp_store);
+        for a in &mut self.arms {
+            a.pattern.assign(temp_store);
+            match &mut a.body {
+                ast::ArmBody::Expr(e) => e.assign(temp_store),
+                ast::ArmBody::Block(b) => b.assign(temp_store),
+            }
+        }
+
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
+impl AstAssign for ast::Pattern {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        match self {
+            ast::Pattern::Lit(p) => p.expr.assign(temp_store),
+            ast::Pattern::Variant(p) => {
+                for a in &mut p.args {
+                    a.assign(temp_store);
+                }
+            }
+            ast::Pattern::Tuple(p) => {
+                for e in &mut p.elems {
+                    e.assign(temp_store);
+                }
+            }
+            ast::Pattern::Wildcard(_) | ast::Pattern::Binding(_) => {}
+        }
+    }
+}
+
+impl AstAssign for ast::LambdaExpr {
+    fn assign(&mut self, temp_store: &mut TempStore) {
+        match &mut self.body {
+            ast::LambdaBody::Expr(e) => e.assign(temp_store),
+            ast::LambdaBody::Block(b) => b.assign(temp_store),
+        }
+
+        let t = temp_store.temp();
+        self.dest = pvm::Operand::Variable(t)
+    }
+}
+
 impl AstAssign for ast::Expr {
     fn assign(&mut self, temp_store: &mut TempStore) {
         match self {
             ast::Expr::IntLit(e) => e.assign(temp_store),
             ast::Expr::FloatLit(e) => e.assign(temp_store),
+            ast::Expr::CharLit(e) => e.assign(temp_store),
             ast::Expr::BoolLit(e) => e.assign(temp_store),
             ast::Expr::StringLit(e) => e.assign(temp_store),
+            ast::Expr::InterpString(e) => e.assign(temp_store),
             ast::Expr::Ident(e) => e.assign(temp_store),
             ast::Expr::Call(e) => e.assign(temp_store),
             ast::Expr::Unary(e) => e.assign(temp_store),
             ast::Expr::Binary(e) => e.assign(temp_store),
+            ast::Expr::Range(e) => e.assign(temp_store),
+            ast::Expr::ListLit(e) => e.assign(temp_store),
+            ast::Expr::MapLit(e) => e.assign(temp_store),
+            ast::Expr::SetLit(e) => e.assign(temp_store),
+            ast::Expr::TupleLit(e) => e.assign(temp_store),
+            ast::Expr::Index(e) => e.assign(temp_store),
+            ast::Expr::Field(e) => e.assign(temp_store),
+            ast::Expr::Cast(e) => e.assign(temp_store),
+            ast::Expr::NoneLit(e) => e.assign(temp_store),
+            ast::Expr::Is(e) => e.assign(temp_store),
+            ast::Expr::Try(e) => e.assign(temp_store),
+            ast::Expr::Match(e) => e.assign(temp_store),
+            ast::Expr::Lambda(e) => e.assign(temp_store),
         }
     }
 }
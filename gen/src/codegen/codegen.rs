@@ -1,10 +1,18 @@
-use ast::{Ast, Expr, Operator, PineType};
+use ast::{Ast, Builtin, Expr, Operator, PineType};
 use crate::codegen::append::*;
-use crate::codegen::context::Context;
+use crate::codegen::context::{Context, Loop};
 use crate::codegen::{Inst, InstVec};
+use crate::runtime::Runtime;
+use crate::temp::TempStore;
+use std::rc::Rc;
 
-pub(crate) fn codegen(program: &ast::Program) -> InstVec {
-    let mut context = Context::new();
+pub(crate) fn codegen(program: &ast::Program, temp_store: TempStore) -> InstVec {
+    let mut context = Context::new(temp_store);
+    context.core_funs = program.modules.iter()
+        .filter(|m| m.name == ast::CORE_MODULE)
+        .flat_map(|m| &m.funs)
+        .map(|f| (f.ident.name.clone(), f.ident.symbol.clone()))
+        .collect();
     program.gen(&mut context)
 }
 
@@ -21,6 +29,9 @@ macro_rules! concat {
     }
 }
 
+/// The number of elements a list has room for before it must grow.
+const LIST_MIN_CAPACITY: usize = 4;
+
 fn wrap<T>(inst: T) -> Inst
 // TODO replace wrap with .into()
 where
@@ -35,8 +46,28 @@ trait AstCodeGen {
 
 impl AstCodeGen for ast::Program {
     fn gen(&self, context: &mut Context) -> InstVec {
-        let main_call_inst = wrap(pvm::CallInst::new(pvm::Operand::Label(self.main_fun.borrow().name.clone())));
-        let mut insts = vec![main_call_inst];
+        // vtables and the variants without payloads are built before main is called, and are held in global variables
+        let mut insts = Vec::new();
+        for m in &self.modules {
+            for i in m.impls.iter().filter(|i| i.iface.is_some()) {
+                let v_insts = gen_vtable(i, context);
+                insts = concat!(insts, v_insts);
+            }
+
+            for e in &m.enums {
+                let v_insts = gen_unit_variants(e);
+                insts = concat!(insts, v_insts);
+            }
+        }
+
+        // global variables are initialized in the order of the modules, after the modules they import
+        for m in &self.modules {
+            let g_insts = gen_globals(m, context);
+            insts = concat!(insts, g_insts);
+        }
+
+        let main_call_inst = wrap(pvm::CallInst::new(self.main_fun.borrow().dest.clone()));
+        insts = concat!(insts, main_call_inst);
         match &self.main_fun.borrow().pine_type {
             PineType::Function { ret, .. } => {
                 if **ret == PineType::Integer {
@@ -52,39 +83,272 @@ impl AstCodeGen for ast::Program {
             _ => panic!("codegen bug")
         }
         
-        let m_insts = self.main_module.gen(context);
-        concat!(insts, m_insts)
+        for m in &self.modules {
+            let m_insts = m.gen(context);
+            insts = concat!(insts, m_insts);
+        }
+
+        // generic functions are generated once for every set of type arguments they are called with,
+        // and the functions and constructors of the core module once they are used, which may call further functions
+        while let Some(instance) = context.instances.pop() {
+            let fun = self.modules.iter()
+                .flat_map(|m| m.funs.iter().chain(m.impls.iter().flat_map(|i| &i.funs)))
+                .find(|f| Rc::ptr_eq(&f.ident.symbol, &instance.symbol));
+            let f_insts = match fun {
+                Some(fun) => {
+                    context.subst = instance.subst;
+                    gen_fun(fun, instance.label, context)
+                }
+                None => self.modules.iter()
+                    .flat_map(|m| &m.objs)
+                    .find(|o| Rc::ptr_eq(&o.ident.symbol, &instance.symbol))
+                    .expect("codegen bug")
+                    .gen(context),
+            };
+            insts = concat!(insts, f_insts);
+        }
+
+        context.subst.clear();
+        insts = concat!(insts, std::mem::take(&mut context.lambdas));
+
+        // the closure being called is passed to lambdas in a variable that is global, since it is set before main is called
+        if context.closures {
+            insts.insert(0, wrap(pvm::MoveInst::new(closure_var(), pvm::Operand::Constant(0))));
+        }
+
+        // the first block is allocated before anything else and never freed, so that no box is at address zero
+        if context.optionals {
+            let null = pvm::Operand::Variable("null".to_string());
+            insts.insert(0, wrap(pvm::AllocInst::new(null, pvm::Operand::Constant(8))));
+        }
+
+        for r in &context.runtime {
+            insts = concat!(insts, r.insts());
+        }
+
+        insts
+    }
+}
+
+/// Generates the initialization of the global variables of a module, and of its constants that are strings,
+/// which are allocated once and held in global variables.
+fn gen_globals(module: &ast::Module, context: &mut Context) -> InstVec {
+    let mut insts = Vec::new();
+    for c in &module.consts {
+        if let Some(ast::ConstValue::String(value)) = &c.ident.symbol.borrow().constant {
+            let s_insts = gen_string(value, c.ident.dest(), context);
+            insts = concat!(insts, s_insts);
+        }
+    }
+
+    for g in &module.globals {
+        let e_insts = g.expr.gen(context);
+        let move_inst = wrap(pvm::MoveInst::new(g.ident.dest(), g.expr.dest()));
+        insts = concat!(insts, e_insts, move_inst);
     }
+
+    insts
 }
 
 impl AstCodeGen for ast::Module {
     fn gen(&self, context: &mut Context) -> InstVec {
+        // the functions, methods and constructors of the core module are generated once they are used
+        let is_core = self.name == ast::CORE_MODULE;
         let mut insts = Vec::new();
-        for f in &self.funs {
+        for o in self.objs.iter().filter(|_| !is_core) {
+            let o_insts = o.gen(context);
+            insts = concat!(insts, o_insts);
+        }
+
+        for e in &self.enums {
+            let e_insts = e.gen(context);
+            insts = concat!(insts, e_insts);
+        }
+
+        for i in &self.interfaces {
+            let i_insts = i.gen(context);
+            insts = concat!(insts, i_insts);
+        }
+
+        for f in self.funs.iter().filter(|f| !is_generic(f) && !is_core) {
             let f_insts = f.gen(context);
             insts = concat!(insts, f_insts);
         }
 
+        for i in &self.impls {
+            let is_core_methods = is_core && i.iface.is_none();
+            for f in i.funs.iter().filter(|f| !is_generic(f) && !is_core_methods) {
+                let f_insts = f.gen(context);
+                insts = concat!(insts, f_insts);
+            }
+        }
+
         insts
     }
 }
 
-impl AstCodeGen for ast::Fun {
+/// Generates the vtable of an implementation, which holds the addresses
+/// of the implementing functions in the order of the interface methods.
+fn gen_vtable(impl_block: &ast::Impl, context: &mut Context) -> InstVec {
+    let impl_def = match (impl_block.iface.as_ref().map(|i| &i.ty), &impl_block.obj.ty) {
+        (Some(PineType::Interface(iface)), PineType::Object(obj, _)) => iface.borrow().impl_for(obj).expect("codegen bug"),
+        _ => panic!("codegen bug")
+    };
+
+    let vtable = pvm::Operand::Variable(ast::IfaceDef::vtable(&impl_def));
+    let addr = context.temp();
+    let slot = context.temp();
+    let size = impl_def.methods.len().max(1) * 8;
+    let alloc_inst = wrap(pvm::AllocInst::new(vtable.clone(), pvm::Operand::Constant(pvm::to_u64!(size))));
+    let mut insts = vec![alloc_inst];
+    for (i, m) in impl_def.methods.iter().enumerate() {
+        let addr_inst = wrap(pvm::FunAddrInst::new(addr.clone(), m.borrow().dest.clone()));
+        let offset = pvm::Operand::Constant(pvm::to_u64!(i * 8));
+        let slot_inst = wrap(pvm::AddInst::new(slot.clone(), vtable.clone(), offset));
+        let store_inst = wrap(pvm::StoreInst::new(slot.clone(), addr.clone()));
+        insts = concat!(insts, addr_inst, slot_inst, store_inst);
+    }
+
+    insts
+}
+
+impl AstCodeGen for ast::Interface {
+    /// Generates a function for each method that dispatches to the implementation
+    /// through the vtable of its first argument.
+    /// An interface value is a block holding the address of the object and the address of the vtable.
     fn gen(&self, context: &mut Context) -> InstVec {
         let mut insts = Vec::new();
+        for (i, m) in self.methods.iter().enumerate() {
+            let fun_inst = wrap(pvm::FunInst::new(m.ident.dest()));
+            insts = concat!(insts, fun_inst);
 
-        let fun_l = pvm::Operand::Label(self.ident.symbol.borrow().name.clone());
-        let fun_i = wrap(pvm::FunInst::new(fun_l));
-        insts = concat!(insts, fun_i);
+            let params: Vec<pvm::Operand> = m.params.iter().map(|_| context.temp()).collect();
+            for p in &params {
+                let popa_inst = wrap(pvm::PopaInst::new(p.clone()));
+                insts = concat!(insts, popa_inst);
+            }
 
-        for p in &self.params {
-            let p_insts = p.gen(context);
-            insts = concat!(insts, p_insts)
+            let obj = context.temp();
+            let fun = context.temp();
+            let obj_inst = wrap(pvm::LoadInst::new(obj.clone(), params[0].clone()));
+            let vtable_addr_inst = wrap(pvm::AddInst::new(fun.clone(), params[0].clone(), pvm::Operand::Constant(8)));
+            let vtable_inst = wrap(pvm::LoadInst::new(fun.clone(), fun.clone()));
+            let offset = pvm::Operand::Constant(pvm::to_u64!(i * 8));
+            let slot_inst = wrap(pvm::AddInst::new(fun.clone(), fun.clone(), offset));
+            let fun_addr_inst = wrap(pvm::LoadInst::new(fun.clone(), fun.clone()));
+            let pusha_inst = wrap(pvm::PushaInst::new(obj));
+            insts = concat!(insts, obj_inst, vtable_addr_inst, vtable_inst, slot_inst, fun_addr_inst, pusha_inst);
+            for p in &params[1..] {
+                let pusha_inst = wrap(pvm::PushaInst::new(p.clone()));
+                insts = concat!(insts, pusha_inst);
+            }
+
+            // the return value of the implementation is left in the return queue for the caller
+            let call_inst = wrap(pvm::CallIndirectInst::new(fun));
+            let ret_inst = wrap(pvm::RetInst::new());
+            insts = concat!(insts, call_inst, ret_inst);
+        }
+
+        insts
+    }
+}
+
+impl AstCodeGen for ast::Obj {
+    /// Generates the constructor of the object, which is called like a function
+    /// with one argument per field and returns the address of the new object.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let fun_inst = wrap(pvm::FunInst::new(self.ident.dest()));
+        let obj = context.temp();
+        let addr = context.temp();
+
+        // an object without fields still takes up a word, since empty blocks cannot be allocated
+        let size = self.fields.len().max(1) * 8;
+        let alloc_inst = wrap(pvm::AllocInst::new(obj.clone(), pvm::Operand::Constant(pvm::to_u64!(size))));
+        let mut insts = concat!(fun_inst, alloc_inst);
+        for i in 0..self.fields.len() {
+            let value = context.temp();
+            let popa_inst = wrap(pvm::PopaInst::new(value.clone()));
+            let offset = pvm::Operand::Constant(pvm::to_u64!(i * 8));
+            let field_inst = wrap(pvm::AddInst::new(addr.clone(), obj.clone(), offset));
+            let store_inst = wrap(pvm::StoreInst::new(addr.clone(), value));
+            insts = concat!(insts, popa_inst, field_inst, store_inst);
+        }
+
+        let pushr_inst = wrap(pvm::PushrInst::new(obj));
+        let ret_inst = wrap(pvm::RetInst::new());
+        concat!(insts, pushr_inst, ret_inst)
+    }
+}
+
+/// Generates the values of the variants of an enum without payloads.
+/// An enum value is a block holding the tag of its variant, followed by one word per part of its payload.
+fn gen_unit_variants(enum_decl: &ast::Enum) -> InstVec {
+    let mut insts = Vec::new();
+    for (tag, v) in enum_decl.variants.iter().enumerate().filter(|(_, v)| v.payload.is_empty()) {
+        let dest = v.ident.dest();
+        let alloc_inst = wrap(pvm::AllocInst::new(dest.clone(), pvm::Operand::Constant(8)));
+        let tag_inst = wrap(pvm::StoreInst::new(dest, pvm::Operand::Constant(pvm::to_u64!(tag))));
+        insts = concat!(insts, alloc_inst, tag_inst);
+    }
+
+    insts
+}
+
+impl AstCodeGen for ast::Enum {
+    /// Generates the constructors of the variants with payloads, which are called like functions
+    /// with one argument per part of the payload and return the address of the new value.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let mut insts = Vec::new();
+        for (tag, v) in self.variants.iter().enumerate().filter(|(_, v)| !v.payload.is_empty()) {
+            let fun_inst = wrap(pvm::FunInst::new(v.ident.dest()));
+            let value = context.temp();
+            let addr = context.temp();
+            let size = (v.payload.len() + 1) * 8;
+            let alloc_inst = wrap(pvm::AllocInst::new(value.clone(), pvm::Operand::Constant(pvm::to_u64!(size))));
+            let tag_inst = wrap(pvm::StoreInst::new(value.clone(), pvm::Operand::Constant(pvm::to_u64!(tag))));
+            insts = concat!(insts, fun_inst, alloc_inst, tag_inst);
+            for i in 0..v.payload.len() {
+                let part = context.temp();
+                let popa_inst = wrap(pvm::PopaInst::new(part.clone()));
+                let offset = pvm::Operand::Constant(pvm::to_u64!((i + 1) * 8));
+                let part_inst = wrap(pvm::AddInst::new(addr.clone(), value.clone(), offset));
+                let store_inst = wrap(pvm::StoreInst::new(addr.clone(), part));
+                insts = concat!(insts, popa_inst, part_inst, store_inst);
+            }
+
+            let pushr_inst = wrap(pvm::PushrInst::new(value));
+            let ret_inst = wrap(pvm::RetInst::new());
+            insts = concat!(insts, pushr_inst, ret_inst);
         }
 
-        let b_insts = self.block.gen(context);
-        concat!(insts, b_insts)
+        insts
+    }
+}
+
+impl AstCodeGen for ast::Fun {
+    fn gen(&self, context: &mut Context) -> InstVec {
+        gen_fun(self, self.ident.dest(), context)
+    }
+}
+
+/// Determines if a function is generic, in which case it is generated per instance rather than once.
+fn is_generic(fun: &ast::Fun) -> bool {
+    !fun.ident.symbol.borrow().pine_type.generics().is_empty()
+}
+
+/// Generates a function at a label.
+fn gen_fun(fun: &ast::Fun, label: pvm::Operand, context: &mut Context) -> InstVec {
+    let mut insts = Vec::new();
+    let fun_i = wrap(pvm::FunInst::new(label));
+    insts = concat!(insts, fun_i);
+
+    for p in &fun.params {
+        let p_insts = p.gen(context);
+        insts = concat!(insts, p_insts)
     }
+
+    let b_insts = fun.block.gen(context);
+    concat!(insts, b_insts)
 }
 
 impl AstCodeGen for ast::Param {
@@ -105,13 +369,78 @@ impl AstCodeGen for ast::LetStmt {
     }
 }
 
+impl AstCodeGen for ast::LetTupleStmt {
+    /// Generates the binding of the variables of the pattern. A function that returns a tuple returns its elements
+    /// separately, so a call whose result is destructured binds the elements without building the tuple.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let lab_prefix = context.label_gen.let_prefix();
+        let fail = pvm::Operand::Label(format!("{}_end", lab_prefix));
+        let insts = match (self.pattern.as_ref(), self.expr.as_ref()) {
+            (ast::Pattern::Tuple(p), Expr::Call(call)) if call.builtin().is_none() => {
+                let (c_insts, elems) = gen_call(call, context);
+                let mut insts = c_insts;
+                for (e, value) in p.elems.iter().zip(elems) {
+                    let e_insts = gen_pattern(e, value, &fail, context);
+                    insts = concat!(insts, e_insts);
+                }
+
+                insts
+            }
+            _ => {
+                let e_insts = self.expr.gen(context);
+                let p_insts = gen_pattern(&self.pattern, self.expr.dest(), &fail, context);
+                concat!(e_insts, p_insts)
+            }
+        };
+
+        // the pattern matches every value, so the label is only there for the pattern to jump to
+        let fail_inst = wrap(pvm::LabelInst::new(fail));
+        concat!(insts, fail_inst)
+    }
+}
+
 impl AstCodeGen for ast::SetStmt {
+    /// Generates an assignment to a place. The address of an index or field is computed once,
+    /// so a compound assignment loads and stores through the same address.
     fn gen(&self, context: &mut Context) -> InstVec {
         let e_insts = self.expr.gen(context);
         let src = self.expr.dest();
-        let dest = self.ident.dest();
-        let move_inst = wrap(pvm::MoveInst::new(dest, src));
-        concat!(e_insts, move_inst)
+        let (addr, addr_insts) = match self.target.as_ref() {
+            Expr::Index(target) => {
+                let addr = context.temp();
+                (addr.clone(), gen_index_addr(target, addr, context))
+            }
+            Expr::Field(target) => {
+                let addr = context.temp();
+                (addr.clone(), gen_field_addr(target, addr, context))
+            }
+            target => {
+                let op_insts = match self.op {
+                    Some(op) => {
+                        let ty = context.ty(target);
+                        let value = context.temp();
+                        let op_insts = gen_binary(&value, op, &ty, target.dest(), src, context);
+                        let move_inst = wrap(pvm::MoveInst::new(target.dest(), value));
+                        concat!(op_insts, move_inst)
+                    }
+                    None => vec![wrap(pvm::MoveInst::new(target.dest(), src))],
+                };
+                return concat!(e_insts, op_insts);
+            }
+        };
+
+        let store_insts = match self.op {
+            Some(op) => {
+                let ty = context.ty(&self.target);
+                let (old, value) = (context.temp(), context.temp());
+                let load_inst = wrap(pvm::LoadInst::new(old.clone(), addr.clone()));
+                let op_insts = gen_binary(&value, op, &ty, old, src, context);
+                let store_inst = wrap(pvm::StoreInst::new(addr, value));
+                concat!(load_inst, op_insts, store_inst)
+            }
+            None => vec![wrap(pvm::StoreInst::new(addr, src))],
+        };
+        concat!(e_insts, addr_insts, store_insts)
     }
 }
 
@@ -160,9 +489,9 @@ impl AstCodeGen for ast::WhileStmt {
         let end_label = pvm::Operand::Label(format!("{}_end", lab_prefix));
         let top_label_inst = wrap(pvm::LabelInst::new(top_label.clone()));
         let end_label_inst = wrap(pvm::LabelInst::new(end_label.clone()));
-        let top_jump_inst = wrap(pvm::JumpInst::new(top_label));
+        let top_jump_inst = wrap(pvm::JumpInst::new(top_label.clone()));
         let c_insts = self.cond.gen(context);
-        let b_insts = self.block.gen(context);
+        let b_insts = gen_loop_block(&self.block, top_label.clone(), end_label.clone(), context);
         let end_jump_inst = wrap(pvm::JumpZeroInst::new(self.cond.dest(), end_label));
         concat!(
             top_label_inst,
@@ -175,22 +504,261 @@ impl AstCodeGen for ast::WhileStmt {
     }
 }
 
+impl AstCodeGen for ast::ForStmt {
+    fn gen(&self, context: &mut Context) -> InstVec {
+        match self.iter.as_ref() {
+            Expr::Range(r) => gen_range_for(self, r, context),
+            e if matches!(context.ty(e), PineType::Map(..) | PineType::Set(_)) => gen_map_for(self, context),
+            _ => gen_list_for(self, context),
+        }
+    }
+}
+
+/// Generates the block of a loop, in which a continue jumps to the next label and a break jumps to the end label.
+fn gen_loop_block(block: &ast::Block, next: pvm::Operand, end: pvm::Operand, context: &mut Context) -> InstVec {
+    context.loops.push(Loop { next, end });
+    let insts = block.gen(context);
+    context.loops.pop();
+    insts
+}
+
+/// Generates a for loop over a range.
+/// The bounds are evaluated once, and the loop variable is set from a hidden counter
+/// so that setting it in the block does not change the number of iterations.
+fn gen_range_for(stmt: &ast::ForStmt, range: &ast::RangeExpr, context: &mut Context) -> InstVec {
+    let lab_prefix = context.label_gen.for_prefix();
+    let top_label = pvm::Operand::Label(format!("{}_top", lab_prefix));
+    let next_label = pvm::Operand::Label(format!("{}_next", lab_prefix));
+    let end_label = pvm::Operand::Label(format!("{}_end", lab_prefix));
+    let counter = context.temp();
+    let end = context.temp();
+    let cond = context.temp();
+
+    let s_insts = range.start.gen(context);
+    let e_insts = range.end.gen(context);
+    let counter_inst = wrap(pvm::MoveInst::new(counter.clone(), range.start.dest()));
+    let end_inst = wrap(pvm::MoveInst::new(end.clone(), range.end.dest()));
+    let top_label_inst = wrap(pvm::LabelInst::new(top_label.clone()));
+    let cond_inst = if range.inclusive {
+        wrap(pvm::LteInst::new(cond.clone(), counter.clone(), end.clone()))
+    } else {
+        wrap(pvm::LtInst::new(cond.clone(), counter.clone(), end.clone()))
+    };
+    let end_jump_inst = wrap(pvm::JumpZeroInst::new(cond, end_label.clone()));
+    let ident_inst = wrap(pvm::MoveInst::new(stmt.ident.dest(), counter.clone()));
+    let b_insts = gen_loop_block(&stmt.block, next_label.clone(), end_label.clone(), context);
+    let next_label_inst = wrap(pvm::LabelInst::new(next_label));
+    let inc_inst = wrap(pvm::AddInst::new(counter.clone(), counter, pvm::Operand::Constant(1)));
+    let top_jump_inst = wrap(pvm::JumpInst::new(top_label));
+    let end_label_inst = wrap(pvm::LabelInst::new(end_label));
+    concat!(
+        s_insts,
+        e_insts,
+        counter_inst,
+        end_inst,
+        top_label_inst,
+        cond_inst,
+        end_jump_inst,
+        ident_inst,
+        b_insts,
+        next_label_inst,
+        inc_inst,
+        top_jump_inst,
+        end_label_inst
+    )
+}
+
+/// Generates a for loop over a list.
+/// The length and data of the list are reloaded every iteration, since the block may push to the list.
+fn gen_list_for(stmt: &ast::ForStmt, context: &mut Context) -> InstVec {
+    let lab_prefix = context.label_gen.for_prefix();
+    let top_label = pvm::Operand::Label(format!("{}_top", lab_prefix));
+    let next_label = pvm::Operand::Label(format!("{}_next", lab_prefix));
+    let end_label = pvm::Operand::Label(format!("{}_end", lab_prefix));
+    let list = context.temp();
+    let index = context.temp();
+    let len = context.temp();
+    let cond = context.temp();
+    let addr = context.temp();
+
+    let l_insts = stmt.iter.gen(context);
+    let list_inst = wrap(pvm::MoveInst::new(list.clone(), stmt.iter.dest()));
+    let index_inst = wrap(pvm::MoveInst::new(index.clone(), pvm::Operand::Constant(0)));
+    let top_label_inst = wrap(pvm::LabelInst::new(top_label.clone()));
+    let len_inst = wrap(pvm::LoadInst::new(len.clone(), list.clone()));
+    let cond_inst = wrap(pvm::LtInst::new(cond.clone(), index.clone(), len));
+    let end_jump_inst = wrap(pvm::JumpZeroInst::new(cond, end_label.clone()));
+    let elem_insts = gen_list_elem_addr(addr.clone(), list, index.clone(), context);
+    let ident_inst = wrap(pvm::LoadInst::new(stmt.ident.dest(), addr));
+    let b_insts = gen_loop_block(&stmt.block, next_label.clone(), end_label.clone(), context);
+    let next_label_inst = wrap(pvm::LabelInst::new(next_label));
+    let inc_inst = wrap(pvm::AddInst::new(index.clone(), index, pvm::Operand::Constant(1)));
+    let top_jump_inst = wrap(pvm::JumpInst::new(top_label));
+    let end_label_inst = wrap(pvm::LabelInst::new(end_label));
+    concat!(
+        l_insts,
+        list_inst,
+        index_inst,
+        top_label_inst,
+        len_inst,
+        cond_inst,
+        end_jump_inst,
+        elem_insts,
+        ident_inst,
+        b_insts,
+        next_label_inst,
+        inc_inst,
+        top_jump_inst,
+        end_label_inst
+    )
+}
+
+/// Generates a for loop over the keys of a map or the elements of a set, which visits every full slot of the map.
+/// The capacity and data of the map are reloaded every iteration, since the block may insert into the map.
+fn gen_map_for(stmt: &ast::ForStmt, context: &mut Context) -> InstVec {
+    let lab_prefix = context.label_gen.for_prefix();
+    let top_label = pvm::Operand::Label(format!("{}_top", lab_prefix));
+    let next_label = pvm::Operand::Label(format!("{}_next", lab_prefix));
+    let end_label = pvm::Operand::Label(format!("{}_end", lab_prefix));
+    let map = context.temp();
+    let index = context.temp();
+    let cap = context.temp();
+    let cond = context.temp();
+    let addr = context.temp();
+    let offset = context.temp();
+
+    let m_insts = stmt.iter.gen(context);
+    let map_inst = wrap(pvm::MoveInst::new(map.clone(), stmt.iter.dest()));
+    let index_inst = wrap(pvm::MoveInst::new(index.clone(), pvm::Operand::Constant(0)));
+    let top_label_inst = wrap(pvm::LabelInst::new(top_label.clone()));
+    let cap_addr_inst = wrap(pvm::AddInst::new(addr.clone(), map.clone(), pvm::Operand::Constant(8)));
+    let cap_inst = wrap(pvm::LoadInst::new(cap.clone(), addr.clone()));
+    let cond_inst = wrap(pvm::LtInst::new(cond.clone(), index.clone(), cap));
+    let end_jump_inst = wrap(pvm::JumpZeroInst::new(cond.clone(), end_label.clone()));
+    let data_addr_inst = wrap(pvm::AddInst::new(addr.clone(), map, pvm::Operand::Constant(16)));
+    let data_inst = wrap(pvm::LoadInst::new(addr.clone(), addr.clone()));
+    let offset_inst = wrap(pvm::MulInst::new(offset.clone(), index.clone(), pvm::Operand::Constant(24)));
+    let slot_inst = wrap(pvm::AddInst::new(addr.clone(), addr.clone(), offset));
+    let state_inst = wrap(pvm::LoadInst::new(cond.clone(), addr.clone()));
+    let full_inst = wrap(pvm::EqInst::new(cond.clone(), cond.clone(), pvm::Operand::Constant(1)));
+    let skip_jump_inst = wrap(pvm::JumpZeroInst::new(cond, next_label.clone()));
+    let key_addr_inst = wrap(pvm::AddInst::new(addr.clone(), addr.clone(), pvm::Operand::Constant(8)));
+    let ident_inst = wrap(pvm::LoadInst::new(stmt.ident.dest(), addr));
+    let b_insts = gen_loop_block(&stmt.block, next_label.clone(), end_label.clone(), context);
+    let next_label_inst = wrap(pvm::LabelInst::new(next_label));
+    let inc_inst = wrap(pvm::AddInst::new(index.clone(), index, pvm::Operand::Constant(1)));
+    let top_jump_inst = wrap(pvm::JumpInst::new(top_label));
+    let end_label_inst = wrap(pvm::LabelInst::new(end_label));
+    concat!(
+        m_insts,
+        map_inst,
+        index_inst,
+        top_label_inst,
+        cap_addr_inst,
+        cap_inst,
+        cond_inst,
+        end_jump_inst,
+        data_addr_inst,
+        data_inst,
+        offset_inst,
+        slot_inst,
+        state_inst,
+        full_inst,
+        skip_jump_inst,
+        key_addr_inst,
+        ident_inst,
+        b_insts,
+        next_label_inst,
+        inc_inst,
+        top_jump_inst,
+        end_label_inst
+    )
+}
+
+/// Generates the instructions that compute the address of a list element.
+/// A list is a header block holding the length and the address of a data block,
+/// and the data block holds one word per element.
+fn gen_list_elem_addr(dest: pvm::Operand, list: pvm::Operand, index: pvm::Operand, context: &mut Context) -> InstVec {
+    let offset = context.temp();
+    let data_addr_inst = wrap(pvm::AddInst::new(dest.clone(), list, pvm::Operand::Constant(8)));
+    let data_inst = wrap(pvm::LoadInst::new(dest.clone(), dest.clone()));
+    let offset_inst = wrap(pvm::MulInst::new(offset.clone(), index, pvm::Operand::Constant(8)));
+    let elem_inst = wrap(pvm::AddInst::new(dest.clone(), dest.clone(), offset));
+    vec![data_addr_inst, data_inst, offset_inst, elem_inst]
+}
+
+/// Generates the instructions that compute the address of an indexed list element,
+/// raising a runtime error if the index is out of bounds.
+fn gen_index_addr(expr: &ast::IndexExpr, dest: pvm::Operand, context: &mut Context) -> InstVec {
+    let l_insts = expr.expr.gen(context);
+    let i_insts = expr.index.gen(context);
+    let len = context.temp();
+    let len_inst = wrap(pvm::LoadInst::new(len.clone(), expr.expr.dest()));
+    let bounds_inst = wrap(pvm::BoundsInst::new(expr.index.dest(), len));
+    let elem_insts = gen_list_elem_addr(dest, expr.expr.dest(), expr.index.dest(), context);
+    concat!(l_insts, i_insts, len_inst, bounds_inst, elem_insts)
+}
+
+/// Generates the instructions that compute the address of an object field.
+/// An object is a block holding one word per field, in the order the fields are declared.
+fn gen_field_addr(expr: &ast::FieldExpr, dest: pvm::Operand, context: &mut Context) -> InstVec {
+    let index = match expr.expr.ty() {
+        PineType::Object(o, _) => o.borrow().field(&expr.field.name).expect("codegen bug").0,
+        _ => panic!("codegen bug")
+    };
+
+    let o_insts = expr.expr.gen(context);
+    let offset = pvm::Operand::Constant(pvm::to_u64!(index * 8));
+    let field_inst = wrap(pvm::AddInst::new(dest, expr.expr.dest(), offset));
+    concat!(o_insts, field_inst)
+}
+
 impl AstCodeGen for ast::ReturnStmt {
     fn gen(&self, context: &mut Context) -> InstVec {
-        let mut insts = Vec::new();
-        if let Some(expr) = &self.expr {
-            let e_insts = expr.gen(context);
-            // TODO test print code
-            let print_inst = wrap(pvm::PrintiInst::new(expr.dest()));
-            let println_inst = wrap(pvm::PrintlnInst::new());
+        gen_return(self.expr.as_deref(), context)
+    }
+}
 
-            let pushr_inst = wrap(pvm::PushrInst::new(expr.dest()));
-            insts = concat!(insts, e_insts, print_inst, println_inst, pushr_inst);
-        }
+/// Generates a return from a function, pushing the returned value if there is one.
+fn gen_return(expr: Option<&Expr>, context: &mut Context) -> InstVec {
+    let mut insts = Vec::new();
+    if let Some(expr) = expr {
+        insts = match (expr, context.ty(expr)) {
+            // a tuple is returned one element at a time
+            (Expr::TupleLit(e), _) => {
+                let mut insts = Vec::new();
+                for e in &e.elems {
+                    let e_insts = e.gen(context);
+                    insts = concat!(insts, e_insts);
+                }
 
-        let ret_inst = wrap(pvm::RetInst::new());
-        concat!(insts, ret_inst)
+                let pushr_insts: InstVec = e.elems.iter().map(|e| wrap(pvm::PushrInst::new(e.dest()))).collect();
+                concat!(insts, pushr_insts)
+            }
+            (_, PineType::Tuple(elems)) => {
+                let mut insts = expr.gen(context);
+                for i in 0..elems.len() {
+                    let elem = context.temp();
+                    let offset = pvm::Operand::Constant(pvm::to_u64!(i * 8));
+                    let addr_inst = wrap(pvm::AddInst::new(elem.clone(), expr.dest(), offset));
+                    let load_inst = wrap(pvm::LoadInst::new(elem.clone(), elem.clone()));
+                    let pushr_inst = wrap(pvm::PushrInst::new(elem));
+                    insts = concat!(insts, addr_inst, load_inst, pushr_inst);
+                }
+
+                insts
+            }
+            (_, PineType::Void) => expr.gen(context),
+            _ => {
+                let e_insts = expr.gen(context);
+                let pushr_inst = wrap(pvm::PushrInst::new(expr.dest()));
+                concat!(e_insts, pushr_inst)
+            }
+        };
     }
+
+    let ret_inst = wrap(pvm::RetInst::new());
+    concat!(insts, ret_inst)
 }
 
 impl AstCodeGen for ast::ExprStmt {
@@ -213,10 +781,20 @@ impl AstCodeGen for ast::Stmt {
     fn gen(&self, context: &mut Context) -> InstVec {
         match self {
             ast::Stmt::Let(s) => s.gen(context),
+            ast::Stmt::LetTuple(s) => s.gen(context),
             ast::Stmt::Set(s) => s.gen(context),
             ast::Stmt::If(s) => s.gen(context),
             ast::Stmt::While(s) => s.gen(context),
+            ast::Stmt::For(s) => s.gen(context),
             ast::Stmt::Return(s) => s.gen(context),
+            ast::Stmt::Break(_) => {
+                let end = context.loops.last().expect("codegen bug").end.clone();
+                vec![wrap(pvm::JumpInst::new(end))]
+            }
+            ast::Stmt::Continue(_) => {
+                let next = context.loops.last().expect("codegen bug").next.clone();
+                vec![wrap(pvm::JumpInst::new(next))]
+            }
             ast::Stmt::Expr(s) => s.gen(context),
             ast::Stmt::Block(s) => s.gen(context),
         }
@@ -225,7 +803,8 @@ impl AstCodeGen for ast::Stmt {
 
 impl AstCodeGen for ast::IntLitExpr {
     fn gen(&self, _context: &mut Context) -> InstVec {
-        let src = pvm::Operand::Constant(pvm::to_u64!(self.value));
+        // the value of the literal fits in a word, either as an int or as a uint
+        let src = pvm::Operand::Constant(self.value as u64);
         let move_inst = wrap(pvm::MoveInst::new(self.dest.clone(), src));
         vec![move_inst]
     }
@@ -239,6 +818,14 @@ impl AstCodeGen for ast::FloatLitExpr {
     }
 }
 
+impl AstCodeGen for ast::CharLitExpr {
+    fn gen(&self, _context: &mut Context) -> InstVec {
+        let src = pvm::Operand::Constant(self.value as u64);
+        let move_inst = wrap(pvm::MoveInst::new(self.dest.clone(), src));
+        vec![move_inst]
+    }
+}
+
 impl AstCodeGen for ast::BoolLitExpr {
     fn gen(&self, _context: &mut Context) -> InstVec {
         let src = pvm::Operand::Constant(pvm::to_u64!(self.value as u8));
@@ -249,53 +836,636 @@ impl AstCodeGen for ast::BoolLitExpr {
 
 impl AstCodeGen for ast::StringLitExpr {
     fn gen(&self, context: &mut Context) -> InstVec {
-        todo!()
+        gen_string(&self.value, self.dest.clone(), context)
+    }
+}
+
+/// Generates the allocation of a string, storing its address in a destination.
+fn gen_string(value: &str, dest: pvm::Operand, context: &mut Context) -> InstVec {
+    let bytes = value.as_bytes();
+    let size = pvm::Operand::Constant(pvm::to_u64!(bytes.len() + 8));
+    let len = pvm::Operand::Constant(pvm::to_u64!(bytes.len()));
+    let alloc_inst = wrap(pvm::AllocInst::new(dest.clone(), size));
+    let store_inst = wrap(pvm::StoreInst::new(dest.clone(), len));
+    let mut insts = concat!(alloc_inst, store_inst);
+
+    let addr = context.temp();
+    for (i, b) in bytes.iter().enumerate() {
+        let offset = pvm::Operand::Constant(pvm::to_u64!(i + 8));
+        let add_inst = wrap(pvm::AddInst::new(addr.clone(), dest.clone(), offset));
+        let storeb_inst = wrap(pvm::StoreByteInst::new(addr.clone(), pvm::Operand::Constant(pvm::to_u64!(*b))));
+        insts = concat!(insts, add_inst, storeb_inst);
+    }
+
+    insts
+}
+
+impl AstCodeGen for ast::InterpStringExpr {
+    /// The parts are concatenated from left to right into a new string.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let mut insts = Vec::new();
+        for (i, p) in self.parts.iter().enumerate() {
+            let p_insts = p.gen(context);
+            insts = concat!(insts, p_insts);
+            if i == 0 {
+                let move_inst = wrap(pvm::MoveInst::new(self.dest.clone(), p.dest()));
+                insts = concat!(insts, move_inst);
+            } else {
+                let concat_insts = gen_string_op(&self.dest, Operator::Add, self.dest.clone(), p.dest(), context);
+                insts = concat!(insts, concat_insts);
+            }
+        }
+
+        insts
+    }
+}
+
+impl AstCodeGen for ast::ListLitExpr {
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let mut insts = Vec::new();
+        for e in &self.elems {
+            let e_insts = e.gen(context);
+            insts = concat!(insts, e_insts);
+        }
+
+        // the data block is never empty, so that pushing to an empty list can grow it
+        let len = self.elems.len();
+        let cap = len.max(LIST_MIN_CAPACITY);
+        let data = context.temp();
+        let addr = context.temp();
+        let alloc_inst = wrap(pvm::AllocInst::new(self.dest.clone(), pvm::Operand::Constant(16)));
+        let len_inst = wrap(pvm::StoreInst::new(self.dest.clone(), pvm::Operand::Constant(pvm::to_u64!(len))));
+        let alloc_data_inst = wrap(pvm::AllocInst::new(data.clone(), pvm::Operand::Constant(pvm::to_u64!(cap * 8))));
+        let data_addr_inst = wrap(pvm::AddInst::new(addr.clone(), self.dest.clone(), pvm::Operand::Constant(8)));
+        let data_inst = wrap(pvm::StoreInst::new(addr.clone(), data.clone()));
+        insts = concat!(insts, alloc_inst, len_inst, alloc_data_inst, data_addr_inst, data_inst);
+
+        for (i, e) in self.elems.iter().enumerate() {
+            let offset = pvm::Operand::Constant(pvm::to_u64!(i * 8));
+            let elem_addr_inst = wrap(pvm::AddInst::new(addr.clone(), data.clone(), offset));
+            let store_inst = wrap(pvm::StoreInst::new(addr.clone(), e.dest()));
+            insts = concat!(insts, elem_addr_inst, store_inst);
+        }
+
+        insts
+    }
+}
+
+impl AstCodeGen for ast::MapLitExpr {
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let mut insts = gen_map_new(self.dest.clone(), &self.ty.substitute(&context.subst), context);
+        for (k, v) in &self.entries {
+            let k_insts = k.gen(context);
+            let v_insts = v.gen(context);
+            let i_insts = gen_map_insert(self.dest.clone(), k.dest(), v.dest(), context);
+            insts = concat!(insts, k_insts, v_insts, i_insts);
+        }
+
+        insts
+    }
+}
+
+impl AstCodeGen for ast::SetLitExpr {
+    /// Generates a map whose values are unused.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let mut insts = gen_map_new(self.dest.clone(), &self.ty.substitute(&context.subst), context);
+        for e in &self.elems {
+            let e_insts = e.gen(context);
+            let i_insts = gen_map_insert(self.dest.clone(), e.dest(), pvm::Operand::Constant(0), context);
+            insts = concat!(insts, e_insts, i_insts);
+        }
+
+        insts
+    }
+}
+
+/// Generates an empty map of a map or set type, which hashes and compares its keys as strings if they are strings.
+fn gen_map_new(dest: pvm::Operand, ty: &PineType, context: &mut Context) -> InstVec {
+    let str_keys = match ty {
+        PineType::Map(k, _) | PineType::Set(k) => **k == PineType::String,
+        _ => panic!("codegen bug"),
+    };
+
+    let pusha_inst = wrap(pvm::PushaInst::new(pvm::Operand::Constant(pvm::to_u64!(str_keys as u8))));
+    let call_inst = wrap(pvm::CallInst::new(context.runtime(Runtime::MapNew)));
+    let popr_inst = wrap(pvm::PoprInst::new(dest));
+    concat!(pusha_inst, call_inst, popr_inst)
+}
+
+/// Generates the insertion of a key and value into a map.
+fn gen_map_insert(map: pvm::Operand, key: pvm::Operand, value: pvm::Operand, context: &mut Context) -> InstVec {
+    let pusha_m_inst = wrap(pvm::PushaInst::new(map));
+    let pusha_k_inst = wrap(pvm::PushaInst::new(key));
+    let pusha_v_inst = wrap(pvm::PushaInst::new(value));
+    let call_inst = wrap(pvm::CallInst::new(context.runtime(Runtime::MapInsert)));
+    concat!(pusha_m_inst, pusha_k_inst, pusha_v_inst, call_inst)
+}
+
+impl AstCodeGen for ast::TupleLitExpr {
+    /// Generates a block holding one word per element.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let mut insts = Vec::new();
+        for e in &self.elems {
+            let e_insts = e.gen(context);
+            insts = concat!(insts, e_insts);
+        }
+
+        let elems = self.elems.iter().map(|e| e.dest()).collect();
+        let t_insts = gen_tuple(self.dest.clone(), elems, context);
+        concat!(insts, t_insts)
+    }
+}
+
+/// Generates a tuple block from the values of its elements.
+fn gen_tuple(dest: pvm::Operand, elems: Vec<pvm::Operand>, context: &mut Context) -> InstVec {
+    let addr = context.temp();
+    let size = pvm::Operand::Constant(pvm::to_u64!(elems.len() * 8));
+    let mut insts = vec![wrap(pvm::AllocInst::new(dest.clone(), size))];
+    for (i, e) in elems.into_iter().enumerate() {
+        let offset = pvm::Operand::Constant(pvm::to_u64!(i * 8));
+        let elem_addr_inst = wrap(pvm::AddInst::new(addr.clone(), dest.clone(), offset));
+        let store_inst = wrap(pvm::StoreInst::new(addr.clone(), e));
+        insts = concat!(insts, elem_addr_inst, store_inst);
+    }
+
+    insts
+}
+
+impl AstCodeGen for ast::IndexExpr {
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let addr = context.temp();
+        let addr_insts = gen_index_addr(self, addr.clone(), context);
+        let load_inst = wrap(pvm::LoadInst::new(self.dest.clone(), addr));
+        concat!(addr_insts, load_inst)
+    }
+}
+
+impl AstCodeGen for ast::FieldExpr {
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let addr = context.temp();
+        let addr_insts = gen_field_addr(self, addr.clone(), context);
+        let load_inst = wrap(pvm::LoadInst::new(self.dest.clone(), addr));
+        concat!(addr_insts, load_inst)
     }
 }
 
+impl AstCodeGen for ast::CastExpr {
+    /// A value that is cast to an optional is boxed, so that an optional is a pointer that is zero for none.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let e_insts = self.expr.gen(context);
+        if self.target.is_some() {
+            let conv_insts = gen_conversion(&self.dest, self.expr.dest(), &context.ty(&self.expr).resolve(), &self.ty.resolve());
+            return concat!(e_insts, conv_insts);
+        }
+
+        let impl_def = match (self.ty.resolve(), self.expr.ty()) {
+            (PineType::Interface(iface), PineType::Object(obj, _)) => iface.borrow().impl_for(&obj).expect("codegen bug"),
+            (PineType::Optional(_), _) => {
+                context.optionals = true;
+                let alloc_inst = wrap(pvm::AllocInst::new(self.dest.clone(), pvm::Operand::Constant(8)));
+                let store_inst = wrap(pvm::StoreInst::new(self.dest.clone(), self.expr.dest()));
+                return concat!(e_insts, alloc_inst, store_inst);
+            }
+            _ => panic!("codegen bug")
+        };
+
+        let vtable = pvm::Operand::Variable(ast::IfaceDef::vtable(&impl_def));
+        let addr = context.temp();
+        let alloc_inst = wrap(pvm::AllocInst::new(self.dest.clone(), pvm::Operand::Constant(16)));
+        let obj_inst = wrap(pvm::StoreInst::new(self.dest.clone(), self.expr.dest()));
+        let vtable_addr_inst = wrap(pvm::AddInst::new(addr.clone(), self.dest.clone(), pvm::Operand::Constant(8)));
+        let vtable_inst = wrap(pvm::StoreInst::new(addr, vtable));
+        concat!(e_insts, alloc_inst, obj_inst, vtable_addr_inst, vtable_inst)
+    }
+}
+
+/// Generates the conversion of a number or character to another type with an explicit cast.
+/// Integers keep their bits when they are cast to other integers, except that they are truncated to a byte.
+fn gen_conversion(dest: &pvm::Operand, src: pvm::Operand, from: &PineType, to: &PineType) -> InstVec {
+    let conv_inst = match (from, to) {
+        (PineType::Integer, PineType::Float) => wrap(pvm::ItofInst::new(dest.clone(), src)),
+        (_, PineType::Float) if *from != PineType::Float => wrap(pvm::UtofInst::new(dest.clone(), src)),
+        (PineType::Float, PineType::Integer) => wrap(pvm::FtoiInst::new(dest.clone(), src)),
+        (PineType::Float, PineType::UInteger) => wrap(pvm::FtouInst::new(dest.clone(), src)),
+        (PineType::Float, PineType::Byte) => {
+            let ftoi_inst = wrap(pvm::FtoiInst::new(dest.clone(), src));
+            let trunc_inst = wrap(pvm::TruncbInst::new(dest.clone(), dest.clone()));
+            return concat!(ftoi_inst, trunc_inst);
+        }
+        (PineType::Integer | PineType::UInteger, PineType::Byte | PineType::Char) => wrap(pvm::TruncbInst::new(dest.clone(), src)),
+        _ => wrap(pvm::MoveInst::new(dest.clone(), src)),
+    };
+    vec![conv_inst]
+}
+
 impl AstCodeGen for ast::IdentExpr {
+    /// Generates a closure for a function that is used as a value, which captures no variables,
+    /// and loads the value of an optional that has been checked out of its box.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        if self.narrowed {
+            let load_inst = wrap(pvm::LoadInst::new(self.dest.clone(), self.ident.dest()));
+            return vec![load_inst];
+        }
+
+        match fun_label(self, context) {
+            Some(label) => gen_closure(self.dest.clone(), label, vec![], context),
+            None => vec![],
+        }
+    }
+}
+
+impl AstCodeGen for ast::NoneLitExpr {
     fn gen(&self, _context: &mut Context) -> InstVec {
-        vec![]
+        let move_inst = wrap(pvm::MoveInst::new(self.dest.clone(), pvm::Operand::Constant(0)));
+        vec![move_inst]
+    }
+}
+
+impl AstCodeGen for ast::IsExpr {
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let e_insts = self.expr.gen(context);
+        let zero = pvm::Operand::Constant(0);
+        let check_inst = if self.some {
+            wrap(pvm::NeqInst::new(self.dest.clone(), self.expr.dest(), zero))
+        } else {
+            wrap(pvm::EqInst::new(self.dest.clone(), self.expr.dest(), zero))
+        };
+        concat!(e_insts, check_inst)
+    }
+}
+
+impl AstCodeGen for ast::TryExpr {
+    /// Returns an `Err` result as it is, since an error is held the same way whatever the type of the value,
+    /// and otherwise loads the value of the `Ok` result.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let e_insts = self.expr.gen(context);
+        let ok_label = pvm::Operand::Label(format!("{}_ok", context.label_gen.try_prefix()));
+        let tag = context.temp();
+        let tag_inst = wrap(pvm::LoadInst::new(tag.clone(), self.expr.dest()));
+        let ok_jump_inst = wrap(pvm::JumpZeroInst::new(tag, ok_label.clone()));
+        let pushr_inst = wrap(pvm::PushrInst::new(self.expr.dest()));
+        let ret_inst = wrap(pvm::RetInst::new());
+        let ok_label_inst = wrap(pvm::LabelInst::new(ok_label));
+        let addr = context.temp();
+        let addr_inst = wrap(pvm::AddInst::new(addr.clone(), self.expr.dest(), pvm::Operand::Constant(8)));
+        let load_inst = wrap(pvm::LoadInst::new(self.dest.clone(), addr));
+        concat!(e_insts, tag_inst, ok_jump_inst, pushr_inst, ret_inst, ok_label_inst, addr_inst, load_inst)
+    }
+}
+
+impl AstCodeGen for ast::LambdaExpr {
+    /// Generates the lambda as a function, which is emitted after the other functions, and a closure for it.
+    /// The lambda loads the variables it captures from the closure it is called through,
+    /// into the same variables they are held in outside of the lambda.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let label = pvm::Operand::Label(context.label_gen.lambda_prefix());
+        let captures: Vec<pvm::Operand> = self.symbol.borrow().captures.iter().map(|c| c.borrow().dest.clone()).collect();
+        let closure = context.temp();
+        let fun_inst = wrap(pvm::FunInst::new(label.clone()));
+        let closure_inst = wrap(pvm::MoveInst::new(closure.clone(), closure_var()));
+        let mut insts = concat!(fun_inst, closure_inst);
+        for (i, c) in captures.iter().enumerate() {
+            let offset = pvm::Operand::Constant(pvm::to_u64!((i + 1) * 8));
+            let addr_inst = wrap(pvm::AddInst::new(c.clone(), closure.clone(), offset));
+            let load_inst = wrap(pvm::LoadInst::new(c.clone(), c.clone()));
+            insts = concat!(insts, addr_inst, load_inst);
+        }
+
+        for p in &self.params {
+            let p_insts = p.gen(context);
+            insts = concat!(insts, p_insts);
+        }
+
+        let b_insts = match &self.body {
+            ast::LambdaBody::Expr(e) => gen_return(Some(e), context),
+            ast::LambdaBody::Block(b) => b.gen(context),
+        };
+        insts = concat!(insts, b_insts);
+        context.lambdas = concat!(std::mem::take(&mut context.lambdas), insts);
+
+        gen_closure(self.dest.clone(), label, captures, context)
+    }
+}
+
+/// Gets the variable that holds the closure being called, which lambdas load their captured variables from.
+fn closure_var() -> pvm::Operand {
+    pvm::Operand::Variable("closure".to_string())
+}
+
+/// Generates a closure, which is a block holding the address of a function followed by the values of the variables it captures.
+fn gen_closure(dest: pvm::Operand, label: pvm::Operand, captures: Vec<pvm::Operand>, context: &mut Context) -> InstVec {
+    context.closures = true;
+    let addr = context.temp();
+    let size = (captures.len() + 1) * 8;
+    let alloc_inst = wrap(pvm::AllocInst::new(dest.clone(), pvm::Operand::Constant(pvm::to_u64!(size))));
+    let addr_inst = wrap(pvm::FunAddrInst::new(addr.clone(), label));
+    let store_inst = wrap(pvm::StoreInst::new(dest.clone(), addr.clone()));
+    let mut insts = concat!(alloc_inst, addr_inst, store_inst);
+    for (i, c) in captures.into_iter().enumerate() {
+        let offset = pvm::Operand::Constant(pvm::to_u64!((i + 1) * 8));
+        let slot_inst = wrap(pvm::AddInst::new(addr.clone(), dest.clone(), offset));
+        let store_inst = wrap(pvm::StoreInst::new(addr.clone(), c));
+        insts = concat!(insts, slot_inst, store_inst);
     }
+
+    insts
 }
 
 impl AstCodeGen for ast::CallExpr {
     fn gen(&self, context: &mut Context) -> InstVec {
-        let mut insts = Vec::new();
-        let f_insts = self.fun.gen(context);
+        if let Some(builtin) = self.builtin() {
+            return gen_builtin_call(self, builtin, context);
+        }
+
+        let (insts, values) = gen_call(self, context);
+        match self.ty.substitute(&context.subst) {
+            PineType::Tuple(_) => {
+                let t_insts = gen_tuple(self.dest.clone(), values, context);
+                concat!(insts, t_insts)
+            }
+            _ => insts,
+        }
+    }
+}
+
+/// Generates a call, popping the return value into the destination of the call.
+/// A tuple is returned one element at a time, so its elements are popped into temps instead, which are returned.
+/// A call to a value that is not a function, object or variant by name is made indirectly through a closure.
+fn gen_call(expr: &ast::CallExpr, context: &mut Context) -> (InstVec, Vec<pvm::Operand>) {
+    let callee = match expr.fun.as_ref() {
+        Expr::Ident(ident) => fun_label(ident, context),
+        _ => None,
+    };
+
+    let mut insts = Vec::new();
+    if callee.is_none() {
+        let f_insts = expr.fun.gen(context);
         insts = concat!(insts, f_insts);
-        let mut push_insts = Vec::new();
-        for a in &self.args {
-            let a_insts = a.gen(context);
-            let pusha_inst = wrap(pvm::PushaInst::new(a.dest()));
-            push_insts.push(pusha_inst);
-            insts = concat!(insts, a_insts);
+    }
+
+    let mut push_insts = Vec::new();
+    for a in &expr.args {
+        let a_insts = a.gen(context);
+        let pusha_inst = wrap(pvm::PushaInst::new(a.dest()));
+        push_insts.push(pusha_inst);
+        insts = concat!(insts, a_insts);
+    }
+
+    insts = match callee {
+        Some(label) => {
+            let call_inst = wrap(pvm::CallInst::new(label));
+            concat!(insts, push_insts, call_inst)
+        }
+        None => {
+            // the closure is passed after the arguments are evaluated, since they may call other closures
+            let addr = context.temp();
+            let addr_inst = wrap(pvm::LoadInst::new(addr.clone(), expr.fun.dest()));
+            let closure_inst = wrap(pvm::MoveInst::new(closure_var(), expr.fun.dest()));
+            let call_inst = wrap(pvm::CallIndirectInst::new(addr));
+            concat!(insts, addr_inst, closure_inst, push_insts, call_inst)
         }
+    };
 
-        let call_inst = wrap(pvm::CallInst::new(self.fun.dest())); // TODO will this work for lambdas?
-        insts = concat!(insts, push_insts, call_inst);
+    let values = match context.ty(&expr.fun) {
+        PineType::Function { ret, ..} => match *ret {
+            PineType::Void => vec![],
+            PineType::Tuple(elems) => elems.iter().map(|_| context.temp()).collect(),
+            _ => vec![expr.dest.clone()],
+        },
+        _ => panic!("codegen bug")
+    };
 
-        match self.fun.ty() {
-            PineType::Function { ret, ..} => {
-                if *ret != PineType::Void {
-                    let popr_inst = wrap(pvm::PoprInst::new(self.dest.clone()));
-                    insts = concat!(insts, popr_inst);
-                }
+    for v in &values {
+        let popr_inst = wrap(pvm::PoprInst::new(v.clone()));
+        insts = concat!(insts, popr_inst);
+    }
+
+    (insts, values)
+}
+
+/// Gets the label of the function, object constructor or variant constructor an identifier refers to, if any.
+/// The label of a generic function is that of its instance for the type the identifier is used with.
+/// Constructors of generic objects and enums do not depend on the type arguments, so they are generated once.
+fn fun_label(ident: &ast::IdentExpr, context: &mut Context) -> Option<pvm::Operand> {
+    let symbol = &ident.ident.symbol;
+    let (dest, generic, variant) = {
+        let s = symbol.borrow();
+        (s.dest.clone(), s.obj.is_none() && s.variant.is_none() && !s.pine_type.generics().is_empty(), s.variant.clone())
+    };
+
+    // the variants of the builtin Result are constructed by runtime routines
+    if let Some((_, tag)) = variant.filter(|(e, _)| e.borrow().is_builtin()) {
+        let routine = if tag == 0 { Runtime::ResultOk } else { Runtime::ResultErr };
+        return Some(context.runtime(routine));
+    }
+
+    match dest {
+        pvm::Operand::Label(_) if generic => {
+            let ty = ident.ty.resolve_all().substitute(&context.subst);
+            Some(context.instance(symbol, &ty))
+        }
+        pvm::Operand::Label(_) => Some(context.fun(symbol)),
+        _ => None,
+    }
+}
+
+/// Generates a call to a builtin, which is lowered in place rather than called.
+fn gen_builtin_call(expr: &ast::CallExpr, builtin: Builtin, context: &mut Context) -> InstVec {
+    match builtin {
+        Builtin::Print | Builtin::Println => {
+            let mut insts = Vec::new();
+            for a in &expr.args {
+                let a_insts = a.gen(context);
+                let print_insts = gen_print(a, context);
+                insts = concat!(insts, a_insts, print_insts);
             }
-            _ => panic!("codegen bug")
+
+            if builtin == Builtin::Println {
+                let println_inst = wrap(pvm::PrintlnInst::new());
+                insts = concat!(insts, println_inst);
+            }
+
+            insts
+        }
+        Builtin::ToString => gen_to_string(expr, context),
+        Builtin::LoadChar => {
+            // the bytes of a string follow its length word
+            let (s, i) = (&expr.args[0], &expr.args[1]);
+            let s_insts = s.gen(context);
+            let i_insts = i.gen(context);
+            let (len, addr) = (context.temp(), context.temp());
+            let len_inst = wrap(pvm::LoadInst::new(len.clone(), s.dest()));
+            let bounds_inst = wrap(pvm::BoundsInst::new(i.dest(), len));
+            let base_inst = wrap(pvm::AddInst::new(addr.clone(), s.dest(), pvm::Operand::Constant(8)));
+            let index_inst = wrap(pvm::AddInst::new(addr.clone(), addr.clone(), i.dest()));
+            let load_inst = wrap(pvm::LoadByteInst::new(expr.dest.clone(), addr));
+            concat!(s_insts, i_insts, len_inst, bounds_inst, base_inst, index_inst, load_inst)
+        }
+        Builtin::Halt => {
+            let a = &expr.args[0];
+            let a_insts = a.gen(context);
+            let exit_inst = wrap(pvm::ExitInst::new(a.dest()));
+            concat!(a_insts, exit_inst)
+        }
+        Builtin::Len => {
+            // lists, strings, maps and sets all hold their length in the first word
+            let a = &expr.args[0];
+            let a_insts = a.gen(context);
+            let load_inst = wrap(pvm::LoadInst::new(expr.dest.clone(), a.dest()));
+            concat!(a_insts, load_inst)
+        }
+        Builtin::Push => {
+            let (l, v) = (&expr.args[0], &expr.args[1]);
+            let l_insts = l.gen(context);
+            let v_insts = v.gen(context);
+            let pusha_l_inst = wrap(pvm::PushaInst::new(l.dest()));
+            let pusha_v_inst = wrap(pvm::PushaInst::new(v.dest()));
+            let call_inst = wrap(pvm::CallInst::new(context.runtime(Runtime::ListPush)));
+            concat!(l_insts, v_insts, pusha_l_inst, pusha_v_inst, call_inst)
         }
+        Builtin::Insert => {
+            let mut insts = Vec::new();
+            for a in &expr.args {
+                let a_insts = a.gen(context);
+                insts = concat!(insts, a_insts);
+            }
 
-        insts
+            // the values of a set are unused
+            let value = match expr.args.get(2) {
+                Some(v) => v.dest(),
+                None => pvm::Operand::Constant(0),
+            };
+            let i_insts = gen_map_insert(expr.args[0].dest(), expr.args[1].dest(), value, context);
+            concat!(insts, i_insts)
+        }
+        Builtin::Get | Builtin::Remove | Builtin::Contains => {
+            let routine = match builtin {
+                Builtin::Get => Runtime::MapGet,
+                Builtin::Remove => Runtime::MapRemove,
+                _ => Runtime::MapContains,
+            };
+            let (m, k) = (&expr.args[0], &expr.args[1]);
+            let m_insts = m.gen(context);
+            let k_insts = k.gen(context);
+            let pusha_m_inst = wrap(pvm::PushaInst::new(m.dest()));
+            let pusha_k_inst = wrap(pvm::PushaInst::new(k.dest()));
+            let call_inst = wrap(pvm::CallInst::new(context.runtime(routine)));
+            let mut insts = concat!(m_insts, k_insts, pusha_m_inst, pusha_k_inst, call_inst);
+            if builtin != Builtin::Remove {
+                let popr_inst = wrap(pvm::PoprInst::new(expr.dest.clone()));
+                insts = concat!(insts, popr_inst);
+            }
+
+            insts
+        }
+    }
+}
+
+/// Generates the instructions that convert the value of an expression to a string.
+fn gen_to_string(expr: &ast::CallExpr, context: &mut Context) -> InstVec {
+    let a = &expr.args[0];
+    let insts = a.gen(context);
+    match expr.args.get(1) {
+        Some(p) => {
+            let p_insts = p.gen(context);
+            let conv_inst = wrap(pvm::FtospInst::new(expr.dest.clone(), a.dest(), p.dest()));
+            concat!(insts, p_insts, conv_inst)
+        }
+        None => {
+            let conv_insts = gen_value_to_string(&context.ty(a), expr.dest.clone(), a.dest(), context);
+            concat!(insts, conv_insts)
+        }
+    }
+}
+
+/// Generates the instructions that convert a value of a type to a string.
+/// Objects are converted by calling their `to_string` method, and lists, sets, maps and optionals
+/// by calling the generic functions of the core module that convert their elements.
+fn gen_value_to_string(ty: &PineType, dest: pvm::Operand, src: pvm::Operand, context: &mut Context) -> InstVec {
+    let routine = match ty {
+        PineType::Integer => return vec![wrap(pvm::ItosInst::new(dest, src))],
+        PineType::UInteger | PineType::Byte => return vec![wrap(pvm::UtosInst::new(dest, src))],
+        PineType::Float => return vec![wrap(pvm::FtosInst::new(dest, src))],
+        PineType::String => return vec![wrap(pvm::MoveInst::new(dest, src))],
+        PineType::Tuple(elems) => return gen_tuple_to_string(elems, dest, src, context),
+        PineType::Char => context.runtime(Runtime::CharToStr),
+        PineType::Bool => context.runtime(Runtime::BoolToStr),
+        PineType::Object(..) => context.method(ty, "to_string"),
+        PineType::List(_) | PineType::Set(_) | PineType::Map(..) | PineType::Optional(_) => {
+            let name = match ty {
+                PineType::List(_) => "list_to_string",
+                PineType::Set(_) => "set_to_string",
+                PineType::Map(..) => "map_to_string",
+                _ => "optional_to_string",
+            };
+            let fun_type = PineType::Function { params: vec![ty.clone()], ret: Box::new(PineType::String) };
+            context.core_instance(name, &fun_type)
+        }
+        _ => panic!("codegen bug")
+    };
+
+    let pusha_inst = wrap(pvm::PushaInst::new(src));
+    let call_inst = wrap(pvm::CallInst::new(routine));
+    let popr_inst = wrap(pvm::PoprInst::new(dest));
+    concat!(pusha_inst, call_inst, popr_inst)
+}
+
+/// Generates the conversion of a tuple to a string, which converts its elements and separates them in parentheses.
+fn gen_tuple_to_string(elems: &[PineType], dest: pvm::Operand, src: pvm::Operand, context: &mut Context) -> InstVec {
+    let addr = context.temp();
+    let elem = context.temp();
+    let part = context.temp();
+    let mut insts = gen_string("(", dest.clone(), context);
+    for (i, ty) in elems.iter().enumerate() {
+        if i > 0 {
+            let sep_insts = gen_string(", ", part.clone(), context);
+            let concat_insts = gen_string_op(&dest, Operator::Add, dest.clone(), part.clone(), context);
+            insts = concat!(insts, sep_insts, concat_insts);
+        }
+
+        let offset = pvm::Operand::Constant(pvm::to_u64!(i * 8));
+        let addr_inst = wrap(pvm::AddInst::new(addr.clone(), src.clone(), offset));
+        let load_inst = wrap(pvm::LoadInst::new(elem.clone(), addr.clone()));
+        let conv_insts = gen_value_to_string(ty, part.clone(), elem.clone(), context);
+        let concat_insts = gen_string_op(&dest, Operator::Add, dest.clone(), part.clone(), context);
+        insts = concat!(insts, addr_inst, load_inst, conv_insts, concat_insts);
     }
+
+    let close_insts = gen_string(")", part.clone(), context);
+    let concat_insts = gen_string_op(&dest, Operator::Add, dest.clone(), part, context);
+    concat!(insts, close_insts, concat_insts)
+}
+
+/// Generates the instructions that print the value of an expression.
+fn gen_print(expr: &ast::Expr, context: &mut Context) -> InstVec {
+    let routine = match context.ty(expr) {
+        PineType::Integer => return vec![wrap(pvm::PrintiInst::new(expr.dest()))],
+        PineType::UInteger | PineType::Byte => return vec![wrap(pvm::PrintuInst::new(expr.dest()))],
+        PineType::Char => return vec![wrap(pvm::PrintcInst::new(expr.dest()))],
+        PineType::Float => return vec![wrap(pvm::PrintfInst::new(expr.dest()))],
+        PineType::Bool => Runtime::PrintBool,
+        PineType::String => Runtime::PrintStr,
+        _ => panic!("codegen bug")
+    };
+
+    let pusha_inst = wrap(pvm::PushaInst::new(expr.dest()));
+    let call_inst = wrap(pvm::CallInst::new(context.runtime(routine)));
+    concat!(pusha_inst, call_inst)
 }
 
 impl AstCodeGen for ast::UnaryExpr {
     fn gen(&self, context: &mut Context) -> InstVec {
         let e_insts = self.expr.gen(context);
+        let ty = context.ty(&self.expr);
+        if let PineType::Object(..) = ty {
+            let call_insts = gen_overload(&self.dest, self.op, &ty, vec![self.expr.dest()], false, context);
+            return concat!(e_insts, call_insts);
+        }
+
         let op_inst = match self.op {
             Operator::Not => wrap(pvm::SubuInst::new(self.dest.clone(), pvm::Operand::Constant(1), self.expr.dest())),
             Operator::Subtract => {
-                match self.expr.ty() {
+                match context.ty(&self.expr) {
                     PineType::Integer => wrap(pvm::NegInst::new(self.dest.clone(), self.expr.dest())),
                     PineType::Float => wrap(pvm::NegfInst::new(self.dest.clone(), self.expr.dest())),
                     _ => panic!("codegen bug")
@@ -309,98 +1479,315 @@ impl AstCodeGen for ast::UnaryExpr {
 
 impl AstCodeGen for ast::BinaryExpr {
     fn gen(&self, context: &mut Context) -> InstVec {
+        if matches!(self.op, Operator::And | Operator::Or) {
+            return gen_short_circuit(self, context);
+        }
+
         let l_insts = self.left.gen(context);
         let r_insts = self.right.gen(context);
-        let op_inst = match self.op {
-            Operator::Equals => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::EqInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::EqfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
-                }
-            },
-            Operator::NotEquals => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::NeqInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::NeqfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
-                }
-            },
-            Operator::GreaterThan => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::GtInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::GtfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
-                }
-            },
-            Operator::LessThan => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::LtInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::LtfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
-                }
-            },
-            Operator::GreaterThanOrEqual => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::GteInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::GtfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
-                }
-            },
-            Operator::LessThanOrEqual => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::LteInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::LtefInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
-                }
-            },
-            Operator::And => wrap(pvm::AndInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-            Operator::Or => wrap(pvm::OrInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-            Operator::Add => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::AddInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::AddfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
-                }
-            },
-            Operator::Subtract => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::SubInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::SubfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
-                }
-            },
-            Operator::Multiply => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::MulInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::MulfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
-                }
-            },
-            Operator::Divide => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::DivInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::DivfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
-                }
-            },
-            Operator::Power => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::PowInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::PowfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
+        let ty = context.ty(&self.left);
+        let op_insts = gen_binary(&self.dest, self.op, &ty, self.left.dest(), self.right.dest(), context);
+        concat!(l_insts, r_insts, op_insts)
+    }
+}
+
+/// Generates `and` or `or`, which only evaluates the right operand if the left operand does not decide the result.
+fn gen_short_circuit(expr: &ast::BinaryExpr, context: &mut Context) -> InstVec {
+    let lab_prefix = match expr.op {
+        Operator::And => context.label_gen.and_prefix(),
+        _ => context.label_gen.or_prefix(),
+    };
+    let end_label = pvm::Operand::Label(format!("{}_end", lab_prefix));
+
+    let l_insts = expr.left.gen(context);
+    let l_move_inst = wrap(pvm::MoveInst::new(expr.dest.clone(), expr.left.dest()));
+    let jump_inst = match expr.op {
+        Operator::And => wrap(pvm::JumpZeroInst::new(expr.dest.clone(), end_label.clone())),
+        _ => wrap(pvm::JumpNotZeroInst::new(expr.dest.clone(), end_label.clone())),
+    };
+    let r_insts = expr.right.gen(context);
+    let r_move_inst = wrap(pvm::MoveInst::new(expr.dest.clone(), expr.right.dest()));
+    let end_label_inst = wrap(pvm::LabelInst::new(end_label));
+    concat!(l_insts, l_move_inst, jump_inst, r_insts, r_move_inst, end_label_inst)
+}
+
+/// Generates a binary operation on operands of a type, storing the result in a destination.
+fn gen_binary(dest: &pvm::Operand, op: Operator, ty: &PineType, l: pvm::Operand, r: pvm::Operand, context: &mut Context) -> InstVec {
+    if let PineType::Object(..) = ty {
+        let (operands, negate) = match op {
+            Operator::GreaterThan => (vec![r, l], false),
+            Operator::LessThanOrEqual => (vec![r, l], true),
+            Operator::NotEquals | Operator::GreaterThanOrEqual => (vec![l, r], true),
+            _ => (vec![l, r], false),
+        };
+        return gen_overload(dest, op, ty, operands, negate, context);
+    }
+
+    if *ty == PineType::String {
+        return gen_string_op(dest, op, l, r, context);
+    }
+
+    // unsigned integers, bytes and characters use the unsigned instruction family
+    let unsigned = matches!(ty, PineType::UInteger | PineType::Byte | PineType::Char);
+    let op_inst = match op {
+        Operator::Equals => {
+            match ty {
+                PineType::Integer | PineType::Bool => wrap(pvm::EqInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::EqfInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::EquInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::NotEquals => {
+            match ty {
+                PineType::Integer | PineType::Bool => wrap(pvm::NeqInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::NeqfInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::NequInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::GreaterThan => {
+            match ty {
+                PineType::Integer => wrap(pvm::GtInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::GtfInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::GtuInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::LessThan => {
+            match ty {
+                PineType::Integer => wrap(pvm::LtInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::LtfInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::LtuInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::GreaterThanOrEqual => {
+            match ty {
+                PineType::Integer => wrap(pvm::GteInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::GtefInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::GteuInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::LessThanOrEqual => {
+            match ty {
+                PineType::Integer => wrap(pvm::LteInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::LtefInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::LteuInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::Add => {
+            match ty {
+                PineType::Integer => wrap(pvm::AddInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::AddfInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::AdduInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::Subtract => {
+            match ty {
+                PineType::Integer => wrap(pvm::SubInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::SubfInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::SubuInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::Multiply => {
+            match ty {
+                PineType::Integer => wrap(pvm::MulInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::MulfInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::MuluInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::Divide => {
+            match ty {
+                PineType::Integer => wrap(pvm::DivInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::DivfInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::DivuInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::Power => {
+            match ty {
+                PineType::Integer => wrap(pvm::PowInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::PowfInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::PowuInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        Operator::Modulo => {
+            match ty {
+                PineType::Integer => wrap(pvm::ModInst::new(dest.clone(), l, r)),
+                PineType::Float => wrap(pvm::ModfInst::new(dest.clone(), l, r)),
+                _ if unsigned => wrap(pvm::ModuInst::new(dest.clone(), l, r)),
+                _ => panic!("codegen bug")
+            }
+        },
+        _ => panic!("codegen bug")
+    };
+
+    // arithmetic on bytes wraps around
+    if *ty == PineType::Byte && matches!(op, Operator::Add | Operator::Subtract | Operator::Multiply | Operator::Power) {
+        let trunc_inst = wrap(pvm::TruncbInst::new(dest.clone(), dest.clone()));
+        return concat!(op_inst, trunc_inst);
+    }
+
+    vec![op_inst]
+}
+
+/// Generates an operator on an object as a call to the method that overloads it.
+/// The result of the method is negated for the operators that are the negation of `eq` or `lt`.
+fn gen_overload(dest: &pvm::Operand, op: Operator, ty: &PineType, operands: Vec<pvm::Operand>, negate: bool, context: &mut Context) -> InstVec {
+    let method = match operands.len() {
+        1 => op.unary_method(),
+        _ => op.binary_method(),
+    };
+    let label = match method {
+        Some(m) => context.method(ty, m),
+        None => panic!("codegen bug")
+    };
+
+    let mut insts = Vec::new();
+    for o in operands {
+        let pusha_inst = wrap(pvm::PushaInst::new(o));
+        insts = concat!(insts, pusha_inst);
+    }
+
+    let call_inst = wrap(pvm::CallInst::new(label));
+    let popr_inst = wrap(pvm::PoprInst::new(dest.clone()));
+    insts = concat!(insts, call_inst, popr_inst);
+    if negate {
+        let not_inst = wrap(pvm::SubuInst::new(dest.clone(), pvm::Operand::Constant(1), dest.clone()));
+        insts = concat!(insts, not_inst);
+    }
+
+    insts
+}
+
+/// Generates a binary operation on strings as a call to a runtime routine.
+/// Comparisons compare the result of `rt.str_cmp` against zero.
+fn gen_string_op(dest: &pvm::Operand, op: Operator, l: pvm::Operand, r: pvm::Operand, context: &mut Context) -> InstVec {
+    let dest = dest.clone();
+    let routine = match op {
+        Operator::Add => Runtime::StrConcat,
+        _ => Runtime::StrCmp,
+    };
+
+    let pusha_l_inst = wrap(pvm::PushaInst::new(l));
+    let pusha_r_inst = wrap(pvm::PushaInst::new(r));
+    let call_inst = wrap(pvm::CallInst::new(context.runtime(routine)));
+    let popr_inst = wrap(pvm::PoprInst::new(dest.clone()));
+    let insts = concat!(pusha_l_inst, pusha_r_inst, call_inst, popr_inst);
+
+    let zero = pvm::Operand::Constant(0);
+    let cmp_inst = match op {
+        Operator::Add => return insts,
+        Operator::Equals => wrap(pvm::EqInst::new(dest.clone(), dest, zero)),
+        Operator::NotEquals => wrap(pvm::NeqInst::new(dest.clone(), dest, zero)),
+        Operator::GreaterThan => wrap(pvm::GtInst::new(dest.clone(), dest, zero)),
+        Operator::LessThan => wrap(pvm::LtInst::new(dest.clone(), dest, zero)),
+        Operator::GreaterThanOrEqual => wrap(pvm::GteInst::new(dest.clone(), dest, zero)),
+        Operator::LessThanOrEqual => wrap(pvm::LteInst::new(dest.clone(), dest, zero)),
+        _ => panic!("codegen bug")
+    };
+    concat!(insts, cmp_inst)
+}
+
+impl AstCodeGen for ast::MatchExpr {
+    /// Generates the arms of the match in order. Each arm jumps to the next arm if its pattern does not match.
+    fn gen(&self, context: &mut Context) -> InstVec {
+        let lab_prefix = context.label_gen.match_prefix();
+        let end_label = pvm::Operand::Label(format!("{}_end", lab_prefix));
+        let value = context.temp();
+        let e_insts = self.expr.gen(context);
+        let value_inst = wrap(pvm::MoveInst::new(value.clone(), self.expr.dest()));
+        let mut insts = concat!(e_insts, value_inst);
+        for (i, a) in self.arms.iter().enumerate() {
+            let next_label = pvm::Operand::Label(format!("{}_arm{}", lab_prefix, i + 1));
+            let p_insts = gen_pattern(&a.pattern, value.clone(), &next_label, context);
+            let b_insts = match &a.body {
+                ast::ArmBody::Block(b) => b.gen(context),
+                ast::ArmBody::Expr(e) => {
+                    let e_insts = e.gen(context);
+                    if self.is_stmt || context.ty(e) == PineType::Void {
+                        e_insts
+                    } else {
+                        let move_inst = wrap(pvm::MoveInst::new(self.dest.clone(), e.dest()));
+                        concat!(e_insts, move_inst)
+                    }
                 }
-            },
-            Operator::Modulo => {
-                match self.left.ty() {
-                    PineType::Integer => wrap(pvm::ModInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    PineType::Float => wrap(pvm::ModfInst::new(self.dest.clone(), self.left.dest(), self.right.dest())),
-                    _ => panic!("codegen bug")
+            };
+
+            let jump_end_inst = wrap(pvm::JumpInst::new(end_label.clone()));
+            let next_label_inst = wrap(pvm::LabelInst::new(next_label));
+            insts = concat!(insts, p_insts, b_insts, jump_end_inst, next_label_inst);
+        }
+
+        let end_label_inst = wrap(pvm::LabelInst::new(end_label));
+        concat!(insts, end_label_inst)
+    }
+}
+
+/// Generates the instructions that jump to a label if a pattern does not match a value,
+/// and that bind the variables of the pattern otherwise.
+fn gen_pattern(pattern: &ast::Pattern, value: pvm::Operand, fail: &pvm::Operand, context: &mut Context) -> InstVec {
+    match pattern {
+        ast::Pattern::Wildcard(_) => vec![],
+        ast::Pattern::Binding(p) => vec![wrap(pvm::MoveInst::new(p.ident.dest(), value))],
+        ast::Pattern::Lit(p) => {
+            let l_insts = p.expr.gen(context);
+            let cond = context.temp();
+            let cmp_insts = match context.ty(&p.expr) {
+                PineType::String => {
+                    let pusha_v_inst = wrap(pvm::PushaInst::new(value));
+                    let pusha_l_inst = wrap(pvm::PushaInst::new(p.expr.dest()));
+                    let call_inst = wrap(pvm::CallInst::new(context.runtime(Runtime::StrCmp)));
+                    let popr_inst = wrap(pvm::PoprInst::new(cond.clone()));
+                    let eq_inst = wrap(pvm::EqInst::new(cond.clone(), cond.clone(), pvm::Operand::Constant(0)));
+                    concat!(pusha_v_inst, pusha_l_inst, call_inst, popr_inst, eq_inst)
                 }
-            },
-            _ => panic!("codegen bug")
-        };
-        concat!(l_insts, r_insts, op_inst)
+                PineType::Float => vec![wrap(pvm::EqfInst::new(cond.clone(), value, p.expr.dest()))],
+                _ => vec![wrap(pvm::EqInst::new(cond.clone(), value, p.expr.dest()))],
+            };
+            let jump_inst = wrap(pvm::JumpZeroInst::new(cond, fail.clone()));
+            concat!(l_insts, cmp_insts, jump_inst)
+        }
+        ast::Pattern::Variant(p) => {
+            let tag = p.variant().1;
+            let cond = context.temp();
+            let tag_inst = wrap(pvm::LoadInst::new(cond.clone(), value.clone()));
+            let eq_inst = wrap(pvm::EqInst::new(cond.clone(), cond.clone(), pvm::Operand::Constant(pvm::to_u64!(tag))));
+            let jump_inst = wrap(pvm::JumpZeroInst::new(cond, fail.clone()));
+            let mut insts = concat!(tag_inst, eq_inst, jump_inst);
+
+            // the parts of the payload follow the tag
+            for (i, a) in p.args.iter().enumerate() {
+                let part = context.temp();
+                let offset = pvm::Operand::Constant(pvm::to_u64!((i + 1) * 8));
+                let part_addr_inst = wrap(pvm::AddInst::new(part.clone(), value.clone(), offset));
+                let part_inst = wrap(pvm::LoadInst::new(part.clone(), part.clone()));
+                let a_insts = gen_pattern(a, part, fail, context);
+                insts = concat!(insts, part_addr_inst, part_inst, a_insts);
+            }
+
+            insts
+        }
+        ast::Pattern::Tuple(p) => {
+            let mut insts = Vec::new();
+            for (i, e) in p.elems.iter().enumerate() {
+                let elem = context.temp();
+                let offset = pvm::Operand::Constant(pvm::to_u64!(i * 8));
+                let elem_addr_inst = wrap(pvm::AddInst::new(elem.clone(), value.clone(), offset));
+                let elem_inst = wrap(pvm::LoadInst::new(elem.clone(), elem.clone()));
+                let e_insts = gen_pattern(e, elem, fail, context);
+                insts = concat!(insts, elem_addr_inst, elem_inst, e_insts);
+            }
+
+            insts
+        }
     }
 }
 
@@ -409,12 +1796,27 @@ impl AstCodeGen for ast::Expr {
         match self {
             Expr::IntLit(e) => e.gen(context),
             Expr::FloatLit(e) => e.gen(context),
+            Expr::CharLit(e) => e.gen(context),
             Expr::BoolLit(e) => e.gen(context),
             Expr::StringLit(e) => e.gen(context),
+            Expr::InterpString(e) => e.gen(context),
             Expr::Ident(e) => e.gen(context),
             Expr::Call(e) => e.gen(context),
             Expr::Unary(e) => e.gen(context),
             Expr::Binary(e) => e.gen(context),
+            Expr::Range(_) => panic!("codegen bug"), // ranges are only generated by for loops
+            Expr::ListLit(e) => e.gen(context),
+            Expr::MapLit(e) => e.gen(context),
+            Expr::SetLit(e) => e.gen(context),
+            Expr::TupleLit(e) => e.gen(context),
+            Expr::Index(e) => e.gen(context),
+            Expr::Field(e) => e.gen(context),
+            Expr::Cast(e) => e.gen(context),
+            Expr::NoneLit(e) => e.gen(context),
+            Expr::Is(e) => e.gen(context),
+            Expr::Try(e) => e.gen(context),
+            Expr::Match(e) => e.gen(context),
+            Expr::Lambda(e) => e.gen(context),
         }
     }
 }
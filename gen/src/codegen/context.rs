@@ -1,3 +1,9 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use ast::{Expr, PineType, SymbolRef};
+use crate::codegen::InstVec;
+use crate::runtime::Runtime;
+use crate::temp::TempStore;
+
 pub(crate) struct LabelGen {
     counter: usize,
 }
@@ -19,16 +25,261 @@ impl LabelGen {
     pub(crate) fn while_prefix(&mut self) -> String {
         self.label(format!("while{}", self.counter))
     }
+
+    pub(crate) fn for_prefix(&mut self) -> String {
+        self.label(format!("for{}", self.counter))
+    }
+
+    pub(crate) fn match_prefix(&mut self) -> String {
+        self.label(format!("match{}", self.counter))
+    }
+
+    pub(crate) fn let_prefix(&mut self) -> String {
+        self.label(format!("let{}", self.counter))
+    }
+
+    pub(crate) fn lambda_prefix(&mut self) -> String {
+        self.label(format!("lambda{}", self.counter))
+    }
+
+    pub(crate) fn and_prefix(&mut self) -> String {
+        self.label(format!("and{}", self.counter))
+    }
+
+    pub(crate) fn or_prefix(&mut self) -> String {
+        self.label(format!("or{}", self.counter))
+    }
+
+    pub(crate) fn try_prefix(&mut self) -> String {
+        self.label(format!("try{}", self.counter))
+    }
+}
+
+/// An instance of a generic function, which is generated once per set of type arguments,
+/// or a function of the core module, which is generated once it is used.
+pub(crate) struct Instance {
+    pub symbol: SymbolRef,
+    pub subst: HashMap<usize, PineType>,
+    pub label: pvm::Operand,
+}
+
+/// The labels of a loop that break and continue statements jump to.
+pub(crate) struct Loop {
+    /// The label of the next iteration.
+    pub next: pvm::Operand,
+    /// The label after the loop.
+    pub end: pvm::Operand,
 }
 
 pub(crate) struct Context {
-    pub label_gen: LabelGen
+    pub label_gen: LabelGen,
+    pub temp_store: TempStore,
+    pub runtime: BTreeSet<Runtime>,
+    /// The type arguments of the generic function instance being generated, by the id of the type parameter.
+    pub subst: HashMap<usize, PineType>,
+    /// The instances of generic functions and the functions of the core module that have been used but not generated.
+    pub instances: Vec<Instance>,
+    instance_labels: HashSet<String>,
+    /// The functions generated for lambdas, which are emitted after the other functions.
+    pub lambdas: InstVec,
+    /// The loops enclosing the code being generated, innermost last.
+    pub loops: Vec<Loop>,
+    /// Whether the program creates closures, in which case the variable that passes closures to lambdas is made global.
+    pub closures: bool,
+    /// Whether the program boxes optionals, in which case the block at address zero is reserved, since zero is none.
+    pub optionals: bool,
+    /// The functions of the core module by name, which codegen calls to convert collections to strings.
+    pub core_funs: HashMap<String, SymbolRef>,
 }
 
 impl Context {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(temp_store: TempStore) -> Self {
         Self {
-            label_gen: LabelGen::new()
+            label_gen: LabelGen::new(),
+            temp_store,
+            runtime: BTreeSet::new(),
+            subst: HashMap::new(),
+            instances: Vec::new(),
+            instance_labels: HashSet::new(),
+            lambdas: Vec::new(),
+            loops: Vec::new(),
+            closures: false,
+            optionals: false,
+            core_funs: HashMap::new(),
+        }
+    }
+
+    /// Gets the type of an expression within the generic function instance being generated.
+    pub(crate) fn ty(&self, expr: &Expr) -> PineType {
+        expr.ty().substitute(&self.subst)
+    }
+
+    /// Gets the label of the instance of a generic function for the type it is called with,
+    /// queueing the instance to be generated if it has not been already.
+    pub(crate) fn instance(&mut self, symbol: &SymbolRef, ty: &PineType) -> pvm::Operand {
+        let (scheme, dest) = {
+            let symbol = symbol.borrow();
+            (symbol.pine_type.clone(), symbol.dest.clone())
+        };
+
+        let mut subst = HashMap::new();
+        bind_generics(&scheme, ty, &mut subst);
+        let args: Vec<String> = scheme.generics().iter()
+            .map(|id| subst.get(id).map(mangle).unwrap_or_else(|| "_".to_string()))
+            .collect();
+        let label = match dest {
+            pvm::Operand::Label(l) => format!("{}${}", l, args.join("$")),
+            _ => panic!("codegen bug")
+        };
+
+        if self.instance_labels.insert(label.clone()) {
+            self.instances.push(Instance {
+                symbol: symbol.clone(),
+                subst,
+                label: pvm::Operand::Label(label.clone()),
+            });
+        }
+
+        pvm::Operand::Label(label)
+    }
+
+    /// Gets the label of the instance of a generic function of the core module for the type it is called with.
+    pub(crate) fn core_instance(&mut self, name: &str, ty: &PineType) -> pvm::Operand {
+        let symbol = self.core_funs.get(name).cloned().expect("codegen bug");
+        self.instance(&symbol, ty)
+    }
+
+    /// Gets the label of a function, queueing it to be generated if it is a function, method or constructor of the core module
+    /// that has not been already, so that only the parts of the core module that are used are linked into the program.
+    pub(crate) fn fun(&mut self, symbol: &SymbolRef) -> pvm::Operand {
+        let (dest, is_core_fun) = {
+            let s = symbol.borrow();
+            (s.dest.clone(), s.module.as_deref() == Some(ast::CORE_MODULE))
+        };
+
+        match &dest {
+            pvm::Operand::Label(l) if is_core_fun && self.instance_labels.insert(l.clone()) => {
+                self.instances.push(Instance {
+                    symbol: symbol.clone(),
+                    subst: HashMap::new(),
+                    label: dest.clone(),
+                });
+            }
+            _ => {}
+        }
+
+        dest
+    }
+
+    /// Gets the label of a method of an object type, which for a generic object
+    /// is that of its instance for the type arguments of the object.
+    pub(crate) fn method(&mut self, ty: &PineType, name: &str) -> pvm::Operand {
+        let symbol = match ty {
+            PineType::Object(o, _) => o.borrow().method(name).expect("codegen bug"),
+            _ => panic!("codegen bug")
+        };
+
+        let scheme = symbol.borrow().pine_type.clone();
+        match &scheme {
+            PineType::Function { params, .. } if !scheme.generics().is_empty() => {
+                let mut subst = HashMap::new();
+                bind_generics(&params[0], ty, &mut subst);
+                self.instance(&symbol, &scheme.substitute(&subst))
+            }
+            _ => self.fun(&symbol),
+        }
+    }
+
+    /// Gets a new temp for intermediate values.
+    pub(crate) fn temp(&mut self) -> pvm::Operand {
+        pvm::Operand::Variable(self.temp_store.temp())
+    }
+
+    /// Gets the label of a runtime routine, marking it and the routines it calls to be linked into the program.
+    pub(crate) fn runtime(&mut self, routine: Runtime) -> pvm::Operand {
+        if self.runtime.insert(routine) {
+            for d in routine.deps() {
+                self.runtime(d);
+            }
         }
+
+        routine.label()
     }
-}
\ No newline at end of file
+}
+/// Binds the type parameters within a generic type to the corresponding parts of a concrete type.
+fn bind_generics(generic: &PineType, ty: &PineType, subst: &mut HashMap<usize, PineType>) {
+    match (generic.resolve(), ty.resolve()) {
+        (PineType::Generic(id, _), t) => {
+            subst.insert(id, t);
+        }
+        (PineType::List(g), PineType::List(t)) | (PineType::Set(g), PineType::Set(t)) | (PineType::Optional(g), PineType::Optional(t)) => bind_generics(&g, &t, subst),
+        (PineType::Map(gk, gv), PineType::Map(tk, tv)) => {
+            bind_generics(&gk, &tk, subst);
+            bind_generics(&gv, &tv, subst);
+        }
+        (PineType::Object(_, gs), PineType::Object(_, ts))
+        | (PineType::Enum(_, gs), PineType::Enum(_, ts))
+        | (PineType::Tuple(gs), PineType::Tuple(ts)) => {
+            for (g, t) in gs.iter().zip(&ts) {
+                bind_generics(g, t, subst);
+            }
+        }
+        (PineType::Function { params: gp, ret: gr }, PineType::Function { params: tp, ret: tr }) => {
+            for (g, t) in gp.iter().zip(&tp) {
+                bind_generics(g, t, subst);
+            }
+
+            bind_generics(&gr, &tr, subst);
+        }
+        _ => {}
+    }
+}
+
+/// Gets the part of an instance label that names a type argument.
+fn mangle(ty: &PineType) -> String {
+    match ty.resolve() {
+        PineType::Integer => "int".to_string(),
+        PineType::UInteger => "uint".to_string(),
+        PineType::Byte => "byte".to_string(),
+        PineType::Char => "char".to_string(),
+        PineType::Float => "float".to_string(),
+        PineType::Bool => "bool".to_string(),
+        PineType::String => "string".to_string(),
+        PineType::List(t) => format!("list.{}", mangle(&t)),
+        PineType::Map(k, v) => format!("map.{}.{}", mangle(&k), mangle(&v)),
+        PineType::Set(t) => format!("set.{}", mangle(&t)),
+        PineType::Optional(t) => format!("opt.{}", mangle(&t)),
+        PineType::Object(o, args) => {
+            let o = o.borrow();
+            let mut name = format!("{}::{}", o.module, o.name);
+            for a in &args {
+                name = format!("{}.{}", name, mangle(a));
+            }
+
+            name
+        }
+        PineType::Enum(e, args) => {
+            let e = e.borrow();
+            let mut name = format!("{}::{}", e.module, e.name);
+            for a in &args {
+                name = format!("{}.{}", name, mangle(a));
+            }
+
+            name
+        }
+        PineType::Tuple(elems) => {
+            let elems: Vec<String> = elems.iter().map(mangle).collect();
+            format!("tuple.{}.{}", elems.len(), elems.join("."))
+        }
+        PineType::Interface(i) => {
+            let i = i.borrow();
+            format!("{}::{}", i.module, i.name)
+        }
+        PineType::Function { params, ret } => {
+            let params: Vec<String> = params.iter().map(mangle).collect();
+            format!("fun.{}.{}.{}", params.len(), params.join("."), mangle(&ret))
+        }
+        PineType::Void => "void".to_string(),
+        _ => "_".to_string(),
+    }
+}
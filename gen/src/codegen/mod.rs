@@ -4,5 +4,5 @@ mod context;
 
 pub(crate) use codegen::codegen;
 
-type Inst = Box<dyn pvm::Instruction>;
-type InstVec = Vec<Inst>;
+pub(crate) type Inst = Box<dyn pvm::Instruction>;
+pub(crate) type InstVec = Vec<Inst>;
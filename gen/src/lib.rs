@@ -2,8 +2,9 @@ mod codegen;
 mod error;
 mod temp;
 mod assign;
+mod runtime;
 
 pub fn codegen(program: &mut ast::Program) -> Vec<Box<dyn pvm::Instruction>> {
-    assign::assign(program);
-    codegen::codegen(program)
+    let temp_store = assign::assign(program);
+    codegen::codegen(program, temp_store)
 }
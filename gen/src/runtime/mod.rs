@@ -0,0 +1,94 @@
+use crate::codegen::InstVec;
+
+/// A runtime routine written in PVM assembly.
+/// Routines are only linked into a program when codegen uses them.
+///
+/// Strings are heap blocks laid out as a length word followed by the bytes of the string.
+/// Lists are header blocks holding a length word and the address of a data block,
+/// which holds one word per element and grows by doubling.
+/// Maps are header blocks holding a length word, the capacity, the address of a data block
+/// and whether the keys are strings. The data block holds a slot of three words per entry:
+/// whether the slot is empty, full or removed, the key, and the value. Sets are maps whose values are unused.
+/// Results are enum values of the builtin `Result` enum, whose variants are constructed by routines.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Runtime {
+    StrConcat,
+    StrCmp,
+    PrintStr,
+    PrintBool,
+    BoolToStr,
+    CharToStr,
+    ListPush,
+    MapData,
+    MapNew,
+    MapFind,
+    MapInsert,
+    MapGet,
+    MapRemove,
+    MapContains,
+    ResultOk,
+    ResultErr,
+}
+
+impl Runtime {
+    /// Gets the label of the routine.
+    pub(crate) fn label(&self) -> pvm::Operand {
+        let name = match self {
+            Runtime::StrConcat => "rt.str_concat",
+            Runtime::StrCmp => "rt.str_cmp",
+            Runtime::PrintStr => "rt.print_str",
+            Runtime::PrintBool => "rt.print_bool",
+            Runtime::BoolToStr => "rt.bool_to_str",
+            Runtime::CharToStr => "rt.char_to_str",
+            Runtime::ListPush => "rt.list_push",
+            Runtime::MapData => "rt.map_data",
+            Runtime::MapNew => "rt.map_new",
+            Runtime::MapFind => "rt.map_find",
+            Runtime::MapInsert => "rt.map_insert",
+            Runtime::MapGet => "rt.map_get",
+            Runtime::MapRemove => "rt.map_remove",
+            Runtime::MapContains => "rt.map_contains",
+            Runtime::ResultOk => "rt.result_ok",
+            Runtime::ResultErr => "rt.result_err",
+        };
+        pvm::Operand::Label(name.to_string())
+    }
+
+    /// Gets the PVM assembly source of the routine.
+    fn source(&self) -> &'static str {
+        match self {
+            Runtime::StrConcat => include_str!("str_concat.pvm"),
+            Runtime::StrCmp => include_str!("str_cmp.pvm"),
+            Runtime::PrintStr => include_str!("print_str.pvm"),
+            Runtime::PrintBool => include_str!("print_bool.pvm"),
+            Runtime::BoolToStr => include_str!("bool_to_str.pvm"),
+            Runtime::CharToStr => include_str!("char_to_str.pvm"),
+            Runtime::ListPush => include_str!("list_push.pvm"),
+            Runtime::MapData => include_str!("map_data.pvm"),
+            Runtime::MapNew => include_str!("map_new.pvm"),
+            Runtime::MapFind => include_str!("map_find.pvm"),
+            Runtime::MapInsert => include_str!("map_insert.pvm"),
+            Runtime::MapGet => include_str!("map_get.pvm"),
+            Runtime::MapRemove => include_str!("map_remove.pvm"),
+            Runtime::MapContains => include_str!("map_contains.pvm"),
+            Runtime::ResultOk => include_str!("result_ok.pvm"),
+            Runtime::ResultErr => include_str!("result_err.pvm"),
+        }
+    }
+
+    /// Gets the routines that the routine calls.
+    pub(crate) fn deps(&self) -> Vec<Runtime> {
+        match self {
+            Runtime::MapNew => vec![Runtime::MapData],
+            Runtime::MapFind => vec![Runtime::StrCmp],
+            Runtime::MapInsert => vec![Runtime::MapData, Runtime::MapFind],
+            Runtime::MapGet | Runtime::MapRemove | Runtime::MapContains => vec![Runtime::MapFind],
+            _ => vec![],
+        }
+    }
+
+    /// Parses the routine into PVM instructions.
+    pub(crate) fn insts(&self) -> InstVec {
+        pvm::parse(self.source()).expect("runtime bug")
+    }
+}
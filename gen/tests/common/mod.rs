@@ -0,0 +1,50 @@
+use std::cell::RefCell;
+use std::io::Read;
+use std::rc::Rc;
+use pvm::{execute_with_config, Error, ExecuteConfig};
+use test_util::TestFileContent;
+
+const MEMORY: usize = 1024 * 1024;
+
+/// Executes a program and compares its output, or its runtime error, against the references of the test.
+/// Every program that compiles must have an expected output or runtime error.
+pub(crate) fn run(instructions: Vec<Box<dyn pvm::Instruction>>, test_content: TestFileContent) {
+    if test_content.pvm_ref_content.is_none() && test_content.runtime_err_ref_content.is_none() {
+        panic!("the test has neither a .pvm.ref nor a .rterr file");
+    }
+
+    let stdin: Rc<RefCell<dyn Read>> = if let Some(test_input_file) = test_content.test_input_file {
+        Rc::new(RefCell::new(test_input_file))
+    } else {
+        Rc::new(RefCell::new(std::io::stdin()))
+    };
+
+    let buffer: Rc<RefCell<Vec<u8>>> = Rc::new(RefCell::new(Vec::new()));
+    let config = ExecuteConfig::new(MEMORY, stdin, buffer.clone());
+    let result = execute_with_config(instructions, config);
+    if let Some(err_ref) = test_content.runtime_err_ref_content {
+        match result {
+            Err(Error::Wrapped(e, _)) => assert_eq!(err_ref.trim(), format!("{}", e)),
+            _ => panic!("expected the program to fail at runtime"),
+        }
+    } else if !matches!(result, Err(Error::Exit(_))) {
+        result.unwrap();
+    }
+
+    let pvm_ref_content = match test_content.pvm_ref_content {
+        Some(content) => content,
+        None => return,
+    };
+    let actual: String = buffer
+        .borrow()
+        .iter()
+        .map(|b| *b as char)
+        .collect();
+    equal(pvm_ref_content, actual);
+}
+
+fn equal(expected: String, actual: String) {
+    let expected = expected.lines().collect::<Vec<_>>();
+    let actual = actual.lines().collect::<Vec<_>>();
+    assert_eq!(expected, actual);
+}
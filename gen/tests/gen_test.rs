@@ -0,0 +1,38 @@
+mod common;
+
+use std::path::PathBuf;
+use test_util::{generate_single_file_tests, test_file_content};
+
+generate_single_file_tests!(gen);
+
+fn test(mut test_base_path: PathBuf) {
+    let pine_path = test_base_path.with_extension("p");
+    let test_content = test_file_content(&mut test_base_path);
+    let mut program = match ast::parse(pine_path) {
+        Ok(program) => program,
+        Err(_) if test_content.err_ref_content.is_some() => return, // compile errors are tested by ast
+        Err(e) => panic!("{}", e),
+    };
+    let instructions = gen::codegen(&mut program);
+
+    common::run(instructions, test_content);
+}
+
+#[test]
+fn test_core_unused_funs() {
+    // only the functions of the core module that are used, and the functions they call, are linked into the program
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../resources/tests/single_file/core/math/test.p");
+    let mut program = ast::parse(path).unwrap();
+    let funs: Vec<String> = gen::codegen(&mut program)
+        .iter()
+        .map(|i| format!("{}", i))
+        .filter(|i| i.starts_with("fun core::"))
+        .collect();
+
+    assert!(funs.contains(&"fun core::abs".to_string()));
+    assert!(funs.contains(&"fun core::max$int".to_string()));
+    assert!(funs.contains(&"fun core::min$string".to_string()));
+    assert!(!funs.iter().any(|f| f.starts_with("fun core::max$string") || f == "fun core::to_upper"));
+    assert!(!funs.iter().any(|f| f.starts_with("fun core::Stack")));
+}
@@ -0,0 +1,18 @@
+mod common;
+
+use std::path::PathBuf;
+use test_util::{generate_module_tests, test_file_content};
+
+generate_module_tests!(gen);
+
+fn test(mut test_base_path: PathBuf) {
+    let pine_path = test_base_path.with_extension("p");
+    let test_content = test_file_content(&mut test_base_path);
+    let mut program = match ast::parse(pine_path) {
+        Ok(program) => program,
+        Err(_) if test_content.err_ref_content.is_some() => return, // compile errors are tested by ast
+        Err(e) => panic!("{}", e),
+    };
+    let instructions = gen::codegen(&mut program);
+    common::run(instructions, test_content);
+}
@@ -0,0 +1,115 @@
+use pvm_proc_macros::Inst;
+use crate::env::Environment;
+use crate::inst::*;
+use crate::cast::*;
+use crate::parse::{Line, Literal, Parse, Token};
+
+extern crate pvm_proc_macros;
+use pvm_proc_macros::*;
+
+/// Checks that an index is within the bounds of a collection of the given length.
+/// Negative indices are out of bounds.
+#[inst(name = "bounds", operands = [OperandFormat::Value, OperandFormat::Value])]
+pub struct BoundsInst {
+    pub(crate) index: Operand,
+    pub(crate) len: Operand,
+}
+
+impl Instruction for BoundsInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let index = from_u64!(self.index.value(env)?; i64);
+        let len = from_u64!(self.len.value(env)?; i64);
+        if index < 0 || index >= len {
+            Err(ExecuteError::index_out_of_bounds(index, len))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Checks that a key was found in a map.
+#[inst(name = "haskey", operands = [OperandFormat::Value])]
+pub struct HasKeyInst {
+    pub(crate) found: Operand,
+}
+
+impl Instruction for HasKeyInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        if self.found.value(env)? == 0 {
+            Err(ExecuteError::key_not_found())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic]
+    fn test_bounds_validation() {
+        let i = Operand::Label("test".to_string());
+        let l = Operand::Constant(2);
+        let inst = BoundsInst::new(i, l);
+        inst.validate().unwrap();
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut context = Environment::default();
+
+        let vals: Vec<(i64, i64)> = vec![(0, 1), (1, 2), (9, 10)];
+        for (i, (index, len)) in vals.iter().enumerate() {
+            let idx = Operand::Constant(to_u64!(*index));
+            let l = Operand::Constant(to_u64!(*len));
+            let mut inst = BoundsInst::new(idx, l);
+            inst.execute(&mut context).unwrap();
+            inst.inc_inst_ptr(&mut context).unwrap();
+            assert_eq!(context.inst_ptr, i + 1);
+        }
+    }
+
+    #[test]
+    fn test_bounds_out_of_bounds() {
+        let mut context = Environment::default();
+
+        let vals: Vec<(i64, i64)> = vec![(0, 0), (2, 2), (10, 2), (-1, 2)];
+        for (index, len) in vals {
+            let idx = Operand::Constant(to_u64!(index));
+            let l = Operand::Constant(to_u64!(len));
+            let mut inst = BoundsInst::new(idx, l);
+            let err = inst.execute(&mut context).unwrap_err();
+            let msg = format!("{}", err);
+            assert_eq!(msg, format!("Runtime Error: index {} is out of bounds for length {}", index, len));
+        }
+    }
+
+    #[test]
+    fn test_bounds_display() {
+        let i = Operand::Variable(String::from("i"));
+        let l = Operand::Variable(String::from("n"));
+        let inst = BoundsInst::new(i, l);
+        let display = format!("{}", inst);
+        assert_eq!(display, "bounds i n");
+    }
+
+    #[test]
+    fn test_haskey() {
+        let mut context = Environment::default();
+        let mut inst = HasKeyInst::new(Operand::Constant(1));
+        inst.execute(&mut context).unwrap();
+
+        let mut inst = HasKeyInst::new(Operand::Constant(0));
+        let err = inst.execute(&mut context).unwrap_err();
+        assert_eq!(format!("{}", err), "Runtime Error: key is not in the map");
+    }
+
+    #[test]
+    fn test_haskey_display() {
+        let inst = HasKeyInst::new(Operand::Variable(String::from("found")));
+        let display = format!("{}", inst);
+        assert_eq!(display, "haskey found");
+    }
+}
@@ -97,6 +97,22 @@ impl ExecuteError {
     pub(crate) fn local_var_not_saved(name: &str) -> Error {
         Self::error(format!("local variable {} not saved", name))
     }
+
+    pub(crate) fn index_out_of_bounds(index: i64, len: i64) -> Error {
+        Self::error(format!("index {} is out of bounds for length {}", index, len))
+    }
+
+    pub(crate) fn key_not_found() -> Error {
+        Self::error("key is not in the map")
+    }
+
+    pub(crate) fn fun_addr_does_not_exist(addr: u64) -> Error {
+        Self::error(format!("{} is not the address of a function", addr))
+    }
+
+    pub(crate) fn precision_out_of_range(precision: i64, max: i64) -> Error {
+        Self::error(format!("precision {} is not between 0 and {}", precision, max))
+    }
 }
 
 impl Display for ExecuteError {
@@ -120,6 +120,50 @@ impl Instruction for CallInst {
     }
 }
 
+/// Gets the address of a function, so that it can be called indirectly.
+#[inst(name = "funaddr", operands = [OperandFormat::Variable, OperandFormat::Label])]
+pub struct FunAddrInst {
+    pub(crate) dest: Operand,
+    pub(crate) lab: Operand,
+}
+
+impl Instruction for FunAddrInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let label = self.lab.label()?;
+        let addr = match env.fun_labels.get(&label) {
+            Some(addr) => *addr,
+            None => return Err(ExecuteError::label_does_not_exist(&label)),
+        };
+
+        self.dest.set_value(addr as u64, env)?;
+        Ok(())
+    }
+}
+
+/// Calls the function at an address computed at runtime.
+#[inst(name = "calli", operands = [OperandFormat::Value])]
+pub struct CallIndirectInst {
+    pub(crate) src: Operand,
+}
+
+impl Instruction for CallIndirectInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        env.ret_queue.clear();
+        env.ret_addr_stack.push(env.inst_ptr + 1);
+        Ok(())
+    }
+
+    fn inc_inst_ptr(&self, env: &mut Environment) -> Result<(), Error> {
+        let addr = self.src.value(env)?;
+        if env.fun_labels.values().any(|a| *a as u64 == addr) {
+            env.inst_ptr = addr as usize;
+            Ok(())
+        } else {
+            Err(ExecuteError::fun_addr_does_not_exist(addr))
+        }
+    }
+}
+
 /// Returns from a function to the call point.
 #[inst(name = "ret", operands = [])]
 pub struct RetInst {
@@ -211,6 +255,46 @@ mod tests {
         assert_eq!(context.inst_ptr, 0);
     }
 
+    #[test]
+    fn test_funaddr() {
+        let mut context = Environment::default();
+        let inst = FunInst::new(Operand::Label("test".to_string()));
+        inst.initialize(&mut context, 3).unwrap();
+
+        let mut inst = FunAddrInst::new(Operand::Variable("f".to_string()), Operand::Label("test".to_string()));
+        inst.execute(&mut context).unwrap();
+        let val = inst.dest.value(&context).unwrap();
+        assert_eq!(val, 3);
+    }
+
+    #[test]
+    fn test_calli() {
+        let mut context = Environment::default();
+
+        let inst = FunInst::new(Operand::Label("test".to_string()));
+        inst.initialize(&mut context, 0).unwrap();
+        inst.inc_inst_ptr(&mut context).unwrap();
+
+        let mut inst = CallIndirectInst::new(Operand::Constant(0));
+        inst.execute(&mut context).unwrap();
+        inst.inc_inst_ptr(&mut context).unwrap();
+        assert_eq!(context.ret_addr_stack.pop(), Some(2));
+        assert_eq!(context.inst_ptr, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_calli_invalid_addr() {
+        let mut context = Environment::default();
+
+        let inst = FunInst::new(Operand::Label("test".to_string()));
+        inst.initialize(&mut context, 0).unwrap();
+
+        let mut inst = CallIndirectInst::new(Operand::Constant(5));
+        inst.execute(&mut context).unwrap();
+        inst.inc_inst_ptr(&mut context).unwrap();
+    }
+
     #[test]
     fn test_ret() {
         let mut context = Environment::default();
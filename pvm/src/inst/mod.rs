@@ -3,6 +3,7 @@ mod print;
 mod jump;
 mod label;
 mod un_op;
+mod string;
 mod alloc;
 mod load;
 mod store;
@@ -11,10 +12,12 @@ mod fun;
 mod operand;
 mod exit;
 mod read;
+mod check;
 
 pub use operand::*;
 pub use bin_op::*;
 pub use un_op::*;
+pub use string::*;
 pub use print::*;
 pub use read::*;
 pub use jump::*;
@@ -24,6 +27,7 @@ pub use load::*;
 pub use store::*;
 pub use fun::*;
 pub use exit::*;
+pub use check::*;
 pub use error::*;
 use crate::env::Environment;
 use crate::error::Error;
@@ -0,0 +1,198 @@
+use crate::env::Environment;
+use crate::inst::*;
+use crate::parse::{Line, Literal, Parse, Token};
+use crate::cast::*;
+
+extern crate pvm_proc_macros;
+use pvm_proc_macros::*;
+
+/// Allocates a string, which is a length word followed by the bytes of the string, and returns its address.
+fn alloc_string(value: &str, env: &mut Environment) -> Result<u64, Error> {
+    let bytes = value.as_bytes();
+    let addr = env.memory.allocate(bytes.len() + 8)?;
+    env.memory.store(addr, to_u64!(bytes.len()))?;
+    for (i, b) in bytes.iter().enumerate() {
+        env.memory.store_byte(addr + 8 + i, *b)?;
+    }
+
+    Ok(to_u64!(addr))
+}
+
+/// Converts a signed integer to a string.
+#[inst(name = "itos", operands = [OperandFormat::Variable, OperandFormat::Value])]
+pub struct ItosInst {
+    pub(crate) dest: Operand,
+    pub(crate) src: Operand,
+}
+
+impl Instruction for ItosInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let val = from_u64!(self.src.value(env)?; i64);
+        let addr = alloc_string(&val.to_string(), env)?;
+        self.dest.set_value(addr, env)?;
+        Ok(())
+    }
+}
+
+/// Converts an unsigned integer to a string.
+#[inst(name = "utos", operands = [OperandFormat::Variable, OperandFormat::Value])]
+pub struct UtosInst {
+    pub(crate) dest: Operand,
+    pub(crate) src: Operand,
+}
+
+impl Instruction for UtosInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let val = self.src.value(env)?;
+        let addr = alloc_string(&val.to_string(), env)?;
+        self.dest.set_value(addr, env)?;
+        Ok(())
+    }
+}
+
+/// Converts a floating point number to a string, as it is printed by `printf`.
+#[inst(name = "ftos", operands = [OperandFormat::Variable, OperandFormat::Value])]
+pub struct FtosInst {
+    pub(crate) dest: Operand,
+    pub(crate) src: Operand,
+}
+
+impl Instruction for FtosInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let val = from_u64!(self.src.value(env)?; f64);
+        let addr = alloc_string(&val.to_string(), env)?;
+        self.dest.set_value(addr, env)?;
+        Ok(())
+    }
+}
+
+/// The largest number of digits after the decimal point that `ftosp` converts a number with.
+const MAX_PRECISION: i64 = 100;
+
+/// Converts a floating point number to a string with a number of digits after the decimal point.
+#[inst(name = "ftosp", operands = [OperandFormat::Variable, OperandFormat::Value, OperandFormat::Value])]
+pub struct FtospInst {
+    pub(crate) dest: Operand,
+    pub(crate) src: Operand,
+    pub(crate) precision: Operand,
+}
+
+impl Instruction for FtospInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let val = from_u64!(self.src.value(env)?; f64);
+        let precision = from_u64!(self.precision.value(env)?; i64);
+        if !(0..=MAX_PRECISION).contains(&precision) {
+            return Err(ExecuteError::precision_out_of_range(precision, MAX_PRECISION));
+        }
+
+        let addr = alloc_string(&format!("{:.*}", precision as usize, val), env)?;
+        self.dest.set_value(addr, env)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads the string at the address held by an operand.
+    fn read_string(op: &Operand, env: &Environment) -> String {
+        let addr = from_u64!(op.value(env).unwrap(); usize);
+        let len = from_u64!(env.memory.load(addr).unwrap(); usize);
+        (0..len).map(|i| env.memory.load_byte(addr + 8 + i).unwrap() as char).collect()
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_itos_validation() {
+        let d = Operand::Constant(0);
+        let s = Operand::Constant(0);
+        let inst = ItosInst::new(d, s);
+        inst.validate().unwrap();
+    }
+
+    #[test]
+    fn test_itos_inst() {
+        let mut context = Environment::default();
+        for v in [-120i64, -1, 0, 7, 4096] {
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(to_u64!(v));
+            let mut inst = ItosInst::new(d, s);
+
+            inst.execute(&mut context).unwrap();
+            assert_eq!(read_string(&inst.dest, &context), v.to_string());
+        }
+    }
+
+    #[test]
+    fn test_itos_display() {
+        let d = Operand::Variable("x".to_string());
+        let s = Operand::Variable("y".to_string());
+        let inst = ItosInst::new(d, s);
+        let display = format!("{}", inst);
+        assert_eq!(display, "itos x y");
+    }
+
+    #[test]
+    fn test_utos_inst() {
+        let mut context = Environment::default();
+        for v in [0u64, 255, u64::MAX] {
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(v);
+            let mut inst = UtosInst::new(d, s);
+
+            inst.execute(&mut context).unwrap();
+            assert_eq!(read_string(&inst.dest, &context), v.to_string());
+        }
+    }
+
+    #[test]
+    fn test_ftos_inst() {
+        let mut context = Environment::default();
+        let vals: Vec<(f64, &str)> = vec![(-1.5, "-1.5"), (0.0, "0"), (100.14, "100.14")];
+        for (v, expected) in vals {
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(to_u64!(v));
+            let mut inst = FtosInst::new(d, s);
+
+            inst.execute(&mut context).unwrap();
+            assert_eq!(read_string(&inst.dest, &context), expected);
+        }
+    }
+
+    #[test]
+    fn test_ftosp_inst() {
+        let mut context = Environment::default();
+        let vals: Vec<(f64, u64, &str)> = vec![(1.23456, 2, "1.23"), (2.5, 0, "2"), (-1.0, 3, "-1.000")];
+        for (v, p, expected) in vals {
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(to_u64!(v));
+            let mut inst = FtospInst::new(d, s, Operand::Constant(p));
+
+            inst.execute(&mut context).unwrap();
+            assert_eq!(read_string(&inst.dest, &context), expected);
+        }
+    }
+
+    #[test]
+    fn test_ftosp_precision_out_of_range() {
+        let mut context = Environment::default();
+        for p in [-1i64, MAX_PRECISION + 1, i64::MIN] {
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(to_u64!(1.5f64));
+            let mut inst = FtospInst::new(d, s, Operand::Constant(to_u64!(p)));
+
+            let err = inst.execute(&mut context).unwrap_err();
+            assert_eq!(format!("{}", err), format!("Runtime Error: precision {} is not between 0 and 100", p));
+        }
+    }
+
+    #[test]
+    fn test_ftosp_display() {
+        let d = Operand::Variable("x".to_string());
+        let s = Operand::Variable("y".to_string());
+        let inst = FtospInst::new(d, s, Operand::Constant(2));
+        let display = format!("{}", inst);
+        assert_eq!(display, "ftosp x y 2");
+    }
+}
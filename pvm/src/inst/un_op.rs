@@ -54,6 +54,100 @@ impl Instruction for NegfInst {
     }
 }
 
+/// Converts a signed integer to a floating point number.
+#[inst(name = "itof", operands = [OperandFormat::Variable, OperandFormat::Value])]
+pub struct ItofInst {
+    pub(crate) dest: Operand,
+    pub(crate) src: Operand,
+}
+
+impl Instruction for ItofInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let val = from_u64!(self.src.value(env)?; i64);
+        let res = val as f64;
+        self.dest.set_value(to_u64!(res), env)?;
+        Ok(())
+    }
+}
+
+/// Converts an unsigned integer to a floating point number.
+#[inst(name = "utof", operands = [OperandFormat::Variable, OperandFormat::Value])]
+pub struct UtofInst {
+    pub(crate) dest: Operand,
+    pub(crate) src: Operand,
+}
+
+impl Instruction for UtofInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let val = self.src.value(env)?;
+        let res = val as f64;
+        self.dest.set_value(to_u64!(res), env)?;
+        Ok(())
+    }
+}
+
+/// Converts a floating point number to a signed integer, truncating towards zero.
+#[inst(name = "ftoi", operands = [OperandFormat::Variable, OperandFormat::Value])]
+pub struct FtoiInst {
+    pub(crate) dest: Operand,
+    pub(crate) src: Operand,
+}
+
+impl Instruction for FtoiInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let val = from_u64!(self.src.value(env)?; f64);
+        let res = val as i64;
+        self.dest.set_value(to_u64!(res), env)?;
+        Ok(())
+    }
+}
+
+/// Converts a floating point number to an unsigned integer, truncating towards zero.
+#[inst(name = "ftou", operands = [OperandFormat::Variable, OperandFormat::Value])]
+pub struct FtouInst {
+    pub(crate) dest: Operand,
+    pub(crate) src: Operand,
+}
+
+impl Instruction for FtouInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let val = from_u64!(self.src.value(env)?; f64);
+        let res = val as u64;
+        self.dest.set_value(res, env)?;
+        Ok(())
+    }
+}
+
+/// Truncates an integer to its lowest byte.
+#[inst(name = "truncb", operands = [OperandFormat::Variable, OperandFormat::Value])]
+pub struct TruncbInst {
+    pub(crate) dest: Operand,
+    pub(crate) src: Operand,
+}
+
+impl Instruction for TruncbInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let val = from_u64!(self.src.value(env)?; u8);
+        self.dest.set_value(val as u64, env)?;
+        Ok(())
+    }
+}
+
+/// Sign-extends the lowest byte of an integer.
+#[inst(name = "sextb", operands = [OperandFormat::Variable, OperandFormat::Value])]
+pub struct SextbInst {
+    pub(crate) dest: Operand,
+    pub(crate) src: Operand,
+}
+
+impl Instruction for SextbInst {
+    fn execute(&mut self, env: &mut Environment) -> Result<(), Error> {
+        let val = from_u64!(self.src.value(env)?; i8);
+        self.dest.set_value(to_u64!(val as i64), env)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{from_u64, to_u64};
@@ -186,4 +280,119 @@ mod tests {
         let expected = format!("negf x {}", to_u64!(100.14_f64));
         assert_eq!(display, expected);
     }
+
+    #[test]
+    #[should_panic]
+    fn test_itof_validation() {
+        let d = Operand::Constant(0);
+        let s = Operand::Constant(0);
+        let inst = ItofInst::new(d, s);
+        inst.validate().unwrap();
+    }
+
+    #[test]
+    fn test_itof_inst() {
+        let mut i = 0;
+        let mut context = Environment::default();
+        for v in -32i64..32 {
+            i += 1;
+
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(to_u64!(v));
+            let mut inst = ItofInst::new(d, s);
+
+            inst.execute(&mut context).unwrap();
+            inst.inc_inst_ptr(&mut context).unwrap();
+            assert_eq!(from_u64!(inst.dest.value(&context).unwrap(); f64), v as f64);
+            assert_eq!(context.inst_ptr, i);
+        }
+    }
+
+    #[test]
+    fn test_itof_display() {
+        let d = Operand::Variable("x".to_string());
+        let s = Operand::Constant(0);
+        let inst = ItofInst::new(d, s);
+        let display = format!("{}", inst);
+        assert_eq!(display, "itof x 0");
+    }
+
+    #[test]
+    fn test_utof_inst() {
+        let mut context = Environment::default();
+        let vals: Vec<u64> = vec![0, 1, 100, u64::MAX];
+        for v in vals {
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(v);
+            let mut inst = UtofInst::new(d, s);
+
+            inst.execute(&mut context).unwrap();
+            assert_eq!(from_u64!(inst.dest.value(&context).unwrap(); f64), v as f64);
+        }
+    }
+
+    #[test]
+    fn test_ftoi_inst() {
+        let mut context = Environment::default();
+        let vals: Vec<(f64, i64)> = vec![(-100.7, -100), (-1.0, -1), (0.0, 0), (0.9, 0), (100.14, 100)];
+        for (v, expected) in vals {
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(to_u64!(v));
+            let mut inst = FtoiInst::new(d, s);
+
+            inst.execute(&mut context).unwrap();
+            assert_eq!(from_u64!(inst.dest.value(&context).unwrap(); i64), expected);
+        }
+    }
+
+    #[test]
+    fn test_ftou_inst() {
+        let mut context = Environment::default();
+        let vals: Vec<(f64, u64)> = vec![(-1.0, 0), (0.0, 0), (0.9, 0), (100.14, 100)];
+        for (v, expected) in vals {
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(to_u64!(v));
+            let mut inst = FtouInst::new(d, s);
+
+            inst.execute(&mut context).unwrap();
+            assert_eq!(inst.dest.value(&context).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_truncb_inst() {
+        let mut context = Environment::default();
+        let vals: Vec<(i64, u64)> = vec![(-1, 255), (0, 0), (65, 65), (256, 0), (300, 44)];
+        for (v, expected) in vals {
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(to_u64!(v));
+            let mut inst = TruncbInst::new(d, s);
+
+            inst.execute(&mut context).unwrap();
+            assert_eq!(inst.dest.value(&context).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_sextb_inst() {
+        let mut context = Environment::default();
+        let vals: Vec<(u64, i64)> = vec![(0, 0), (65, 65), (127, 127), (128, -128), (255, -1), (300, 44)];
+        for (v, expected) in vals {
+            let d = Operand::Variable(String::from("x"));
+            let s = Operand::Constant(v);
+            let mut inst = SextbInst::new(d, s);
+
+            inst.execute(&mut context).unwrap();
+            assert_eq!(from_u64!(inst.dest.value(&context).unwrap(); i64), expected);
+        }
+    }
+
+    #[test]
+    fn test_sextb_display() {
+        let d = Operand::Variable("x".to_string());
+        let s = Operand::Variable("y".to_string());
+        let inst = SextbInst::new(d, s);
+        let display = format!("{}", inst);
+        assert_eq!(display, "sextb x y");
+    }
 }
@@ -70,6 +70,16 @@ fn parse_instruction(line: &Line) -> Result<Box<dyn Instruction>, Error> {
             MoveInst::NAME => MoveInst::parse(line),
             NegInst::NAME => NegInst::parse(line),
             NegfInst::NAME => NegfInst::parse(line),
+            ItofInst::NAME => ItofInst::parse(line),
+            UtofInst::NAME => UtofInst::parse(line),
+            FtoiInst::NAME => FtoiInst::parse(line),
+            FtouInst::NAME => FtouInst::parse(line),
+            TruncbInst::NAME => TruncbInst::parse(line),
+            SextbInst::NAME => SextbInst::parse(line),
+            ItosInst::NAME => ItosInst::parse(line),
+            UtosInst::NAME => UtosInst::parse(line),
+            FtosInst::NAME => FtosInst::parse(line),
+            FtospInst::NAME => FtospInst::parse(line),
             JumpInst::NAME => JumpInst::parse(line),
             JumpZeroInst::NAME => JumpZeroInst::parse(line),
             JumpNotZeroInst::NAME => JumpNotZeroInst::parse(line),
@@ -87,6 +97,8 @@ fn parse_instruction(line: &Line) -> Result<Box<dyn Instruction>, Error> {
             PushrInst::NAME => PushrInst::parse(line),
             PoprInst::NAME => PoprInst::parse(line),
             CallInst::NAME => CallInst::parse(line),
+            FunAddrInst::NAME => FunAddrInst::parse(line),
+            CallIndirectInst::NAME => CallIndirectInst::parse(line),
             RetInst::NAME => RetInst::parse(line),
             PrintiInst::NAME => PrintiInst::parse(line),
             PrintuInst::NAME => PrintuInst::parse(line),
@@ -97,6 +109,8 @@ fn parse_instruction(line: &Line) -> Result<Box<dyn Instruction>, Error> {
             PrintlnInst::NAME => PrintlnInst::parse(line),
             ReadInst::NAME => ReadInst::parse(line),
             ExitInst::NAME => ExitInst::parse(line),
+            BoundsInst::NAME => BoundsInst::parse(line),
+            HasKeyInst::NAME => HasKeyInst::parse(line),
             inst => Err(ParseError::inst_not_recognized(inst, line.line))
         },
         _ => Err(ParseError::invalid_token(line.line)),
@@ -24,7 +24,12 @@ fn test(mut test_base_path: PathBuf) {
     let buffer: Rc<RefCell<Vec<u8>>> = Rc::new(RefCell::new(Vec::new()));
     let config = ExecuteConfig::new(MEMORY, stdin, buffer.clone());
     let result = execute_with_config(instructions, config);
-    if let Err(Error::Exit(e)) = &result {
+    if let Some(err_ref) = test_content.runtime_err_ref_content {
+        match result {
+            Err(Error::Wrapped(e, _)) => assert_eq!(err_ref.trim(), format!("{}", e)),
+            _ => panic!("expected the program to fail at runtime"),
+        }
+    } else if let Err(Error::Exit(e)) = &result {
         assert_eq!(e.exit_code, 0);
     } else {
         result.unwrap();
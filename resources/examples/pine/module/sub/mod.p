@@ -1,3 +1,5 @@
+import util
+
 export func
 
 fun func() begin
@@ -1,4 +1,4 @@
-export fun
+export func
 
 fun func() begin
 
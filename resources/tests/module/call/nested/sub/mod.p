@@ -0,0 +1,13 @@
+import util
+
+export name, bump, count
+
+let count = 0
+
+fun name() -> string begin
+    return "sub with " + util::name()
+end
+
+fun bump() begin
+    count += util::inc(0)
+end
@@ -0,0 +1,13 @@
+export name, inc
+
+fun name() -> string begin
+    return "sub::util"
+end
+
+fun inc(x: int) -> int begin
+    return helper(x)
+end
+
+fun helper(x: int) -> int begin
+    return x + 1
+end
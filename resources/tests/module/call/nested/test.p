@@ -0,0 +1,16 @@
+import util
+import sub
+
+let count = 1
+
+fun main() begin
+    println(util::name())
+    println(sub::name())
+    println(sub::util::name())
+    println(sub::util::inc(count))
+    sub::bump()
+    sub::bump()
+    println(sub::count)
+    println(util::count)
+    println(count)
+end
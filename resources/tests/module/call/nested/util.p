@@ -0,0 +1,7 @@
+export name, count
+
+let count = 10
+
+fun name() -> string begin
+    return "util"
+end
@@ -0,0 +1,4 @@
+import core
+
+fun main() begin
+end
@@ -0,0 +1,4 @@
+fun func() begin
+end
+
+import a
@@ -0,0 +1,4 @@
+import a
+
+fun main() begin
+end
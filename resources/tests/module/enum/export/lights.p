@@ -0,0 +1,15 @@
+export Light, next
+
+enum Light begin
+    Red
+    Yellow
+    Green
+end
+
+fun next(l: Light) -> Light begin
+    return match l begin
+        case Red => Green
+        case Green => Yellow
+        case Yellow => Red
+    end
+end
@@ -0,0 +1,13 @@
+import lights
+
+fun stop(l: lights::Light) -> bool begin
+    return match l begin
+        case lights::Red => true
+        case _ => false
+    end
+end
+
+fun main() begin
+    let l = lights::next(lights::Yellow)
+    println(stop(l))
+end
@@ -0,0 +1,4 @@
+import util
+
+fun main() begin
+end
@@ -0,0 +1,4 @@
+export func, other
+
+fun func() begin
+end
@@ -0,0 +1,4 @@
+export func
+
+fun func() begin
+end
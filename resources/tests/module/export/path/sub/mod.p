@@ -0,0 +1,7 @@
+import util
+
+export func
+
+fun func() begin
+    util::inc(0)
+end
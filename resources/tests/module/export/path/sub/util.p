@@ -0,0 +1,9 @@
+export inc
+
+fun inc(x: int) -> int begin
+    return helper(x)
+end
+
+fun helper(x: int) -> int begin
+    return x + 1
+end
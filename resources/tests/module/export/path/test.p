@@ -0,0 +1,9 @@
+import util
+import sub
+
+fun main() -> int begin
+    util::func()
+    sub::func()
+    println(sub::util::inc(1))
+    return 0
+end
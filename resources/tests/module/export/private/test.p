@@ -0,0 +1,5 @@
+import util
+
+fun main() begin
+    util::helper()
+end
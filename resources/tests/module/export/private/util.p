@@ -0,0 +1,8 @@
+export func
+
+fun func() begin
+    helper()
+end
+
+fun helper() begin
+end
@@ -0,0 +1,7 @@
+import b
+
+export func
+
+fun func() -> int begin
+    return b::inc(0)
+end
@@ -0,0 +1,5 @@
+import a
+
+fun main() begin
+    println(a::b::inc(1))
+end
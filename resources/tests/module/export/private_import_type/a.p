@@ -0,0 +1,6 @@
+import b
+
+export func
+
+fun func() begin
+end
@@ -0,0 +1,5 @@
+export Point
+
+obj Point begin
+    x: int
+end
@@ -0,0 +1,7 @@
+import a
+
+fun f(p: a::b::Point) begin
+end
+
+fun main() begin
+end
@@ -0,0 +1,5 @@
+export inc
+
+fun inc(x: int) -> int begin
+    return x + 1
+end
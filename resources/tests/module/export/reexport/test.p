@@ -0,0 +1,6 @@
+import a
+
+fun main() begin
+    println(a::func())
+    println(a::b::inc(1))
+end
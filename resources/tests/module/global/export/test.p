@@ -0,0 +1,9 @@
+import util
+
+let start = util::total + util::LIMIT
+
+fun main() begin
+    util::add(start)
+    println(util::total)
+    println(util::GREETING)
+end
@@ -0,0 +1,10 @@
+export LIMIT, GREETING, total, add
+
+const LIMIT = 3
+const GREETING = "hi"
+
+let total = LIMIT * 10
+
+fun add(x: int) begin
+    total += x
+end
@@ -0,0 +1,5 @@
+export Shape
+
+interface Shape begin
+    fun area(self: Shape) -> int
+end
@@ -0,0 +1,27 @@
+import shapes
+
+obj Rect begin
+    w: int
+    h: int
+end
+
+impl shapes::Shape for Rect begin
+    fun area(self: Rect) -> int begin
+        return self.w * self.h
+    end
+end
+
+fun total(xs: [shapes::Shape]) -> int begin
+    let sum = 0
+    for s in xs do
+        set sum = sum + s.area()
+    end
+    return sum
+end
+
+fun main() begin
+    let xs: [shapes::Shape] = []
+    push(xs, Rect(1, 2))
+    println(total(xs))
+    println(Rect(3, 3).area())
+end
@@ -0,0 +1,4 @@
+import c
+
+fun func() begin
+end
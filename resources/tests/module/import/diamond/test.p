@@ -0,0 +1,5 @@
+import a
+import b
+
+fun main() begin
+end
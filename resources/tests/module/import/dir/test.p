@@ -0,0 +1,4 @@
+import sub
+
+fun main() begin
+end
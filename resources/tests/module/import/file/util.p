@@ -0,0 +1,2 @@
+fun func() begin
+end
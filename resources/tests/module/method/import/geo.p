@@ -0,0 +1,27 @@
+export Point, Rect, Shape
+
+obj Point begin
+    x: int
+    y: int
+end
+
+impl Point begin
+    fun norm1(self: Point) -> int begin
+        return self.x + self.y
+    end
+end
+
+interface Shape begin
+    fun area(self: Shape) -> int
+end
+
+obj Rect begin
+    w: int
+    h: int
+end
+
+impl Shape for Rect begin
+    fun area(self: Rect) -> int begin
+        return self.w * self.h
+    end
+end
@@ -0,0 +1,25 @@
+import geo
+
+obj Square begin
+    side: int
+end
+
+impl geo::Shape for Square begin
+    fun area(self: Square) -> int begin
+        return self.side * self.side
+    end
+end
+
+fun main() begin
+    let p = geo::Point(3, 4)
+    println(p.norm1())
+    println(geo::Rect(2, 3).area())
+    println(Square(2).area())
+
+    let shapes: [geo::Shape] = []
+    shapes.push(geo::Rect(1, 5))
+    shapes.push(Square(3))
+    for s in shapes do
+        println(s.area())
+    end
+end
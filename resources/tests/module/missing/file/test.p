@@ -0,0 +1,4 @@
+fun main() begin
+end
+
+import nope
@@ -0,0 +1,10 @@
+export Rect, area
+
+obj Rect begin
+    w: int
+    h: int
+end
+
+fun area(r: Rect) -> int begin
+    return r.w * r.h
+end
@@ -0,0 +1,11 @@
+import shapes
+
+obj Room begin
+    name: string
+    floor: shapes::Rect
+end
+
+fun main() begin
+    let r = Room("hall", shapes::Rect(2, 3))
+    println(shapes::area(r.floor))
+end
@@ -0,0 +1,4 @@
+obj Rect begin
+    w: int
+    h: int
+end
@@ -0,0 +1,7 @@
+import shapes
+
+fun f(r: shapes::Rect) begin
+end
+
+fun main() begin
+end
@@ -0,0 +1,15 @@
+fun main() begin
+    let x = 7
+    set x += 3
+    println(x)
+    set x -= 4
+    println(x)
+    set x *= 5
+    println(x)
+    set x /= 4
+    println(x)
+    set x %= 4
+    println(x)
+    set x **= 3
+    println(x)
+end
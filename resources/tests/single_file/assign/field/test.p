@@ -0,0 +1,18 @@
+obj Counter begin
+    value: int
+end
+
+impl Counter begin
+    fun inc(self: Counter) begin
+        self.value += 1
+    end
+end
+
+fun main() begin
+    let c = Counter(0)
+    for i in 0..3 do
+        c.inc()
+    end
+    set c.value **= 2
+    println(c.value)
+end
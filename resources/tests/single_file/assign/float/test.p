@@ -0,0 +1,7 @@
+fun main() begin
+    let x = 1.5
+    x *= 4.0
+    x -= 0.5
+    x /= 2.0
+    println(x)
+end
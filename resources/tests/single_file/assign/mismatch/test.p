@@ -0,0 +1,4 @@
+fun main() begin
+    let x = 1
+    x += 2.0
+end
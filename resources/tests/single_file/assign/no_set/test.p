@@ -0,0 +1,9 @@
+fun main() begin
+    let total = 0
+    let i = 0
+    while i < 5 do
+        i += 1
+        total += i * i
+    end
+    println(total)
+end
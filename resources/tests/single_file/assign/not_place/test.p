@@ -0,0 +1,7 @@
+fun two() -> int begin
+    return 2
+end
+
+fun main() begin
+    two() += 1
+end
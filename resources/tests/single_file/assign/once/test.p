@@ -0,0 +1,12 @@
+fun next(calls: [int]) -> int begin
+    calls[0] += 1
+    return 1
+end
+
+fun main() begin
+    let calls = [0]
+    let xs = [10, 20, 30]
+    xs[next(calls)] += 5
+    println(xs[1])
+    println(calls[0])
+end
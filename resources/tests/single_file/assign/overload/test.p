@@ -0,0 +1,17 @@
+obj Vec begin
+    x: int
+    y: int
+end
+
+impl Vec begin
+    fun add(self: Vec, other: Vec) -> Vec begin
+        return Vec(self.x + other.x, self.y + other.y)
+    end
+end
+
+fun main() begin
+    let v = Vec(1, 2)
+    v += Vec(10, 20)
+    println(v.x)
+    println(v.y)
+end
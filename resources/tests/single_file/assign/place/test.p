@@ -0,0 +1,13 @@
+fun main() begin
+    let counts = [0, 0, 0]
+    for x in [2, 0, 2, 1, 2] do
+        counts[x] += 1
+    end
+    println(counts[0])
+    println(counts[1])
+    println(counts[2])
+
+    let grid = [[1, 2], [3, 4]]
+    grid[1][0] *= 10
+    println(grid[1][0])
+end
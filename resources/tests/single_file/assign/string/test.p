@@ -0,0 +1,8 @@
+fun main() begin
+    let s = "to"
+    s += " be"
+    let words = ["or", "not"]
+    words[1] += " to be"
+    println(s)
+    println(words[1])
+end
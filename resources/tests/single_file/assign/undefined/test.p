@@ -0,0 +1,4 @@
+fun main() begin
+    let b = true
+    set b += false
+end
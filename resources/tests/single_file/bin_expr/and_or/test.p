@@ -0,0 +1,17 @@
+fun tick(calls: [int], result: bool) -> bool begin
+    calls[0] += 1
+    return result
+end
+
+fun main() begin
+    let calls = [0]
+    println(tick(calls, false) and tick(calls, true) or tick(calls, true))
+    println(calls[0])
+    println(tick(calls, true) or tick(calls, false) and tick(calls, false))
+    println(calls[0])
+    let i = 0
+    while i < 10 and not (i == 4) do
+        i += 1
+    end
+    println(i)
+end
@@ -0,0 +1,10 @@
+fun positive_at(xs: [int], i: int) -> bool begin
+    return i < len(xs) and xs[i] > 0
+end
+
+fun main() begin
+    let xs = [3, -1]
+    println(positive_at(xs, 0))
+    println(positive_at(xs, 1))
+    println(positive_at(xs, 2))
+end
@@ -0,0 +1,14 @@
+fun tick(calls: [int], result: bool) -> bool begin
+    calls[0] += 1
+    return result
+end
+
+fun main() begin
+    let calls = [0]
+    let xs: [int] = []
+    println(len(xs) == 0 or xs[0] == 1)
+    println(tick(calls, true) or tick(calls, false))
+    println(calls[0])
+    println(tick(calls, false) or tick(calls, true))
+    println(calls[0])
+end
@@ -0,0 +1,12 @@
+fun main() begin
+    let b = 250 as byte
+    set b += 10 as byte
+    println(b)
+    println(0 as byte - 1 as byte)
+    println(16 as byte * 16 as byte)
+    println(b as int + 256)
+    println(511.0 as byte)
+
+    let counts = {'a': 1 as byte}
+    println(get(counts, 'a'))
+end
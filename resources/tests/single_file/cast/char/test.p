@@ -0,0 +1,20 @@
+fun shift(c: char, n: int) -> char begin
+    return (((c as int - 'a' as int) + n) % 26 + 'a' as int) as char
+end
+
+fun main() begin
+    let word = ['p', 'i', 'n', 'e']
+    for c in word do
+        print(shift(c, 3))
+    end
+    println()
+
+    match shift('z', 1) begin
+        case 'a' then println("wrapped")
+        case _ then println("not wrapped")
+    end
+
+    println('a' < 'b')
+    println('\'' as int)
+    print('\n')
+end
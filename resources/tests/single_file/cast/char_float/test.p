@@ -0,0 +1,4 @@
+fun main() begin
+    let c = 'a'
+    let f = c as float
+end
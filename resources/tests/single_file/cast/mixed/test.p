@@ -0,0 +1,5 @@
+fun main() begin
+    let x = 1 as uint
+    let n = 1
+    let y = x + n
+end
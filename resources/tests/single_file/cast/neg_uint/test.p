@@ -0,0 +1,4 @@
+fun main() begin
+    let x = 1 as uint
+    let y = -x
+end
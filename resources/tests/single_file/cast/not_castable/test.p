@@ -0,0 +1,4 @@
+fun main() begin
+    let s = "12"
+    let n = s as int
+end
@@ -0,0 +1,18 @@
+fun average(xs: [int]) -> float begin
+    let total = 0
+    for x in xs do
+        set total += x
+    end
+
+    return total as float / len(xs) as float
+end
+
+fun main() begin
+    println(average([1, 2, 4]))
+    println(7.9 as int)
+    println(-7.9 as int)
+    println((2.5 * 4.0) as int + 1)
+    println(3 as float / 2.0)
+    println(-1 as uint as float > 0.0)
+    println(300 as byte)
+end
@@ -0,0 +1,13 @@
+fun main() begin
+    let max = -1 as uint
+    println(max)
+    println(max / 2 as uint)
+    println(max > 1 as uint)
+    println(-1 > 1)
+
+    let x: uint = 7 as uint
+    set x %= 4 as uint
+    println(x)
+    println(x as int - 4)
+    println(2.75 as uint)
+end
@@ -0,0 +1,5 @@
+const SIZE = 4
+
+fun main() begin
+    set SIZE = 5
+end
@@ -0,0 +1,17 @@
+const SIZE = 4
+const SCALE: float = 2.5
+const DEBUG = false
+
+fun area() -> int begin
+    return SIZE * SIZE
+end
+
+fun main() begin
+    println(area())
+    println(SCALE * 2.0)
+    println(DEBUG)
+    for i in 0..SIZE do
+        print(i)
+    end
+    println("")
+end
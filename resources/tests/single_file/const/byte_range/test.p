@@ -0,0 +1,5 @@
+const B: byte = 256
+
+fun main() begin
+    println(B)
+end
@@ -0,0 +1,6 @@
+const ZERO = 0
+const BAD = 10 / ZERO
+
+fun main() begin
+    println(BAD)
+end
@@ -0,0 +1,12 @@
+const KB = 1024
+const MB = KB * KB
+const HALF = -(MB / 2) % 1000
+const BIG = MB > KB and not (KB == 0)
+const POW = 2 ** 10 - 1
+
+fun main() begin
+    println(MB)
+    println(HALF)
+    println(BIG)
+    println(POW)
+end
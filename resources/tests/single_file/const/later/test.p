@@ -0,0 +1,6 @@
+const A = B + 1
+const B = 1
+
+fun main() begin
+    println(A)
+end
@@ -0,0 +1,5 @@
+const SCALE: float = 2
+
+fun main() begin
+    println(SCALE)
+end
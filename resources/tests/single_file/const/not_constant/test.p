@@ -0,0 +1,9 @@
+fun size() -> int begin
+    return 4
+end
+
+const SIZE = size()
+
+fun main() begin
+    println(SIZE)
+end
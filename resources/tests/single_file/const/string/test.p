@@ -0,0 +1,13 @@
+const GREETING = "hello"
+const TARGET = GREETING + ", world"
+const SAME = GREETING == "hello"
+
+fun greet(name: string) -> string begin
+    return GREETING + " " + name
+end
+
+fun main() begin
+    println(TARGET)
+    println(greet("pine"))
+    println(SAME)
+end
@@ -0,0 +1,16 @@
+const U: uint = 5
+const BIG = 18446744073709551615
+const HALF = BIG / 2 + 1
+const B: byte = 255
+const WRAP = B + 1
+const C = 'a'
+const LATER = C < 'b'
+
+fun main() begin
+    println(U * 2)
+    println(HALF)
+    println(B)
+    println(WRAP)
+    println(C)
+    println(LATER)
+end
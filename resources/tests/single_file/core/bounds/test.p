@@ -0,0 +1,3 @@
+fun main() begin
+    println(char_at("pine", 4))
+end
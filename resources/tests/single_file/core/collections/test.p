@@ -0,0 +1,33 @@
+fun main() begin
+    let s: Stack<int> = stack()
+    s.push(1)
+    s.push(2)
+    println(s.pop() is some)
+    s.push(3)
+    while not s.is_empty() do
+        let x = s.pop()
+        if x is some then
+            println(x)
+        end
+    end
+    println(s.peek() is none)
+
+    let q: Queue<string> = queue()
+    q.push("pine")
+    q.push("fir")
+    let first = q.pop()
+    if first is some then
+        println(first)
+    end
+    let next = q.peek()
+    if next is some then
+        println(next)
+    end
+    q.pop()
+    println(q.is_empty())
+
+    let m = {"a": 1}
+    println(join(keys(m), ","))
+    println(sum(values(m)))
+    println(len(elements({4, 2, 4})))
+end
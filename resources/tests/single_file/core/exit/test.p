@@ -0,0 +1,5 @@
+fun main() begin
+    println("before")
+    exit(0)
+    println("after")
+end
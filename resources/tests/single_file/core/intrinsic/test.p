@@ -0,0 +1,3 @@
+fun main() begin
+    println(load_char("pine", 0))
+end
@@ -0,0 +1,15 @@
+fun main() begin
+    let words = split("pine,fir,,oak", ",")
+    println(len(words))
+    println(join(words, " "))
+    println(join(reverse(words), "-"))
+    println(sum([1, 2, 3, 4]))
+
+    let i = index_of(words, "oak")
+    if i is some then
+        println(i)
+    end
+    if index_of([1, 2], 3) is none then
+        println("missing")
+    end
+end
@@ -0,0 +1,11 @@
+fun main() begin
+    println(abs(-4))
+    println(abs(4))
+    println(max(3, 9))
+    println(min(3, 9))
+    println(min("pine", "fir"))
+    println(clamp(1.5, 0.0, 1.0))
+    println(clamp(-2, 0, 10))
+    println(sqrt(16.0))
+    println(gcd(12, -18))
+end
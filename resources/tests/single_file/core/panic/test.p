@@ -0,0 +1,9 @@
+fun check(x: int) -> int begin
+    assert(x > 0, "x must be positive")
+    return x
+end
+
+fun main() begin
+    println(check(1))
+    println(check(-1))
+end
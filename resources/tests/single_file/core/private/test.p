@@ -0,0 +1,3 @@
+fun main() begin
+    println(core::matches_at("pine", "in", 1))
+end
@@ -0,0 +1,8 @@
+fun max(a: int, b: int) -> int begin
+    return a + b
+end
+
+fun main() begin
+    println(max(1, 2))
+    println(core::max(1, 2))
+end
@@ -0,0 +1,30 @@
+fun main() begin
+    let s = "Hello, Pine"
+    println(to_upper(s))
+    println(s.to_lower())
+    println(trim("  spaced  ") + "|")
+    println(substring(s, 7, 11))
+    println(repeat("ab", 3))
+    println(char_at(s, 1))
+    println(len(chars(s)))
+    println(s.starts_with("Hell"))
+    println(ends_with(s, "pine"))
+
+    let i = find(s, "Pine")
+    if i is some then
+        println(i)
+    end
+    if find(s, "fir") is none then
+        println("no fir")
+    end
+
+    let n = parse_int("-120")
+    if n is some then
+        println(n + 1)
+    end
+    if parse_int("12a") is none then
+        println("not a number")
+    end
+    println(is_digit('7'))
+    println(is_space('x'))
+end
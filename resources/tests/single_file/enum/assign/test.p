@@ -0,0 +1,8 @@
+enum Light begin
+    Red
+    Green
+end
+
+fun main() begin
+    set Red = Green
+end
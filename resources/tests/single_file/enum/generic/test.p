@@ -0,0 +1,26 @@
+enum Option<T> begin
+    Some(T)
+    None
+end
+
+fun first<T>(xs: [T]) -> Option<T> begin
+    if len(xs) == 0 then
+        return None
+    end
+
+    return Some(xs[0])
+end
+
+fun or_else<T>(o: Option<T>, default: T) -> T begin
+    return match o begin
+        case Some(x) => x
+        case None => default
+    end
+end
+
+fun main() begin
+    let empty: [string] = []
+    println(or_else(first([3, 4]), 0))
+    println(or_else(first(empty), "none"))
+    println(or_else(first(["a"]), "none"))
+end
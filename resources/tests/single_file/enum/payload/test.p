@@ -0,0 +1,20 @@
+enum Shape begin
+    Circle(float)
+    Rect(float, float)
+    Empty
+end
+
+fun area(s: Shape) -> float begin
+    return match s begin
+        case Circle(r) => 3.0 * r * r
+        case Rect(w, h) => w * h
+        case Empty => 0.0
+    end
+end
+
+fun main() begin
+    let shapes = [Circle(2.0), Rect(2.0, 3.5), Empty]
+    for s in shapes do
+        println(area(s))
+    end
+end
@@ -0,0 +1,32 @@
+enum Light begin
+    Red
+    Yellow
+    Green
+end
+
+fun next(l: Light) -> Light begin
+    match l begin
+        case Red then
+            return Green
+        case Green then
+            return Yellow
+        case Yellow then
+            return Red
+    end
+end
+
+fun name(l: Light) -> string begin
+    return match l begin
+        case Red => "red"
+        case Yellow => "yellow"
+        case Green => "green"
+    end
+end
+
+fun main() begin
+    let l = Red
+    for i in 0..4 do
+        println(name(l))
+        set l = next(l)
+    end
+end
@@ -0,0 +1,15 @@
+fun main() begin
+    for i in 0..100 do
+        if i * i > 50 then
+            println(i)
+            break
+        end
+    end
+
+    for s in ["a", "b", "stop", "c"] do
+        if s == "stop" then
+            break
+        end
+        println(s)
+    end
+end
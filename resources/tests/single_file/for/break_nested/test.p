@@ -0,0 +1,12 @@
+fun main() begin
+    let count = 0
+    for i in 0..3 do
+        for j in 0..3 do
+            if j > i then
+                break
+            end
+            set count = count + 1
+        end
+    end
+    println(count)
+end
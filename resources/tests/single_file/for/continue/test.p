@@ -0,0 +1,17 @@
+fun main() begin
+    let total = 0
+    for i in 1..=10 do
+        if i % 3 != 0 then
+            continue
+        end
+        set total = total + i
+    end
+    println(total)
+
+    for x in [1, -2, 3] do
+        if x < 0 then
+            continue
+        end
+        println(x)
+    end
+end
@@ -0,0 +1,11 @@
+fun main() begin
+    let m = {"a": 1, "b": -2, "c": 3}
+    let total = 0
+    for k in m do
+        if get(m, k) < 0 then
+            continue
+        end
+        set total = total + get(m, k)
+    end
+    println(total)
+end
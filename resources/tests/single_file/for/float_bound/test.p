@@ -0,0 +1,5 @@
+fun main() begin
+    for i in 0..2.5 do
+        println(i)
+    end
+end
@@ -0,0 +1,11 @@
+fun total(xs: [int]) -> int begin
+    let sum = 0
+    for x in xs do
+        set sum = sum + x
+    end
+    return sum
+end
+
+fun main() begin
+    println(total([1, 2, 3]))
+end
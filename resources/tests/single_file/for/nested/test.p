@@ -0,0 +1,8 @@
+fun main() begin
+    for i in 1..=3 do
+        for j in 0..i do
+            print(j)
+        end
+        println()
+    end
+end
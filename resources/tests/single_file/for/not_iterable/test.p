@@ -0,0 +1,5 @@
+fun main() begin
+    for i in 10 do
+        println(i)
+    end
+end
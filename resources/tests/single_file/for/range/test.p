@@ -0,0 +1,10 @@
+fun main() begin
+    for i in 0..5 do
+        print(i)
+    end
+    println()
+    for i in 3..3 do
+        print(i)
+    end
+    println()
+end
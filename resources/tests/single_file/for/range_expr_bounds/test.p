@@ -0,0 +1,11 @@
+fun count() -> int begin
+    return 2
+end
+
+fun main() begin
+    let n = 2
+    for i in n - 1..n + count() do
+        print(i)
+    end
+    println()
+end
@@ -0,0 +1,3 @@
+fun main() begin
+    let r = 0..10
+end
@@ -0,0 +1,8 @@
+fun main() begin
+    let n = 3
+    for i in 0..n do
+        set i = i * 10
+        set n = 0
+        println(i)
+    end
+end
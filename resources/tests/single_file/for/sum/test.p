@@ -0,0 +1,12 @@
+fun sum(n: int) -> int begin
+    let total = 0
+    for i in 1..=n do
+        set total = total + i
+    end
+    return total
+end
+
+fun main() -> int begin
+    println(sum(100))
+    return 0
+end
@@ -0,0 +1,7 @@
+fun size<T>(x: T) -> int begin
+    return len(x)
+end
+
+fun main() begin
+    println(size([1]))
+end
@@ -0,0 +1,24 @@
+fun id<T>(x: T) -> T begin
+    return x
+end
+
+fun max<T>(a: T, b: T) -> T begin
+    if a > b then
+        return a
+    end
+    return b
+end
+
+fun show<T>(x: T) begin
+    println(x)
+end
+
+fun main() begin
+    println(id(1))
+    println(id("one"))
+    println(max(1, 2))
+    println(max(1.5, 0.5))
+    println(max("a", "b"))
+    show(3)
+    show(false)
+end
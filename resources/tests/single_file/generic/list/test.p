@@ -0,0 +1,18 @@
+fun last<T>(xs: [T]) -> T begin
+    return xs[len(xs) - 1]
+end
+
+fun sum<T>(xs: [T], zero: T) -> T begin
+    let total = zero
+    for x in xs do
+        set total = total + x
+    end
+    return total
+end
+
+fun main() begin
+    println(last([1, 2, 3]))
+    println(last([true, false]))
+    println(sum([1, 2, 3], 0))
+    println(sum([0.5, 0.25], 0.0))
+end
@@ -0,0 +1,18 @@
+fun id<T>(x: T) -> T begin
+    return x
+end
+
+fun twice<T>(x: T) -> T begin
+    return id(id(x))
+end
+
+fun neg<T>(x: T) -> T begin
+    return -x
+end
+
+fun main() begin
+    println(twice(4))
+    println(twice(2.5))
+    println(neg(twice(2)))
+    println(neg(2.5))
+end
@@ -0,0 +1,16 @@
+obj Box<T> begin
+    value: T
+end
+
+fun unbox<T>(b: Box<T>) -> T begin
+    return b.value
+end
+
+fun main() begin
+    let b = Box(2.5)
+    println(b.value + 1.0)
+    let s: Box<string> = Box("boxed")
+    println(unbox(s))
+    set b.value = 0.5
+    println(unbox(b))
+end
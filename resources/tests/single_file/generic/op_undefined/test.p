@@ -0,0 +1,10 @@
+fun max<T>(a: T, b: T) -> T begin
+    if a > b then
+        return a
+    end
+    return b
+end
+
+fun main() begin
+    println(max(true, false))
+end
@@ -0,0 +1,7 @@
+fun f<T>(x: T) -> int begin
+    return x
+end
+
+fun main() begin
+    println(f(1))
+end
@@ -0,0 +1,7 @@
+obj Box<T> begin
+    value: T
+end
+
+fun main() begin
+    let b: Box<int, int> = Box(1)
+end
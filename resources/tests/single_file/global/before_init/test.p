@@ -0,0 +1,6 @@
+let first = second + 1
+let second = 1
+
+fun main() begin
+    println(first)
+end
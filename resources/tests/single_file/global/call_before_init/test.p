@@ -0,0 +1,14 @@
+let a = first()
+let b = 2
+
+fun first() -> int begin
+    return second() + 1
+end
+
+fun second() -> int begin
+    return b
+end
+
+fun main() begin
+    println(a)
+end
@@ -0,0 +1,13 @@
+let count = 0
+
+fun tick() -> int begin
+    count += 1
+    return count
+end
+
+fun main() begin
+    tick()
+    tick()
+    println(tick())
+    println(count)
+end
@@ -0,0 +1,8 @@
+let offset = 100
+let shift = fun(x: int) -> int => x + offset
+
+fun main() begin
+    println(shift(1))
+    set offset = 0
+    println(shift(1))
+end
@@ -0,0 +1,12 @@
+let log = []
+
+fun record(msg: string) begin
+    push(log, msg)
+end
+
+fun main() begin
+    record("start")
+    record("stop")
+    println(len(log))
+    println(log[1])
+end
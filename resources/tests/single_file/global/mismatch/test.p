@@ -0,0 +1,5 @@
+let count: int = 0
+
+fun main() begin
+    set count = "many"
+end
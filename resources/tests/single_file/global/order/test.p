@@ -0,0 +1,13 @@
+const BASE = 10
+
+let first = double(BASE)
+let second = first + 1
+
+fun double(x: int) -> int begin
+    return x * 2
+end
+
+fun main() begin
+    println(first)
+    println(second)
+end
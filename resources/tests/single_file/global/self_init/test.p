@@ -0,0 +1,5 @@
+let x = x + 1
+
+fun main() begin
+    println(x)
+end
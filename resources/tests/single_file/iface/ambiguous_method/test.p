@@ -0,0 +1,28 @@
+interface Shape begin
+    fun describe(self: Shape) -> string
+end
+
+interface Named begin
+    fun describe(self: Named) -> string
+end
+
+obj Square begin
+end
+
+impl Shape for Square begin
+    fun describe(self: Square) -> string begin
+        return "square"
+    end
+end
+
+impl Named for Square begin
+    fun describe(self: Square) -> string begin
+        return "Square"
+    end
+end
+
+fun main() begin
+    let s: Shape = Square()
+    println(s.describe())
+    println(Square().describe())
+end
@@ -0,0 +1,23 @@
+interface Scaler begin
+    fun scale(self: Scaler, by: int, offset: int) -> int
+end
+
+obj Doubler begin
+    base: int
+end
+
+impl Scaler for Doubler begin
+    fun scale(self: Doubler, by: int, offset: int) -> int begin
+        return self.base * 2 * by + offset
+    end
+end
+
+fun apply(s: Scaler) -> int begin
+    return s.scale(3, 1)
+end
+
+fun main() begin
+    let d = Doubler(5)
+    println(d.scale(2, 0))
+    println(apply(d))
+end
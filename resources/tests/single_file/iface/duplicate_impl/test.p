@@ -0,0 +1,14 @@
+interface Shape begin
+end
+
+obj Rect begin
+end
+
+impl Shape for Rect begin
+end
+
+impl Shape for Rect begin
+end
+
+fun main() begin
+end
@@ -0,0 +1,48 @@
+interface Shape begin
+    fun area(self: Shape) -> int
+    fun name(self: Shape) -> string
+end
+
+obj Rect begin
+    w: int
+    h: int
+end
+
+obj Square begin
+    side: int
+end
+
+impl Shape for Rect begin
+    fun area(self: Rect) -> int begin
+        return self.w * self.h
+    end
+
+    fun name(self: Rect) -> string begin
+        return "rect"
+    end
+end
+
+impl Shape for Square begin
+    fun name(self: Square) -> string begin
+        return "square"
+    end
+
+    fun area(self: Square) -> int begin
+        return self.side * self.side
+    end
+end
+
+fun describe(s: Shape) begin
+    print(s.name())
+    print(" ")
+    println(s.area())
+end
+
+fun main() begin
+    describe(Rect(2, 3))
+    describe(Square(4))
+    let s: Shape = Square(5)
+    describe(s)
+    set s = Rect(1, 1)
+    describe(s)
+end
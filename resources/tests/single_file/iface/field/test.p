@@ -0,0 +1,32 @@
+interface Greeter begin
+    fun greet(self: Greeter) -> string
+end
+
+obj English begin
+end
+
+obj Spanish begin
+end
+
+impl Greeter for English begin
+    fun greet(self: English) -> string begin
+        return "hello"
+    end
+end
+
+impl Greeter for Spanish begin
+    fun greet(self: Spanish) -> string begin
+        return "hola"
+    end
+end
+
+obj Person begin
+    greeter: Greeter
+end
+
+fun main() begin
+    let p = Person(English())
+    println(p.greeter.greet())
+    set p.greeter = Spanish()
+    println(p.greeter.greet())
+end
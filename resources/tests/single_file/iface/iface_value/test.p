@@ -0,0 +1,6 @@
+interface Shape begin
+end
+
+fun main() begin
+    let s = Shape
+end
@@ -0,0 +1,35 @@
+interface Animal begin
+    fun speak(self: Animal)
+end
+
+obj Dog begin
+    name: string
+end
+
+obj Cat begin
+    lives: int
+end
+
+impl Animal for Dog begin
+    fun speak(self: Dog) begin
+        println(self.name + " says woof")
+    end
+end
+
+impl Animal for Cat begin
+    fun speak(self: Cat) begin
+        print("a cat with ")
+        print(self.lives)
+        println(" lives says meow")
+    end
+end
+
+fun main() begin
+    let animals: [Animal] = []
+    push(animals, Dog("rex"))
+    push(animals, Cat(9))
+    push(animals, Dog("fido"))
+    for a in animals do
+        a.speak()
+    end
+end
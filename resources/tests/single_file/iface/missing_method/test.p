@@ -0,0 +1,16 @@
+interface Shape begin
+    fun area(self: Shape) -> int
+    fun name(self: Shape) -> string
+end
+
+obj Rect begin
+end
+
+impl Shape for Rect begin
+    fun area(self: Rect) -> int begin
+        return 0
+    end
+end
+
+fun main() begin
+end
@@ -0,0 +1,8 @@
+obj Rect begin
+end
+
+impl Rect for Rect begin
+end
+
+fun main() begin
+end
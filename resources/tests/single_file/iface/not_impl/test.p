@@ -0,0 +1,10 @@
+interface Shape begin
+    fun area(self: Shape) -> int
+end
+
+obj Rect begin
+end
+
+fun main() begin
+    let s: Shape = Rect()
+end
@@ -0,0 +1,19 @@
+interface Shape begin
+    fun area(self: Shape) -> int
+end
+
+obj Rect begin
+end
+
+impl Shape for Rect begin
+    fun area(self: Rect) -> int begin
+        return 0
+    end
+
+    fun perimeter(self: Rect) -> int begin
+        return 0
+    end
+end
+
+fun main() begin
+end
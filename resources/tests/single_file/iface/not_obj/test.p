@@ -0,0 +1,8 @@
+interface Shape begin
+end
+
+impl Shape for int begin
+end
+
+fun main() begin
+end
@@ -0,0 +1,6 @@
+interface Shape begin
+    fun area(x: int) -> int
+end
+
+fun main() begin
+end
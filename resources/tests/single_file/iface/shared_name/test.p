@@ -0,0 +1,36 @@
+interface Shape begin
+    fun describe(self: Shape) -> string
+end
+
+interface Animal begin
+    fun describe(self: Animal) -> string
+end
+
+obj Square begin
+    side: int
+end
+
+impl Shape for Square begin
+    fun describe(self: Square) -> string begin
+        return "square"
+    end
+end
+
+obj Dog begin
+    name: string
+end
+
+impl Animal for Dog begin
+    fun describe(self: Dog) -> string begin
+        return "dog"
+    end
+end
+
+fun main() begin
+    let s: Shape = Square(2)
+    let a: Animal = Dog("rex")
+    println(s.describe())
+    println(a.describe())
+    println(Square(3).describe())
+    println(Dog("fido").describe())
+end
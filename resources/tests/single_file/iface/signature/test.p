@@ -0,0 +1,15 @@
+interface Shape begin
+    fun area(self: Shape) -> int
+end
+
+obj Rect begin
+end
+
+impl Shape for Rect begin
+    fun area(self: Rect) -> float begin
+        return 0.0
+    end
+end
+
+fun main() begin
+end
@@ -0,0 +1,19 @@
+interface Shape begin
+    fun area(self: Shape) -> int
+end
+
+obj Rect begin
+    w: int
+    h: int
+end
+
+impl Shape for Rect begin
+    fun area(self: Rect) -> int begin
+        return self.w * self.h
+    end
+end
+
+fun main() begin
+    let r = Rect(2, 3)
+    println(r.area())
+end
@@ -0,0 +1,7 @@
+fun twice(x) begin
+    return x * 2
+end
+
+fun main() begin
+    println(twice("two"))
+end
@@ -0,0 +1,7 @@
+fun add(a, b) begin
+    return a + b
+end
+
+fun main() begin
+    println(add(1.0, 2.0))
+end
@@ -0,0 +1,17 @@
+obj A begin
+    x: int
+end
+
+obj B begin
+    x: string
+end
+
+fun get(p) begin
+    let v = p.x
+    let a: A = p
+    return v
+end
+
+fun main() begin
+    println(get(A(7)))
+end
@@ -0,0 +1,18 @@
+obj Point begin
+    x: int
+    y: int
+end
+
+fun sum(p) begin
+    return p.x + p.y
+end
+
+fun origin() begin
+    return Point(0, 0)
+end
+
+fun main() begin
+    let p = Point(3, 4)
+    println(sum(p))
+    println(sum(origin()))
+end
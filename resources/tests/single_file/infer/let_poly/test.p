@@ -0,0 +1,9 @@
+fun id(x) begin
+    return x
+end
+
+fun main() begin
+    let f = id
+    println(f(1))
+    println(f("a"))
+end
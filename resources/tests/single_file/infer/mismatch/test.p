@@ -0,0 +1,4 @@
+fun main() begin
+    let x = 1
+    set x = "one"
+end
@@ -0,0 +1,17 @@
+fun add(a, b) begin
+    return a + b
+end
+
+fun half(x) begin
+    return x / 2.0
+end
+
+fun greet(name) begin
+    println("hello " + name)
+end
+
+fun main() begin
+    println(add(1, 2))
+    println(half(3.0))
+    greet("pine")
+end
@@ -0,0 +1,14 @@
+fun id(x) begin
+    return x
+end
+
+fun first(xs) begin
+    return xs[0]
+end
+
+fun main() begin
+    println(id(1))
+    println(id("one"))
+    println(first([true, false]))
+    println(first(["a", "b"]))
+end
@@ -0,0 +1,26 @@
+fun is_even(n) begin
+    if n == 0 then
+        return true
+    end
+    return is_odd(n - 1)
+end
+
+fun is_odd(n) begin
+    if n == 0 then
+        return false
+    end
+    return is_even(n - 1)
+end
+
+fun fact(n) begin
+    if n <= 1 then
+        return 1
+    end
+    return n * fact(n - 1)
+end
+
+fun main() begin
+    println(is_even(10))
+    println(is_odd(7))
+    println(fact(5))
+end
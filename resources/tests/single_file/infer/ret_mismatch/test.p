@@ -0,0 +1,10 @@
+fun pick(b) begin
+    if b then
+        return 1
+    end
+    return "one"
+end
+
+fun main() begin
+    println(pick(true))
+end
@@ -0,0 +1,6 @@
+fun get(p) begin
+    return p.x
+end
+
+fun main() begin
+end
@@ -0,0 +1,14 @@
+fun main() begin
+    let x = 3
+    let y = 2.0 / 3.0
+    println("x = {x}, y = {y:.2}")
+    println("y = {y}")
+    let u = 7 as uint
+    let b = 300 as byte
+    println("{u} {b} {'c'} {true} {false}")
+    println("{{x}} is {x + 1}")
+    println(to_string(2.5, 1))
+    println(x.to_string() + "!")
+    let names = ["fir", "pine"]
+    println("{len(names)} trees, the first is {names[0]}")
+end
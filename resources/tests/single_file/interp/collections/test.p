@@ -0,0 +1,35 @@
+obj Point begin
+    x: int
+    y: int
+end
+
+impl Point begin
+    fun to_string(self: Point) -> string begin
+        return "P{self.x}"
+    end
+end
+
+fun describe<T>(xs: [T]) -> string begin
+    return "<{xs}>"
+end
+
+fun main() begin
+    let xs = [1, 2, 3]
+    println(to_string(xs))
+    println("nested: {[[1.5], []]}")
+    println(to_string({'a'}))
+    println(to_string({"one": 1}))
+    println("{(1, "two", (true, 'c'))}")
+
+    let o: int? = none
+    println("{o}")
+    set o = 3
+    println("{o}")
+
+    println(describe([Point(1, 2), Point(3, 4)]))
+    println(describe([(1, 2)]))
+
+    let ys = []
+    push(ys, 7 as uint)
+    println("{ys}")
+end
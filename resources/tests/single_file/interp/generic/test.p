@@ -0,0 +1,21 @@
+obj Point begin
+    x: int
+    y: int
+end
+
+impl Point begin
+    fun to_string(self: Point) -> string begin
+        return "({self.x}, {self.y})"
+    end
+end
+
+fun describe<T>(x: T) -> string begin
+    return "<{x}>"
+end
+
+fun main() begin
+    println(describe(5))
+    println(describe(1.5))
+    println(describe('z'))
+    println(describe(Point(3, 4)))
+end
@@ -0,0 +1,11 @@
+obj P begin
+    x: int
+end
+
+fun describe<T>(xs: [T]) -> string begin
+    return "<{xs}>"
+end
+
+fun main() begin
+    println(describe([P(1)]))
+end
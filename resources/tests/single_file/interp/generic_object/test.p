@@ -0,0 +1,16 @@
+obj Pair<A, B> begin
+    first: A
+    second: B
+end
+
+impl Pair<A, B> begin
+    fun to_string(self: Pair<A, B>) -> string begin
+        return "({self.first}, {self.second})"
+    end
+end
+
+fun main() begin
+    let p = Pair(1, "one")
+    println("{p}")
+    println(to_string(Pair('x', Pair(true, 2.5))))
+end
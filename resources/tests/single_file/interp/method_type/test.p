@@ -0,0 +1,12 @@
+obj P begin
+    x: int
+end
+
+impl P begin
+    fun to_string(self: P) -> int begin
+        return self.x
+    end
+end
+
+fun main() begin
+end
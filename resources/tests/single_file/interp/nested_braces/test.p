@@ -0,0 +1,4 @@
+fun main() begin
+    println("{"}"}")
+    println("<{"a" + "}"}{"{{"}\"{"\"{"}>")
+end
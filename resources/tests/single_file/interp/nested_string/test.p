@@ -0,0 +1,4 @@
+fun main() begin
+    let names = ["pine", "fir"]
+    println("{join(names, ", ")} and {"{"}{len(names)}{"}"} {'}'} {to_upper("a{b}")}")
+end
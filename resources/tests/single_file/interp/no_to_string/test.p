@@ -0,0 +1,8 @@
+obj P begin
+    x: int
+end
+
+fun main() begin
+    let p = P(1)
+    println("p = {p}")
+end
@@ -0,0 +1,8 @@
+obj P begin
+    x: int
+end
+
+fun main() begin
+    let ps = [P(1)]
+    println("ps = {ps}")
+end
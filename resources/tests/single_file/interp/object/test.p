@@ -0,0 +1,16 @@
+obj Point begin
+    x: int
+    y: int
+end
+
+impl Point begin
+    fun to_string(self: Point) -> string begin
+        return "({self.x}, {self.y})"
+    end
+end
+
+fun main() begin
+    let p = Point(1, -2)
+    println("p = {p}")
+    println(p.to_string())
+end
@@ -0,0 +1,4 @@
+fun main() begin
+    let x = 3
+    println("{x:.2}")
+end
@@ -0,0 +1,7 @@
+fun main() begin
+    let n = 0
+    let f = fun() begin
+        set n = 1
+    end
+    f()
+end
@@ -0,0 +1,14 @@
+fun each(xs: [int], f: fun(int)) begin
+    for x in xs do
+        f(x)
+    end
+end
+
+fun main() begin
+    let total = [0]
+    each([1, 2, 3], fun(x) begin
+        set total[0] = total[0] + x
+    end)
+    println(total[0])
+    each([4, 5], fun(x) => println(x))
+end
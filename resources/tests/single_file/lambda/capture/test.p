@@ -0,0 +1,15 @@
+fun apply(f: fun(int) -> int, x: int) -> int begin
+    return f(x)
+end
+
+fun main() begin
+    let n = 10
+    let name = "sum"
+    let add = fun(x) begin
+        println(name)
+        return x + n
+    end
+    set n = 100
+    println(apply(add, 5))
+    println(add(1))
+end
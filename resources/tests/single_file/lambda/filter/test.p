@@ -0,0 +1,17 @@
+fun filter<T>(xs: [T], keep: fun(T) -> bool) -> [T] begin
+    let ys = []
+    for x in xs do
+        if keep(x) then
+            push(ys, x)
+        end
+    end
+    return ys
+end
+
+fun main() begin
+    let evens = filter([1, 2, 3, 4, 5, 6], fun(x) => x % 2 == 0)
+    println(len(evens))
+    for x in evens do
+        println(x)
+    end
+end
@@ -0,0 +1,19 @@
+fun map<T, U>(xs: [T], f: fun(T) -> U) -> [U] begin
+    let ys = []
+    for x in xs do
+        push(ys, f(x))
+    end
+    return ys
+end
+
+fun main() begin
+    let xs = map([1, 2, 3], fun(x) => x * 2)
+    for x in xs do
+        println(x)
+    end
+
+    let ss = map(xs, fun(x: int) -> bool => x > 2)
+    print(ss[0])
+    print(" ")
+    println(ss[1])
+end
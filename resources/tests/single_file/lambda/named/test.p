@@ -0,0 +1,19 @@
+fun square(x: int) -> int begin
+    return x * x
+end
+
+fun twice(f: fun(int) -> int, x: int) -> int begin
+    return f(f(x))
+end
+
+fun id<T>(x: T) -> T begin
+    return x
+end
+
+fun main() begin
+    println(twice(square, 3))
+    let f = square
+    println(f(4))
+    let g: fun(string) -> string = id
+    println(g("id"))
+end
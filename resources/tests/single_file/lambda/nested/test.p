@@ -0,0 +1,10 @@
+fun main() begin
+    let a = 1
+    let outer = fun(b: int) -> fun(int) -> int begin
+        return fun(c) => a + b + c
+    end
+    let inner = outer(10)
+    println(inner(100))
+    let fs = [fun(x: int) -> int => x + a, fun(x: int) -> int => x * 3]
+    println(fs[0](1) + fs[1](1))
+end
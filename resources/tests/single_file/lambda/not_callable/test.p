@@ -0,0 +1,4 @@
+fun main() begin
+    let n = 1
+    println(n(2))
+end
@@ -0,0 +1,11 @@
+fun adder(n: int) -> fun(int) -> int begin
+    return fun(x) => x + n
+end
+
+fun main() begin
+    let add2 = adder(2)
+    let add5 = adder(5)
+    println(add2(1))
+    println(add5(1))
+    println(adder(10)(add2(0)))
+end
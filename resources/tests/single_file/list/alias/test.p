@@ -0,0 +1,11 @@
+fun fill(xs: [int]) begin
+    push(xs, 1)
+    push(xs, 2)
+end
+
+fun main() begin
+    let xs: [int] = []
+    let ys = xs
+    fill(ys)
+    println(len(xs))
+end
@@ -0,0 +1,18 @@
+fun squares(n: int) -> [int] begin
+    let xs: [int] = []
+    for i in 0..n do
+        push(xs, i * i)
+    end
+    return xs
+end
+
+fun main() begin
+    let xs = squares(0)
+    println(len(xs))
+    for x in xs do
+        println(x)
+    end
+    set xs = []
+    push(xs, 5)
+    println(xs[0])
+end
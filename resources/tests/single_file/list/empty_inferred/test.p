@@ -0,0 +1,6 @@
+fun main() begin
+    let xs = []
+    push(xs, "a")
+    println(xs[0])
+    println(len(xs))
+end
@@ -0,0 +1,11 @@
+fun main() begin
+    let xs = [1, 2, 3]
+    let total = 0
+    for x in xs do
+        if x == 2 then
+            push(xs, 4)
+        end
+        set total = total + x
+    end
+    println(total)
+end
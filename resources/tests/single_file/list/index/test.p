@@ -0,0 +1,11 @@
+fun get(xs: [int], i: int) -> int begin
+    return xs[i]
+end
+
+fun main() begin
+    let xs = [10, 20, 30]
+    let i = 1
+    println(xs[i + 1])
+    println(get(xs, 0))
+    println([4, 5, 6][1])
+end
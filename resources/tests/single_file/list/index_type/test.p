@@ -0,0 +1,4 @@
+fun main() begin
+    let xs = [1, 2]
+    let x = xs[true]
+end
@@ -0,0 +1,7 @@
+fun main() begin
+    let xs: [int] = []
+    println(len(xs))
+    println(len([1, 2, 3]))
+    println(len("pine"))
+    println(len(""))
+end
@@ -0,0 +1,8 @@
+fun main() begin
+    let xs = [1, 2, 3]
+    let ys: [float] = [1.5]
+    let zs = [true, false,]
+    println(xs[0] + xs[1] + xs[2])
+    println(ys[0])
+    println(zs[1])
+end
@@ -0,0 +1,3 @@
+fun main() begin
+    let xs = [1, 2.0]
+end
@@ -0,0 +1,4 @@
+fun main() begin
+    let xs = [1, 2, 3]
+    set xs[-1] = 0
+end
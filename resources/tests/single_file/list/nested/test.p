@@ -0,0 +1,12 @@
+fun main() begin
+    let grid = [[1, 2], [3, 4]]
+    set grid[1][0] = 30
+    push(grid, [5])
+    for row in grid do
+        for x in row do
+            print(x)
+            print(" ")
+        end
+        println()
+    end
+end
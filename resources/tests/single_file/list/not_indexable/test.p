@@ -0,0 +1,4 @@
+fun main() begin
+    let x = 1
+    let y = x[0]
+end
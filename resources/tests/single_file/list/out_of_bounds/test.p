@@ -0,0 +1,5 @@
+fun main() begin
+    let xs = [1, 2, 3]
+    println(xs[2])
+    println(xs[3])
+end
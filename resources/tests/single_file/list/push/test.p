@@ -0,0 +1,12 @@
+fun main() begin
+    let xs = [1]
+    for i in 2..=10 do
+        push(xs, i * i)
+    end
+    println(len(xs))
+    for x in xs do
+        print(x)
+        print(" ")
+    end
+    println()
+end
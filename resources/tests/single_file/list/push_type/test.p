@@ -0,0 +1,4 @@
+fun main() begin
+    let xs = [1]
+    push(xs, "a")
+end
@@ -0,0 +1,7 @@
+fun f() -> int begin
+    return 1
+end
+
+fun main() begin
+    set f() = 2
+end
@@ -0,0 +1,8 @@
+fun main() begin
+    let xs = [1, 2, 3]
+    set xs[0] = 10
+    set xs[len(xs) - 1] = xs[0] + 1
+    for x in xs do
+        println(x)
+    end
+end
@@ -0,0 +1,7 @@
+fun main() begin
+    let words = ["hello", "pine"]
+    push(words, "world")
+    for w in words do
+        println(w + "!")
+    end
+end
@@ -0,0 +1,5 @@
+fun main() begin
+    let names = {true: "yes", false: "no"}
+    println(get(names, 1 < 2))
+    println(get(names, 1 > 2))
+end
@@ -0,0 +1,4 @@
+fun main() begin
+    let m = {:}
+    println(len(m))
+end
@@ -0,0 +1,10 @@
+fun main() begin
+    let m = {3: 30, 1: 10, 2: 20}
+    let total = 0
+    for k in m do
+        print(k)
+        set total = total + get(m, k)
+    end
+    println("")
+    println(total)
+end
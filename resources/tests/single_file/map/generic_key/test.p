@@ -0,0 +1,18 @@
+fun count<T>(xs: [T]) -> Map<T, int> begin
+    let counts = {:}
+    for x in xs do
+        if contains(counts, x) then
+            insert(counts, x, get(counts, x) + 1)
+        else
+            insert(counts, x, 1)
+        end
+    end
+    return counts
+end
+
+fun main() begin
+    let c = count(["x", "y", "x"])
+    println(get(c, "x"))
+    let n = count([1.5])
+    println(len(n))
+end
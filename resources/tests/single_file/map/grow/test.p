@@ -0,0 +1,15 @@
+fun main() begin
+    let counts: Map<string, int> = {:}
+    let words = ["a", "b", "a", "c", "b", "a", "d", "e", "f", "g", "h", "i", "j", "a"]
+    for w in words do
+        if contains(counts, w) then
+            insert(counts, w, get(counts, w) + 1)
+        else
+            insert(counts, w, 1)
+        end
+    end
+    println(len(counts))
+    println(get(counts, "a"))
+    println(get(counts, "b"))
+    println(get(counts, "j"))
+end
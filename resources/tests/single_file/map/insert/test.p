@@ -0,0 +1,10 @@
+fun main() begin
+    let squares: Map<int, int> = {:}
+    for i in 0..5 do
+        insert(squares, i, i * i)
+    end
+    insert(squares, 3, -1)
+    println(len(squares))
+    println(get(squares, 3))
+    println(get(squares, 4))
+end
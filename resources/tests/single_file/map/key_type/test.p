@@ -0,0 +1,4 @@
+fun main() begin
+    let m = {1.5: "x"}
+    println(len(m))
+end
@@ -0,0 +1,7 @@
+fun main() begin
+    let ages = {"alice": 31, "bob": 27}
+    println(get(ages, "alice"))
+    println(get(ages, "bob"))
+    println(len(ages))
+    println(contains(ages, "carol"))
+end
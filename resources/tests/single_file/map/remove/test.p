@@ -0,0 +1,10 @@
+fun main() begin
+    let m = {1: "one", 2: "two", 3: "three"}
+    remove(m, 2)
+    remove(m, 5)
+    println(len(m))
+    println(contains(m, 2))
+    println(contains(m, 3))
+    insert(m, 2, "deux")
+    println(get(m, 2))
+end
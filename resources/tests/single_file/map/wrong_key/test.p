@@ -0,0 +1,4 @@
+fun main() begin
+    let m = {"a": 1}
+    println(get(m, 1))
+end
@@ -0,0 +1,9 @@
+enum Shape begin
+    Rect(float, float)
+end
+
+fun main() begin
+    match Rect(1.0, 2.0) begin
+        case Rect(w) => println(w)
+    end
+end
@@ -0,0 +1,33 @@
+fun classify(n: int) -> string begin
+    return match n begin
+        case 0 => "zero"
+        case -1 => "minus one"
+        case n => match n > 0 begin
+            case true => "positive"
+            case false => "negative"
+        end
+    end
+end
+
+fun greet(lang: string) begin
+    match lang begin
+        case "en" => println("hello")
+        case "fr" => println("bonjour")
+        case other then
+            print("unknown ")
+            println(other)
+    end
+end
+
+fun main() begin
+    println(classify(0))
+    println(classify(-1))
+    println(classify(5))
+    println(classify(-5))
+    greet("fr")
+    greet("de")
+    match 2.5 begin
+        case 2.5 => println("half")
+        case _ => println("other")
+    end
+end
@@ -0,0 +1,11 @@
+enum Light begin
+    Red
+    Green
+end
+
+fun main() begin
+    match Red begin
+        case 1 => println(1)
+        case _ => println(2)
+    end
+end
@@ -0,0 +1,27 @@
+enum Shape begin
+    Circle(float)
+    Rect(float, float)
+end
+
+enum Option<T> begin
+    Some(T)
+    None
+end
+
+fun describe(o: Option<Shape>) begin
+    match o begin
+        case Some(Circle(r)) then
+            print("circle ")
+            println(r)
+        case Some(Rect(1.0, h)) => println(h)
+        case Some(_) => println("rect")
+        case None => println("none")
+    end
+end
+
+fun main() begin
+    describe(Some(Circle(1.5)))
+    describe(Some(Rect(1.0, 7.0)))
+    describe(Some(Rect(2.0, 7.0)))
+    describe(None)
+end
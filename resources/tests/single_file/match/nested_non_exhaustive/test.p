@@ -0,0 +1,13 @@
+enum Option<T> begin
+    Some(T)
+    None
+end
+
+fun main() begin
+    let o = Some(Some(1))
+    match o begin
+        case Some(None) => println(0)
+        case None => println(1)
+        case Some(Some(2)) => println(2)
+    end
+end
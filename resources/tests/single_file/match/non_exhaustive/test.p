@@ -0,0 +1,13 @@
+enum Shape begin
+    Circle(float)
+    Rect(float, float)
+    Empty
+end
+
+fun main() begin
+    let s = Rect(1.0, 2.0)
+    match s begin
+        case Circle(r) => println(r)
+        case Empty => println(0)
+    end
+end
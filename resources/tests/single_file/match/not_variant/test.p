@@ -0,0 +1,10 @@
+fun f(x: int) -> int begin
+    return x
+end
+
+fun main() begin
+    match 1 begin
+        case f(x) => println(x)
+        case _ => println(0)
+    end
+end
@@ -0,0 +1,12 @@
+enum Light begin
+    Red
+    Green
+end
+
+fun main() begin
+    match Red begin
+        case Red => println(1)
+        case _ => println(2)
+        case Green => println(3)
+    end
+end
@@ -0,0 +1,10 @@
+fun main() begin
+    let xs = [1, 2]
+    xs.push(3)
+    println(xs.len())
+    let m = {"a": 1}
+    m.insert("b", 2)
+    println(m.get("b"))
+    println(m.contains("c"))
+    println("hello".len())
+end
@@ -0,0 +1,30 @@
+obj Vec2 begin
+    x: int
+    y: int
+end
+
+impl Vec2 begin
+    fun plus(self: Vec2, other: Vec2) -> Vec2 begin
+        return Vec2(self.x + other.x, self.y + other.y)
+    end
+
+    fun len2(self: Vec2) -> int begin
+        return self.x * self.x + self.y * self.y
+    end
+end
+
+fun map<T, U>(xs: [T], f: fun(T) -> U) -> [U] begin
+    let ys = []
+    for x in xs do
+        push(ys, f(x))
+    end
+    return ys
+end
+
+fun main() begin
+    let v = Vec2(1, 2)
+    println(v.plus(Vec2(2, 2)).plus(v).len2())
+    let ls = [v, Vec2(3, 4)].map(fun(v) => v.len2())
+    println(ls[0])
+    println(ls[1])
+end
@@ -0,0 +1,9 @@
+obj Button begin
+    label: string
+    on_click: fun(string) -> int
+end
+
+fun main() begin
+    let b = Button("ok", fun(s) => len(s))
+    println(b.on_click("clicked"))
+end
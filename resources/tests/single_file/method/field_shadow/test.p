@@ -0,0 +1,14 @@
+obj Button begin
+    label: string
+    cb: fun(int) -> int
+end
+
+fun cb(b: Button) -> int begin
+    return 0
+end
+
+fun main() begin
+    let b = Button("ok", fun(n) => n * 2)
+    println(b.cb(21))
+    println(cb(b))
+end
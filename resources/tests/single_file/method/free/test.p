@@ -0,0 +1,21 @@
+fun sum(xs: [int]) -> int begin
+    let total = 0
+    for x in xs do
+        set total = total + x
+    end
+    return total
+end
+
+fun scale(xs: [int], k: int) -> [int] begin
+    let ys = []
+    for x in xs do
+        push(ys, x * k)
+    end
+    return ys
+end
+
+fun main() begin
+    let xs = [1, 2, 3]
+    println(xs.sum())
+    println(xs.scale(10).sum())
+end
@@ -0,0 +1,35 @@
+obj Box<T> begin
+    value: T
+end
+
+impl Box<T> begin
+    fun get(self: Box<T>) -> T begin
+        return self.value
+    end
+
+    fun put(self: Box<T>, value: T) begin
+        set self.value = value
+    end
+
+    fun add(self: Box<T>, other: Box<T>) -> Box<T> begin
+        return Box(self.value + other.value)
+    end
+
+    fun to_string(self: Box<T>) -> string begin
+        return "Box({self.get()})"
+    end
+end
+
+fun first<T>(boxes: [Box<T>]) -> T begin
+    return boxes[0].get()
+end
+
+fun main() begin
+    let b = Box(2)
+    b.put(b.get() + 1)
+    println(b.get())
+    println(first([Box("boxed")]))
+    println((b + Box(4)).get())
+    println(to_string(Box("a") + Box("b")))
+    println("{Box(1.5)}")
+end
@@ -0,0 +1,12 @@
+obj Box<T> begin
+    value: T
+end
+
+impl Box<int> begin
+    fun get(self: Box<int>) -> int begin
+        return self.value
+    end
+end
+
+fun main() begin
+end
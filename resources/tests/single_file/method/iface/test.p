@@ -0,0 +1,35 @@
+interface Shape begin
+    fun area(self: Shape) -> int
+end
+
+obj Rect begin
+    w: int
+    h: int
+end
+
+obj Square begin
+    s: int
+end
+
+impl Shape for Rect begin
+    fun area(self: Rect) -> int begin
+        return self.w * self.h
+    end
+end
+
+impl Shape for Square begin
+    fun area(self: Square) -> int begin
+        return self.s * self.s
+    end
+end
+
+fun main() begin
+    let r = Rect(2, 3)
+    println(r.area())
+    let shapes: [Shape] = []
+    shapes.push(Square(4))
+    shapes.push(r)
+    for s in shapes do
+        println(s.area())
+    end
+end
@@ -0,0 +1,14 @@
+obj Counter begin
+    value: int
+end
+
+impl Counter begin
+    fun inc(self: Counter) begin
+        set self.value = self.value + 1
+    end
+end
+
+fun main() begin
+    let n = 1
+    n.inc()
+end
@@ -0,0 +1,20 @@
+obj Counter begin
+    value: int
+end
+
+impl Counter begin
+    fun inc(self: Counter) begin
+        set self.value = self.value + 1
+    end
+
+    fun get(self: Counter) -> int begin
+        return self.value
+    end
+end
+
+fun main() begin
+    let c = Counter(0)
+    c.inc()
+    c.inc()
+    println(c.get())
+end
@@ -0,0 +1,31 @@
+obj Circle begin
+    r: int
+end
+
+obj Square begin
+    s: int
+end
+
+impl Circle begin
+    fun area(self: Circle) -> int begin
+        return 3 * self.r * self.r
+    end
+end
+
+impl Square begin
+    fun area(self: Square) -> int begin
+        return self.s * self.s
+    end
+end
+
+fun area(n: int) -> int begin
+    return n
+end
+
+fun main() begin
+    let n = 7
+    println(n.area())
+    println(Circle(2).area())
+    let area = Square(5).area()
+    println(area)
+end
@@ -0,0 +1,15 @@
+obj Temp begin
+    deg: int
+end
+
+impl Temp begin
+    fun to_string(self: Temp) -> string begin
+        return to_string(self.deg) + "C"
+    end
+end
+
+fun main() begin
+    let t = Temp(21)
+    println(t.to_string())
+    println(to_string(t))
+end
@@ -0,0 +1,4 @@
+fun main() begin
+    let xs = [1]
+    xs.total()
+end
@@ -0,0 +1,10 @@
+obj Box begin
+    value: int
+end
+
+fun main() begin
+    let a = Box(1)
+    let b = a
+    set b.value = 2
+    println(a.value)
+end
@@ -0,0 +1,11 @@
+obj Point begin
+    x: int
+    y: int
+end
+
+fun main() begin
+    let p = Point(3, 4)
+    println(p.x)
+    println(p.y)
+    println(p.x * p.x + p.y * p.y)
+end
@@ -0,0 +1,7 @@
+obj Point begin
+    x: int
+    x: float
+end
+
+fun main() begin
+end
@@ -0,0 +1,8 @@
+obj Unit begin
+end
+
+fun main() begin
+    let u = Unit()
+    let v = Unit()
+    println("ok")
+end
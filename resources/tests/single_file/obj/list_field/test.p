@@ -0,0 +1,15 @@
+obj Bag begin
+    items: [string]
+    open: bool
+end
+
+fun main() begin
+    let bags = [Bag(["a"], true), Bag([], false)]
+    push(bags[1].items, "b")
+    push(bags[0].items, "c")
+    for b in bags do
+        println(len(b.items))
+        println(b.open)
+    end
+    println(bags[0].items[1])
+end
@@ -0,0 +1,16 @@
+obj Point begin
+    x: float
+    y: float
+end
+
+obj Line begin
+    start: Point
+    end_: Point
+end
+
+fun main() begin
+    let l = Line(Point(0.0, 1.0), Point(2.0, 3.0))
+    set l.end_.x = 5.5
+    println(l.start.y)
+    println(l.end_.x)
+end
@@ -0,0 +1,8 @@
+obj Point begin
+    x: int
+end
+
+fun main() begin
+    let p = Point(1)
+    println(p.y)
+end
@@ -0,0 +1,4 @@
+fun main() begin
+    let xs = [1]
+    println(xs.len)
+end
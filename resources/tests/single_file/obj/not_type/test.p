@@ -0,0 +1,6 @@
+fun f() begin
+end
+
+fun main() begin
+    let x: [f] = []
+end
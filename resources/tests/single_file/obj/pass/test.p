@@ -0,0 +1,16 @@
+obj Account begin
+    owner: string
+    balance: int
+end
+
+fun deposit(a: Account, amount: int) begin
+    set a.balance = a.balance + amount
+end
+
+fun main() begin
+    let a = Account("ann", 10)
+    deposit(a, 5)
+    deposit(a, 7)
+    println(a.owner)
+    println(a.balance)
+end
@@ -0,0 +1,14 @@
+obj Pair begin
+    first: int
+    second: int
+end
+
+fun swap(p: Pair) -> Pair begin
+    return Pair(p.second, p.first)
+end
+
+fun main() -> int begin
+    let p: Pair = swap(Pair(1, 2))
+    println(p.first)
+    return p.second
+end
@@ -0,0 +1,17 @@
+obj Node begin
+    value: int
+    children: [Node]
+end
+
+fun sum(n: Node) -> int begin
+    let total = n.value
+    for c in n.children do
+        set total = total + sum(c)
+    end
+    return total
+end
+
+fun main() begin
+    let root = Node(1, [Node(2, []), Node(3, [Node(4, [])])])
+    println(sum(root))
+end
@@ -0,0 +1,14 @@
+obj Counter begin
+    name: string
+    value: int
+end
+
+fun main() begin
+    let c = Counter("clicks", 0)
+    for i in 0..5 do
+        set c.value = c.value + i
+    end
+    set c.name = c.name + "!"
+    println(c.name)
+    println(c.value)
+end
@@ -0,0 +1,5 @@
+fun f(p: Point) begin
+end
+
+fun main() begin
+end
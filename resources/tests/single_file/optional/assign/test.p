@@ -0,0 +1,6 @@
+fun main() begin
+    let x: int? = 3
+    if x is some then
+        set x = 4
+    end
+end
@@ -0,0 +1,27 @@
+fun find(xs: [int], target: int) -> int? begin
+    for i in 0..len(xs) do
+        if xs[i] == target then
+            return i
+        end
+    end
+
+    return none
+end
+
+fun main() begin
+    let xs = [4, 8, 15]
+    let i = find(xs, 8)
+    if i is some then
+        println(i)
+    end
+
+    if find(xs, 3) is none then
+        println("missing")
+    end
+
+    let name: string? = none
+    set name = "pine"
+    if name is some then
+        println(name)
+    end
+end
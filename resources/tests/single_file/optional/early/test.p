@@ -0,0 +1,35 @@
+fun shout(s: string?) -> string begin
+    if s is none then
+        return "nothing"
+    end
+
+    return s + "!"
+end
+
+fun index_of(xs: [int], target: int) -> int? begin
+    for i in 0..len(xs) do
+        if xs[i] == target then
+            return i
+        end
+    end
+
+    return none
+end
+
+fun main() begin
+    println(shout(none))
+    println(shout("hi"))
+
+    let xs = [3, 1, 4, 1, 5]
+    let total = 0
+    for target in [4, 5, 9, 3] do
+        let i = index_of(xs, target)
+        if i is none then
+            break
+        end
+
+        set total += i
+    end
+
+    println(total)
+end
@@ -0,0 +1,29 @@
+obj Node begin
+    value: int
+    next: Node?
+end
+
+fun length(node: Node) -> int begin
+    let next = node.next
+    if next is none then
+        return 1
+    end
+
+    return 1 + length(next)
+end
+
+fun main() begin
+    let list = Node(1, Node(2, Node(3, none)))
+    println(length(list))
+
+    let next = list.next
+    if next is some then
+        println(next.value)
+        set next.value = 20
+    end
+
+    let second = list.next
+    if second is some then
+        println(second.value)
+    end
+end
@@ -0,0 +1,6 @@
+fun main() begin
+    let x: int? = 3
+    if x is some then
+        let f = fun() => x + 1
+    end
+end
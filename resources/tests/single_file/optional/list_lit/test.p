@@ -0,0 +1,19 @@
+fun total(xs: [int?]) -> int begin
+    let sum = 0
+    for x in xs do
+        if x is some then
+            set sum = sum + x
+        end
+    end
+    return sum
+end
+
+fun main() begin
+    let xs: [int?] = [3]
+    push(xs, none)
+    push(xs, 4)
+    println(total(xs))
+    println(total([1, 2]))
+    let grid: [[int?]] = [[5], []]
+    println(total(grid[0]))
+end
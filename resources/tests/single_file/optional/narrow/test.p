@@ -0,0 +1,30 @@
+fun add(a: int?, b: int?) -> int begin
+    if a is some and b is some then
+        return a + b
+    elif a is some then
+        return a
+    elif b is none then
+        return 0
+    else
+        return b * 10
+    end
+end
+
+fun small(a: int?) -> bool begin
+    return a is none or a < 3
+end
+
+fun main() begin
+    println(add(1, 2))
+    println(add(5, none))
+    println(add(none, none))
+    println(add(none, 7))
+    println(small(none))
+    println(small(2))
+    println(small(9))
+
+    let x: int? = 4
+    if not (x is none) then
+        println(x)
+    end
+end
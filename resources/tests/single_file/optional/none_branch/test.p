@@ -0,0 +1,6 @@
+fun main() begin
+    let x: int? = none
+    if x is none then
+        println(x + 1)
+    end
+end
@@ -0,0 +1,6 @@
+fun main() begin
+    let x = 3
+    if x is some then
+        println(x)
+    end
+end
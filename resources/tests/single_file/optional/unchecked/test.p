@@ -0,0 +1,4 @@
+fun main() begin
+    let x: int? = 3
+    println(x + 1)
+end
@@ -0,0 +1,8 @@
+fun square(n: int) -> int begin
+    return n * n
+end
+
+fun main() begin
+    let x: int? = none
+    println(square(x))
+end
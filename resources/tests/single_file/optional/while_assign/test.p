@@ -0,0 +1,7 @@
+fun main() begin
+    let x: int? = 3
+    while x is some do
+        set x = none
+        println(x + 1)
+    end
+end
@@ -0,0 +1,22 @@
+obj Node begin
+    value: int
+    next: Node?
+end
+
+fun main() begin
+    let list: Node? = Node(1, Node(2, Node(3, none)))
+    let node = list
+    let total = 0
+    while node is some do
+        set total = total + node.value
+        set node = node.next
+    end
+    println(total)
+
+    let x: int? = 5
+    while x is some and x > 0 do
+        print(x)
+        set x = x - 2
+    end
+    println("")
+end
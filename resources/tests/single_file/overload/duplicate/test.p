@@ -0,0 +1,18 @@
+obj Money begin
+    cents: int
+end
+
+impl Money begin
+    fun neg(self: Money) -> Money begin
+        return Money(-self.cents)
+    end
+end
+
+impl Money begin
+    fun neg(self: Money) -> Money begin
+        return Money(0)
+    end
+end
+
+fun main() begin
+end
@@ -0,0 +1,34 @@
+obj Money begin
+    cents: int
+end
+
+impl Money begin
+    fun add(self: Money, other: Money) -> Money begin
+        return Money(self.cents + other.cents)
+    end
+
+    fun lt(self: Money, other: Money) -> bool begin
+        return self.cents < other.cents
+    end
+end
+
+fun max<T>(a: T, b: T) -> T begin
+    if a > b then
+        return a
+    end
+    return b
+end
+
+fun sum<T>(xs: [T], zero: T) -> T begin
+    let total = zero
+    for x in xs do
+        set total = total + x
+    end
+    return total
+end
+
+fun main() begin
+    println(max(Money(150), Money(99)).cents)
+    println(sum([Money(1), Money(2), Money(3)], Money(0)).cents)
+    println(max(2, 7))
+end
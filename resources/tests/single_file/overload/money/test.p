@@ -0,0 +1,25 @@
+obj Money begin
+    cents: int
+end
+
+impl Money begin
+    fun add(self: Money, other: Money) -> Money begin
+        return Money(self.cents + other.cents)
+    end
+
+    fun mul(self: Money, by: int) -> Money begin
+        return Money(self.cents * by)
+    end
+
+    fun eq(self: Money, other: Money) -> bool begin
+        return self.cents == other.cents
+    end
+end
+
+fun main() begin
+    let price = Money(250)
+    let total = price * 3 + Money(50)
+    println(total.cents)
+    println(total == Money(800))
+    println(total != Money(800))
+end
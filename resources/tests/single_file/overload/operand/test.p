@@ -0,0 +1,13 @@
+obj Money begin
+    cents: int
+end
+
+impl Money begin
+    fun mul(self: Money, by: int) -> Money begin
+        return Money(self.cents * by)
+    end
+end
+
+fun main() begin
+    let m = Money(1) * Money(2)
+end
@@ -0,0 +1,12 @@
+obj Money begin
+    cents: int
+end
+
+impl Money begin
+    fun add(a: int, b: int) -> int begin
+        return a + b
+    end
+end
+
+fun main() begin
+end
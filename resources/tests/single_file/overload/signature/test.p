@@ -0,0 +1,12 @@
+obj Money begin
+    cents: int
+end
+
+impl Money begin
+    fun eq(self: Money, other: int) -> bool begin
+        return self.cents == other
+    end
+end
+
+fun main() begin
+end
@@ -0,0 +1,13 @@
+obj Money begin
+    cents: int
+end
+
+impl Money begin
+    fun add(self: Money, other: Money) -> Money begin
+        return Money(self.cents + other.cents)
+    end
+end
+
+fun main() begin
+    let m = Money(1) - Money(2)
+end
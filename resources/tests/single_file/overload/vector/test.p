@@ -0,0 +1,42 @@
+obj Vec begin
+    x: float
+    y: float
+end
+
+impl Vec begin
+    fun add(self: Vec, other: Vec) -> Vec begin
+        return Vec(self.x + other.x, self.y + other.y)
+    end
+
+    fun mul(self: Vec, by: float) -> Vec begin
+        return Vec(self.x * by, self.y * by)
+    end
+
+    fun neg(self: Vec) -> Vec begin
+        return Vec(-self.x, -self.y)
+    end
+
+    fun eq(self: Vec, other: Vec) -> bool begin
+        return self.x == other.x and self.y == other.y
+    end
+
+    fun lt(self: Vec, other: Vec) -> bool begin
+        return self.x * self.x + self.y * self.y < other.x * other.x + other.y * other.y
+    end
+end
+
+fun main() begin
+    let a = Vec(1.0, 2.0)
+    let b = Vec(0.5, 0.5)
+    let c = a + b * 2.0
+    println(c.x)
+    println(c.y)
+    let d = -c
+    println(d.x)
+    println(a == b)
+    println(a != b)
+    println(a < b)
+    println(a > b)
+    println(a <= b)
+    println(a >= b)
+end
@@ -0,0 +1,4 @@
+fun main() begin
+    let b: byte = 1
+    set b = b + 256
+end
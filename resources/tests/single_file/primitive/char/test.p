@@ -0,0 +1,6 @@
+fun main() -> int begin
+    let c = 'a'
+    let n = 'z'
+    let d = (c as int + 3) as char
+    return 0
+end
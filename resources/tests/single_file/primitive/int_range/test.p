@@ -0,0 +1,4 @@
+fun main() begin
+    let x: uint = 9223372036854775808
+    let y: int = 9223372036854775808
+end
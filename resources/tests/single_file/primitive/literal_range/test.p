@@ -0,0 +1,3 @@
+fun main() begin
+    let x: uint = 18446744073709551616
+end
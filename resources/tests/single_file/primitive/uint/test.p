@@ -0,0 +1,5 @@
+fun main() -> int begin
+    let x = 1 as uint
+    let y = x as byte
+    return 0
+end
@@ -0,0 +1,9 @@
+fun main() begin
+    let x: uint = 18446744073709551615
+    println(x)
+    let y = 18446744073709551615
+    println(y == x)
+    let z: int = 9223372036854775807
+    println(z)
+    println(-9223372036854775807 - 1)
+end
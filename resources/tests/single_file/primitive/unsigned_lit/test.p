@@ -0,0 +1,19 @@
+fun half(x: uint) -> uint begin
+    return x / 2
+end
+
+fun main() begin
+    let x: uint = 10
+    let b: byte = 255
+    set b = b + 1
+    println(half(x) + 1)
+    println(b)
+    let bs: [byte] = [1, 2, 200]
+    push(bs, 100)
+    println(bs[2] + bs[3])
+    if x > 3 then
+        println(x * 3)
+    end
+    set x += 5
+    println(x)
+end
@@ -0,0 +1,6 @@
+fun main() begin
+    println(true)
+    println(1 > 2)
+    print(not false)
+    println()
+end
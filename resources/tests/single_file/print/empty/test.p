@@ -0,0 +1,5 @@
+fun main() begin
+    println("")
+    println()
+    println("x")
+end
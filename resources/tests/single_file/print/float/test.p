@@ -0,0 +1,4 @@
+fun main() begin
+    println(1.5)
+    println(0.25 + 0.5)
+end
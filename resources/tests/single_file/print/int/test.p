@@ -0,0 +1,5 @@
+fun main() begin
+    print(1)
+    print(-2)
+    println(3 * 4)
+end
@@ -0,0 +1,6 @@
+fun f(xs: [int]) begin
+    print(xs)
+end
+
+fun main() begin
+end
@@ -0,0 +1,6 @@
+fun main() -> int begin
+    let x = 7
+    print("x = ")
+    println(x)
+    return x
+end
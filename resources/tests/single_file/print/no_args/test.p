@@ -0,0 +1,3 @@
+fun main() begin
+    print()
+end
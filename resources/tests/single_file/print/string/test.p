@@ -0,0 +1,9 @@
+fun greet(name: string) -> string begin
+    return "hello, " + name
+end
+
+fun main() begin
+    print("pine")
+    println()
+    println(greet("world"))
+end
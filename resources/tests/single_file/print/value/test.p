@@ -0,0 +1,3 @@
+fun main() begin
+    let p = println
+end
@@ -0,0 +1,30 @@
+fun check(n: int) -> Result<int, string> begin
+    if n < 0 then
+        return Err("negative")
+    end
+
+    return Ok(n)
+end
+
+fun main() begin
+    let wrap = Ok
+    match wrap("wrapped") begin
+        case Ok(v) then println(v)
+        case Err(e) then println(e)
+    end
+
+    let double = fun(n: int) -> Result<int, string> begin
+        let m = check(n)?
+        return Ok(m * 2)
+    end
+
+    match double(-1) begin
+        case Ok(v) then println(v)
+        case Err(e) then println(e)
+    end
+
+    match double(21) begin
+        case Ok(v) then println(v)
+        case Err(e) then println(e)
+    end
+end
@@ -0,0 +1,12 @@
+fun half(n: int) -> Result<int, string> begin
+    if n % 2 == 1 then
+        return Err("odd")
+    end
+
+    return Ok(n / 2)
+end
+
+fun quarter(n: int) -> Result<int, int> begin
+    let h = half(n)?
+    return half(h)
+end
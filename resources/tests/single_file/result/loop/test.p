@@ -0,0 +1,28 @@
+fun check(n: int) -> Result<int, string> begin
+    if n < 0 then
+        return Err("negative")
+    end
+
+    return Ok(n)
+end
+
+fun sum(xs: [int]) begin
+    let total = 0
+    for x in xs do
+        set total += check(x)?
+    end
+
+    return Ok(total)
+end
+
+fun show(r: Result<int, string>) begin
+    match r begin
+        case Ok(v) then println(v)
+        case Err(e) then println(e)
+    end
+end
+
+fun main() begin
+    show(sum([1, -2]))
+    show(sum([1, 2]))
+end
@@ -0,0 +1,14 @@
+fun check(n: int) -> Result<int, string> begin
+    if n < 0 then
+        return Err("negative")
+    end
+
+    return Ok(n)
+end
+
+fun main() begin
+    match check(1) begin
+        case Ok(v) then
+            println(v)
+    end
+end
@@ -0,0 +1,7 @@
+fun twice(n: int) -> Result<int, string> begin
+    let m = n?
+    return Ok(m * 2)
+end
+
+fun main() begin
+end
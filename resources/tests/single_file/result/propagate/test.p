@@ -0,0 +1,30 @@
+fun parse_digit(s: string) -> Result<int, string> begin
+    if s == "0" then
+        return Ok(0)
+    elif s == "1" then
+        return Ok(1)
+    end
+
+    return Err("not a digit: " + s)
+end
+
+fun add_digits(a: string, b: string) -> Result<int, string> begin
+    let x = parse_digit(a)?
+    let y = parse_digit(b)?
+    return Ok(x + y)
+end
+
+fun show(r: Result<int, string>) begin
+    match r begin
+        case Ok(v) then
+            println(v)
+        case Err(e) then
+            println(e)
+    end
+end
+
+fun main() begin
+    show(add_digits("1", "1"))
+    show(add_digits("1", "x"))
+    show(add_digits("y", "0"))
+end
@@ -0,0 +1,10 @@
+fun half(n: int) -> Result<int, string> begin
+    return Ok(n / 2)
+end
+
+fun quarter(n: int) -> int begin
+    return half(n)? / 2
+end
+
+fun main() begin
+end
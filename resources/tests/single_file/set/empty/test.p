@@ -0,0 +1,6 @@
+fun main() begin
+    let s = {}
+    insert(s, true)
+    println(contains(s, true))
+    println(contains(s, false))
+end
@@ -0,0 +1,8 @@
+fun main() begin
+    let s = {4, 2, 6}
+    let total = 0
+    for x in s do
+        set total = total + x
+    end
+    println(total)
+end
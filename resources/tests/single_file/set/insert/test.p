@@ -0,0 +1,10 @@
+fun main() begin
+    let seen: Set<string> = {}
+    for w in ["to", "be", "or", "not", "to", "be"] do
+        insert(seen, w)
+    end
+    remove(seen, "or")
+    println(len(seen))
+    println(contains(seen, "not"))
+    println(contains(seen, "or"))
+end
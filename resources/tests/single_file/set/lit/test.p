@@ -0,0 +1,6 @@
+fun main() begin
+    let primes = {2, 3, 5, 7, 3}
+    println(len(primes))
+    println(contains(primes, 5))
+    println(contains(primes, 4))
+end
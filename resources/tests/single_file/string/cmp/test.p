@@ -0,0 +1,8 @@
+fun main() begin
+    println("abc" < "abd")
+    println("ab" < "abc")
+    println("b" > "abc")
+    println("abc" <= "abc")
+    println("abc" >= "abb")
+    println("abc" > "abc")
+end
@@ -0,0 +1,6 @@
+fun main() begin
+    let s = "ab" + "c"
+    let t = "" + s + ""
+    println(t)
+    println(t == "abc")
+end
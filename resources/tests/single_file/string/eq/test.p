@@ -0,0 +1,7 @@
+fun main() begin
+    let s = "abc"
+    println(s == "abc")
+    println(s != "abd")
+    println(s == "ab")
+    println("" == "")
+end
@@ -0,0 +1,5 @@
+fun main() begin
+    let s = "a\tb\n\"c\"\\"
+    println(s == "a" + "\t" + "b" + "\n" + "\"" + "c" + "\"" + "\\")
+    println(s)
+end
@@ -0,0 +1,4 @@
+fun main() begin
+    let s = "hello world"
+    println(s)
+end
@@ -0,0 +1,4 @@
+fun main() -> int begin
+    let s = "ab" - "b"
+    return 0
+end
@@ -0,0 +1,4 @@
+fun main() -> int begin
+    let s = "ab
+    return 0
+end
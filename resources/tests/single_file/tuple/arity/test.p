@@ -0,0 +1,4 @@
+fun main() begin
+    let (a, b, c) = (1, 2)
+    println(a)
+end
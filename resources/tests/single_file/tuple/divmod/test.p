@@ -0,0 +1,9 @@
+fun divmod(a: int, b: int) -> (int, int) begin
+    return (a / b, a % b)
+end
+
+fun main() begin
+    let (q, r) = divmod(17, 5)
+    println(q)
+    println(r)
+end
@@ -0,0 +1,15 @@
+fun pair<A, B>(a: A, b: B) -> (A, B) begin
+    return (a, b)
+end
+
+fun first<A, B>(p: (A, B)) -> A begin
+    let (a, _) = p
+    return a
+end
+
+fun main() begin
+    let (x, y) = pair(1, 2.5)
+    println(x)
+    println(y)
+    println(first(pair("hello", true)))
+end
@@ -0,0 +1,11 @@
+fun main() begin
+    let pairs = [("a", 1), ("b", 2), ("c", 3)]
+    let total = 0
+    for p in pairs do
+        let (name, n) = p
+        print(name)
+        set total = total + n
+    end
+    println("")
+    println(total)
+end
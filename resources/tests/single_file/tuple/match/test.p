@@ -0,0 +1,15 @@
+fun classify(p: (bool, bool)) -> string begin
+    return match p begin
+        case (true, true) => "both"
+        case (true, false) => "first"
+        case (false, true) => "second"
+        case (false, false) => "neither"
+    end
+end
+
+fun main() begin
+    println(classify((true, true)))
+    println(classify((true, false)))
+    println(classify((false, true)))
+    println(classify((false, false)))
+end
@@ -0,0 +1,20 @@
+fun bounds(xs: [int]) -> ((int, int), int) begin
+    let lo = xs[0]
+    let hi = xs[0]
+    for x in xs do
+        if x < lo then
+            set lo = x
+        end
+        if x > hi then
+            set hi = x
+        end
+    end
+    return ((lo, hi), len(xs))
+end
+
+fun main() begin
+    let ((lo, hi), n) = bounds([4, 9, 1, 7])
+    println(lo)
+    println(hi)
+    println(n)
+end
@@ -0,0 +1,4 @@
+fun main() begin
+    let (a, true) = (1, false)
+    println(a)
+end
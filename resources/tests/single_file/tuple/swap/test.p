@@ -0,0 +1,12 @@
+fun swap(p: (int, string)) -> (string, int) begin
+    let (a, b) = p
+    return (b, a)
+end
+
+fun main() begin
+    let p = (1, "one")
+    let s = swap(p)
+    let (name, value): (string, int) = s
+    println(name)
+    println(value)
+end
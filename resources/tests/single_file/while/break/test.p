@@ -0,0 +1,11 @@
+fun main() begin
+    let xs = [4, 8, 15, 16, 23, 42]
+    let i = 0
+    while true do
+        if xs[i] > 10 then
+            break
+        end
+        set i = i + 1
+    end
+    println(i)
+end
@@ -0,0 +1,8 @@
+fun main() begin
+    while true do
+        let f = fun() begin
+            continue
+        end
+        f()
+    end
+end
@@ -0,0 +1,6 @@
+fun main() begin
+    let x = 1
+    if x == 1 then
+        break
+    end
+end
@@ -0,0 +1,17 @@
+fun find(xs: [int], x: int) -> int begin
+    let i = 0
+    while true do
+        if i == len(xs) then
+            break
+        end
+        if xs[i] == x then
+            return i
+        end
+        set i = i + 1
+        return -1
+    end
+end
+
+fun main() begin
+    println(find([1, 2], 2))
+end
@@ -0,0 +1,12 @@
+fun main() begin
+    let i = 0
+    let odd = 0
+    while i < 10 do
+        set i = i + 1
+        if i % 2 == 0 then
+            continue
+        end
+        set odd = odd + i
+    end
+    println(odd)
+end
@@ -1,7 +1,7 @@
 fun main() -> int begin
-    let x = 0
+    let x = 2
     while x < 1000 do
-        set x = x ** x
+        set x = x ** 2
     end
     return x
 end
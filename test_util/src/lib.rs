@@ -2,13 +2,15 @@ use std::fs;
 use std::path::PathBuf;
 
 extern crate test_util_proc_macros;
-pub use test_util_proc_macros::{generate_pvm_tests, generate_single_file_tests};
+pub use test_util_proc_macros::{generate_module_tests, generate_pvm_tests, generate_single_file_tests};
 
 pub struct TestFileContent {
     pub pine_content: Option<String>,
     pub tok_ref_content: Option<String>,
     pub pvm_content: Option<String>,
     pub pvm_ref_content: Option<String>,
+    pub err_ref_content: Option<String>,
+    pub runtime_err_ref_content: Option<String>,
     pub test_input_file: Option<fs::File>,
 }
 
@@ -18,6 +20,8 @@ impl TestFileContent {
         tok_ref_content: Option<String>,
         pvm_content: Option<String>,
         pvm_ref_content: Option<String>,
+        err_ref_content: Option<String>,
+        runtime_err_ref_content: Option<String>,
         test_input_file: Option<fs::File>,
     ) -> Self {
         Self {
@@ -25,6 +29,8 @@ impl TestFileContent {
             tok_ref_content,
             pvm_content,
             pvm_ref_content,
+            err_ref_content,
+            runtime_err_ref_content,
             test_input_file
         }
     }
@@ -59,6 +65,14 @@ pub fn test_file_content(test_base_path: &mut PathBuf) -> TestFileContent {
         Err(_) => None,
     };
     
+    // read the error ref if exists
+    test_base_path.set_extension("err");
+    let err_ref_content = fs::read_to_string(test_base_path.as_path()).ok();
+
+    // read the runtime error ref if exists
+    test_base_path.set_extension("rterr");
+    let runtime_err_ref_content = fs::read_to_string(test_base_path.as_path()).ok();
+
     // read the pvm ref if exists
     test_base_path.set_extension("pvm.ref");
     let pvm_ref_content = match fs::read_to_string(test_base_path.as_path()) {
@@ -71,6 +85,8 @@ pub fn test_file_content(test_base_path: &mut PathBuf) -> TestFileContent {
         token_ref_content,
         pvm_content,
         pvm_ref_content,
+        err_ref_content,
+        runtime_err_ref_content,
         test_input_file
     )
 }
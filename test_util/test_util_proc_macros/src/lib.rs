@@ -11,6 +11,12 @@ pub fn generate_single_file_tests(input: TokenStream) -> TokenStream {
     create_output(&name, "single_file")
 }
 
+#[proc_macro]
+pub fn generate_module_tests(input: TokenStream) -> TokenStream {
+    let name = input.to_string();
+    create_output(&name, "module")
+}
+
 #[proc_macro]
 pub fn generate_pvm_tests(input: TokenStream) -> TokenStream {
     let name = input.to_string();